@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS routing_rules (
+    rule_id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    priority INT NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    payment_method TEXT NULL,
+    merchant_id TEXT NULL,
+    issuing_bank TEXT NULL,
+    min_amount_minor BIGINT NULL,
+    max_amount_minor BIGINT NULL,
+    amount_bucket TEXT NULL,
+    action TEXT NOT NULL CHECK (action IN ('FORCE', 'EXCLUDE', 'BOOST')),
+    gateway_id TEXT NOT NULL,
+    boost DOUBLE PRECISION NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_routing_rules_enabled_priority ON routing_rules(enabled, priority);
+
+ALTER TABLE routing_decisions ADD COLUMN IF NOT EXISTS matched_rule_id UUID NULL;
+ALTER TABLE routing_decisions ADD COLUMN IF NOT EXISTS matched_rule_ids JSONB NOT NULL DEFAULT '[]'::jsonb;
@@ -0,0 +1,36 @@
+CREATE TABLE IF NOT EXISTS gateway_fee_schedule (
+    gateway_id TEXT NOT NULL,
+    payment_method TEXT NOT NULL,
+    card_network TEXT NOT NULL DEFAULT 'ANY',
+    min_amount_minor BIGINT NOT NULL DEFAULT 0,
+    max_amount_minor BIGINT NULL,
+    pct_fee DOUBLE PRECISION NOT NULL,
+    fixed_fee_minor BIGINT NOT NULL DEFAULT 0,
+    gst_pct DOUBLE PRECISION NOT NULL DEFAULT 18.0,
+    PRIMARY KEY (gateway_id, payment_method, card_network, min_amount_minor)
+);
+
+ALTER TABLE scoring_config ADD COLUMN IF NOT EXISTS cost_weight DOUBLE PRECISION NOT NULL DEFAULT 0.0;
+
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS card_network TEXT NULL;
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS expected_fee_minor BIGINT NULL;
+
+INSERT INTO gateway_fee_schedule (gateway_id, payment_method, card_network, min_amount_minor, max_amount_minor, pct_fee, fixed_fee_minor) VALUES
+('razorpay_real', 'UPI', 'ANY', 0, 200000, 0.0, 0),
+('razorpay_real', 'UPI', 'ANY', 200001, NULL, 0.3, 0),
+('razorpay_real', 'CARD', 'ANY', 0, NULL, 2.0, 0),
+('razorpay_real', 'CARD', 'RUPAY', 0, 200000, 0.0, 0),
+('razorpay_real', 'CARD', 'AMEX', 0, NULL, 3.0, 0),
+('razorpay_real', 'NETBANKING', 'ANY', 0, NULL, 1.9, 0),
+('hdfc_mock', 'UPI', 'ANY', 0, NULL, 0.2, 0),
+('hdfc_mock', 'CARD', 'ANY', 0, NULL, 1.8, 0),
+('hdfc_mock', 'CARD', 'AMEX', 0, NULL, 2.7, 0),
+('hdfc_mock', 'NETBANKING', 'ANY', 0, NULL, 0.0, 1500),
+('icici_mock', 'UPI', 'ANY', 0, NULL, 0.25, 0),
+('icici_mock', 'CARD', 'ANY', 0, 1000000, 1.75, 0),
+('icici_mock', 'CARD', 'ANY', 1000001, NULL, 1.5, 0),
+('icici_mock', 'NETBANKING', 'ANY', 0, NULL, 1.5, 0),
+('axis_mock', 'UPI', 'ANY', 0, NULL, 0.2, 0),
+('axis_mock', 'CARD', 'ANY', 0, NULL, 1.9, 0),
+('axis_mock', 'NETBANKING', 'ANY', 0, NULL, 0.0, 1800)
+ON CONFLICT (gateway_id, payment_method, card_network, min_amount_minor) DO NOTHING;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS gateway_volume_targets (
+    gateway_id TEXT PRIMARY KEY,
+    monthly_commit_minor BIGINT NULL,
+    daily_cap_minor BIGINT NULL,
+    monthly_cap_minor BIGINT NULL,
+    pace_boost DOUBLE PRECISION NOT NULL DEFAULT 0.1,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payments_gateway_created_at ON payments(gateway_used, created_at);
@@ -0,0 +1,8 @@
+ALTER TABLE gateways_config ADD COLUMN IF NOT EXISTS max_in_flight INT NULL;
+ALTER TABLE gateways_config ADD COLUMN IF NOT EXISTS max_tps INT NULL;
+
+UPDATE gateways_config SET max_in_flight = 200, max_tps = 300
+WHERE gateway_id = 'razorpay_real' AND max_in_flight IS NULL AND max_tps IS NULL;
+
+UPDATE gateways_config SET max_in_flight = 100, max_tps = 200
+WHERE gateway_id IN ('hdfc_mock', 'icici_mock', 'axis_mock') AND max_in_flight IS NULL AND max_tps IS NULL;
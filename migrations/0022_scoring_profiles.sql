@@ -0,0 +1,57 @@
+CREATE TABLE IF NOT EXISTS scoring_profiles (
+    profile_id TEXT PRIMARY KEY,
+    description TEXT NOT NULL DEFAULT '',
+    current_version INT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS scoring_profile_versions (
+    profile_id TEXT NOT NULL REFERENCES scoring_profiles(profile_id),
+    version INT NOT NULL,
+    weights JSONB NOT NULL,
+    author TEXT NOT NULL,
+    change_note TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (profile_id, version)
+);
+
+CREATE TABLE IF NOT EXISTS scoring_profile_assignments (
+    assignment_id UUID PRIMARY KEY,
+    merchant_id TEXT NULL,
+    payment_method TEXT NULL,
+    amount_bucket TEXT NULL,
+    profile_id TEXT NOT NULL REFERENCES scoring_profiles(profile_id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_scoring_profile_assignments_scope ON scoring_profile_assignments (
+    COALESCE(merchant_id, ''),
+    COALESCE(payment_method, ''),
+    COALESCE(amount_bucket, '')
+);
+
+ALTER TABLE routing_decisions ADD COLUMN IF NOT EXISTS scoring_profile_id TEXT NULL;
+ALTER TABLE routing_decisions ADD COLUMN IF NOT EXISTS scoring_profile_version INT NULL;
+
+INSERT INTO scoring_profiles (profile_id, description, current_version) VALUES
+('default', 'Platform-wide default weights', 1),
+('latency_sensitive', 'Merchants that value checkout speed over marginal success rate', 1)
+ON CONFLICT (profile_id) DO NOTHING;
+
+INSERT INTO scoring_profile_versions (profile_id, version, weights, author, change_note)
+SELECT 'default', 1, jsonb_build_object(
+    'success_rate_weight', success_rate_weight,
+    'latency_weight', latency_weight,
+    'method_affinity_weight', method_affinity_weight,
+    'bank_affinity_weight', bank_affinity_weight,
+    'amount_fit_weight', amount_fit_weight,
+    'time_weight', time_weight,
+    'cost_weight', cost_weight
+), 'migration', 'seeded from scoring_config'
+FROM scoring_config WHERE config_id = 'default'
+ON CONFLICT (profile_id, version) DO NOTHING;
+
+INSERT INTO scoring_profile_versions (profile_id, version, weights, author, change_note) VALUES
+('latency_sensitive', 1, '{"success_rate_weight": 0.25, "latency_weight": 0.40, "method_affinity_weight": 0.12, "bank_affinity_weight": 0.10, "amount_fit_weight": 0.08, "time_weight": 0.05, "cost_weight": 0.0}', 'migration', NULL)
+ON CONFLICT (profile_id, version) DO NOTHING;
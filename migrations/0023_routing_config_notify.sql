@@ -0,0 +1,37 @@
+CREATE OR REPLACE FUNCTION notify_routing_config_changed() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('routing_config_changed', TG_TABLE_NAME);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY[
+        'gateways_config',
+        'scoring_config',
+        'scoring_profiles',
+        'scoring_profile_versions',
+        'scoring_profile_assignments',
+        'gateway_method_affinity',
+        'gateway_amount_fit',
+        'gateway_time_penalty',
+        'bin_bank_map',
+        'gateway_fee_schedule',
+        'routing_rules',
+        'gateway_volume_targets',
+        'retry_policy',
+        'gateway_error_classification',
+        'circuit_breaker_config',
+        'bandit_policy'
+    ] LOOP
+        EXECUTE format('DROP TRIGGER IF EXISTS routing_config_notify ON %I', t);
+        EXECUTE format(
+            'CREATE TRIGGER routing_config_notify AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON %I FOR EACH STATEMENT EXECUTE FUNCTION notify_routing_config_changed()',
+            t
+        );
+    END LOOP;
+END;
+$$;
@@ -0,0 +1,5 @@
+ALTER TABLE scoring_profile_versions ADD COLUMN IF NOT EXISTS signal_blend JSONB NULL;
+
+UPDATE scoring_profile_versions
+SET signal_blend = '{"windows": [{"window_minutes": 1, "weight": 0.35}, {"window_minutes": 5, "weight": 0.45}, {"window_minutes": 15, "weight": 0.15}, {"window_minutes": 60, "weight": 0.05}]}'
+WHERE profile_id = 'latency_sensitive' AND version = 1 AND signal_blend IS NULL;
@@ -0,0 +1,69 @@
+CREATE TABLE IF NOT EXISTS issuers (
+    issuer_id TEXT PRIMARY KEY,
+    display_name TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS issuer_aliases (
+    alias_type TEXT NOT NULL CHECK (alias_type IN ('UPI_HANDLE', 'NETBANKING_CODE', 'BIN_BANK')),
+    alias TEXT NOT NULL,
+    issuer_id TEXT NOT NULL REFERENCES issuers(issuer_id),
+    PRIMARY KEY (alias_type, alias)
+);
+
+CREATE TABLE IF NOT EXISTS gateway_issuer_affinity (
+    gateway_id TEXT NOT NULL,
+    issuer_id TEXT NOT NULL REFERENCES issuers(issuer_id),
+    score DOUBLE PRECISION NOT NULL CHECK (score >= 0 AND score <= 1),
+    PRIMARY KEY (gateway_id, issuer_id)
+);
+
+INSERT INTO issuers (issuer_id, display_name) VALUES
+('HDFC', 'HDFC Bank'),
+('ICICI', 'ICICI Bank'),
+('AXIS', 'Axis Bank'),
+('SBI', 'State Bank of India'),
+('KOTAK', 'Kotak Mahindra Bank'),
+('YES', 'Yes Bank')
+ON CONFLICT (issuer_id) DO NOTHING;
+
+INSERT INTO issuer_aliases (alias_type, alias, issuer_id) VALUES
+('UPI_HANDLE', 'OKHDFCBANK', 'HDFC'),
+('UPI_HANDLE', 'HDFCBANK', 'HDFC'),
+('UPI_HANDLE', 'OKICICI', 'ICICI'),
+('UPI_HANDLE', 'ICICI', 'ICICI'),
+('UPI_HANDLE', 'OKAXIS', 'AXIS'),
+('UPI_HANDLE', 'AXISBANK', 'AXIS'),
+('UPI_HANDLE', 'OKSBI', 'SBI'),
+('UPI_HANDLE', 'SBI', 'SBI'),
+('UPI_HANDLE', 'KOTAK', 'KOTAK'),
+('UPI_HANDLE', 'YBL', 'YES'),
+('NETBANKING_CODE', 'HDFC', 'HDFC'),
+('NETBANKING_CODE', 'ICIC', 'ICICI'),
+('NETBANKING_CODE', 'UTIB', 'AXIS'),
+('NETBANKING_CODE', 'SBIN', 'SBI'),
+('NETBANKING_CODE', 'KKBK', 'KOTAK'),
+('NETBANKING_CODE', 'YESB', 'YES'),
+('BIN_BANK', 'HDFC', 'HDFC'),
+('BIN_BANK', 'ICICI', 'ICICI'),
+('BIN_BANK', 'AXIS', 'AXIS')
+ON CONFLICT (alias_type, alias) DO NOTHING;
+
+INSERT INTO gateway_issuer_affinity (gateway_id, issuer_id, score) VALUES
+('hdfc_mock', 'HDFC', 1.0),
+('icici_mock', 'ICICI', 1.0),
+('axis_mock', 'AXIS', 1.0)
+ON CONFLICT (gateway_id, issuer_id) DO NOTHING;
+
+DO $$
+DECLARE
+    t TEXT;
+BEGIN
+    FOREACH t IN ARRAY ARRAY['issuer_aliases', 'gateway_issuer_affinity'] LOOP
+        EXECUTE format('DROP TRIGGER IF EXISTS routing_config_notify ON %I', t);
+        EXECUTE format(
+            'CREATE TRIGGER routing_config_notify AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON %I FOR EACH STATEMENT EXECUTE FUNCTION notify_routing_config_changed()',
+            t
+        );
+    END LOOP;
+END;
+$$;
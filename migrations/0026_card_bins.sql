@@ -0,0 +1,40 @@
+CREATE TABLE IF NOT EXISTS card_bins (
+    bin_start TEXT NOT NULL,
+    bin_end TEXT NOT NULL,
+    card_network TEXT NOT NULL,
+    card_type TEXT NOT NULL CHECK (card_type IN ('CREDIT', 'DEBIT', 'PREPAID')),
+    issuer_country TEXT NULL,
+    issuer_id TEXT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (bin_start, bin_end),
+    CHECK (length(bin_start) IN (6, 8) AND length(bin_start) = length(bin_end) AND bin_start <= bin_end)
+);
+
+INSERT INTO card_bins (bin_start, bin_end, card_network, card_type, issuer_country, issuer_id) VALUES
+('411111', '411111', 'VISA', 'CREDIT', 'IN', 'HDFC'),
+('555555', '555555', 'MASTERCARD', 'CREDIT', 'IN', 'ICICI'),
+('444433', '444433', 'VISA', 'DEBIT', 'IN', 'AXIS'),
+('60800000', '60849999', 'RUPAY', 'DEBIT', 'IN', 'SBI'),
+('65210000', '65219999', 'RUPAY', 'PREPAID', 'IN', NULL)
+ON CONFLICT (bin_start, bin_end) DO NOTHING;
+
+ALTER TABLE routing_rules ADD COLUMN IF NOT EXISTS card_network TEXT NULL;
+ALTER TABLE routing_rules ADD COLUMN IF NOT EXISTS card_type TEXT NULL;
+
+ALTER TABLE experiment_filters ADD COLUMN IF NOT EXISTS card_network TEXT NULL;
+ALTER TABLE experiment_filters ADD COLUMN IF NOT EXISTS card_type TEXT NULL;
+
+ALTER TABLE payments ADD COLUMN IF NOT EXISTS card_type TEXT NULL;
+
+ALTER TABLE gateway_fee_schedule ADD COLUMN IF NOT EXISTS card_type TEXT NOT NULL DEFAULT 'ANY';
+ALTER TABLE gateway_fee_schedule DROP CONSTRAINT IF EXISTS gateway_fee_schedule_pkey;
+ALTER TABLE gateway_fee_schedule ADD PRIMARY KEY (gateway_id, payment_method, card_network, card_type, min_amount_minor);
+
+INSERT INTO gateway_fee_schedule (gateway_id, payment_method, card_network, card_type, min_amount_minor, max_amount_minor, pct_fee, fixed_fee_minor) VALUES
+('razorpay_real', 'CARD', 'ANY', 'DEBIT', 0, 200000, 0.4, 0),
+('razorpay_real', 'CARD', 'ANY', 'DEBIT', 200001, NULL, 0.9, 0)
+ON CONFLICT (gateway_id, payment_method, card_network, card_type, min_amount_minor) DO NOTHING;
+
+DROP TRIGGER IF EXISTS routing_config_notify ON card_bins;
+CREATE TRIGGER routing_config_notify AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON card_bins
+FOR EACH STATEMENT EXECUTE FUNCTION notify_routing_config_changed();
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS amount_buckets (
+    currency TEXT NOT NULL,
+    bucket_id TEXT NOT NULL,
+    min_amount_minor BIGINT NOT NULL CHECK (min_amount_minor >= 0),
+    max_amount_minor BIGINT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (currency, bucket_id),
+    CHECK (max_amount_minor IS NULL OR max_amount_minor > min_amount_minor)
+);
+
+INSERT INTO amount_buckets (currency, bucket_id, min_amount_minor, max_amount_minor) VALUES
+('INR', 'lt_500', 0, 50000),
+('INR', '500_2000', 50000, 200000),
+('INR', '2000_10000', 200000, 1000000),
+('INR', 'gt_10000', 1000000, NULL)
+ON CONFLICT (currency, bucket_id) DO NOTHING;
+
+DROP TRIGGER IF EXISTS routing_config_notify ON amount_buckets;
+CREATE TRIGGER routing_config_notify AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON amount_buckets
+FOR EACH STATEMENT EXECUTE FUNCTION notify_routing_config_changed();
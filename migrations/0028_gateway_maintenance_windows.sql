@@ -0,0 +1,25 @@
+CREATE TABLE IF NOT EXISTS gateway_maintenance_windows (
+    window_id UUID PRIMARY KEY,
+    gateway_id TEXT NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    payment_methods TEXT[] NOT NULL DEFAULT '{}',
+    issuers TEXT[] NOT NULL DEFAULT '{}',
+    mode TEXT NOT NULL CHECK (mode IN ('EXCLUDE', 'PENALIZE')),
+    penalty_multiplier DOUBLE PRECISION NOT NULL DEFAULT 0.5 CHECK (penalty_multiplier >= 0 AND penalty_multiplier <= 1),
+    reason TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_gateway_maintenance_windows_ends_at
+    ON gateway_maintenance_windows (ends_at);
+
+ALTER TABLE gateway_time_penalty ADD COLUMN IF NOT EXISTS timezone TEXT NOT NULL DEFAULT 'UTC';
+
+ALTER TABLE routing_decisions ADD COLUMN IF NOT EXISTS dropped_gateways_json JSONB NOT NULL DEFAULT '[]'::jsonb;
+
+DROP TRIGGER IF EXISTS routing_config_notify ON gateway_maintenance_windows;
+CREATE TRIGGER routing_config_notify AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON gateway_maintenance_windows
+FOR EACH STATEMENT EXECUTE FUNCTION notify_routing_config_changed();
@@ -0,0 +1,3 @@
+ALTER TABLE bandit_policy
+    ADD COLUMN IF NOT EXISTS discount_half_life_minutes DOUBLE PRECISION NULL DEFAULT 1440
+    CHECK (discount_half_life_minutes IS NULL OR discount_half_life_minutes > 0);
@@ -0,0 +1,14 @@
+ALTER TABLE bandit_policy
+    ADD COLUMN IF NOT EXISTS algorithm TEXT NOT NULL DEFAULT 'THOMPSON' CHECK (algorithm IN ('THOMPSON', 'LINUCB'));
+ALTER TABLE bandit_policy
+    ADD COLUMN IF NOT EXISTS exploration DOUBLE PRECISION NOT NULL DEFAULT 1.0 CHECK (exploration >= 0);
+
+CREATE TABLE IF NOT EXISTS bandit_linear_models (
+    segment TEXT NOT NULL,
+    gateway_id TEXT NOT NULL,
+    a_inv DOUBLE PRECISION[] NOT NULL,
+    b DOUBLE PRECISION[] NOT NULL,
+    observations BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (segment, gateway_id)
+);
@@ -0,0 +1 @@
+ALTER TABLE bandit_policy ADD COLUMN IF NOT EXISTS reward_config JSONB NULL;
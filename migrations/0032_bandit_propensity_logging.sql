@@ -0,0 +1,8 @@
+ALTER TABLE routing_decisions
+    ADD COLUMN IF NOT EXISTS bandit_propensity DOUBLE PRECISION NULL CHECK (bandit_propensity >= 0 AND bandit_propensity <= 1);
+ALTER TABLE routing_decisions
+    ADD COLUMN IF NOT EXISTS bandit_selection_json JSONB NULL;
+
+CREATE INDEX IF NOT EXISTS idx_routing_decisions_bandit_created_at
+    ON routing_decisions (created_at)
+    WHERE bandit_selection_json IS NOT NULL;
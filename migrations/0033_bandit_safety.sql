@@ -0,0 +1,6 @@
+ALTER TABLE bandit_policy
+    ADD COLUMN IF NOT EXISTS max_exploration_share DOUBLE PRECISION NULL CHECK (max_exploration_share >= 0 AND max_exploration_share <= 1);
+ALTER TABLE bandit_policy
+    ADD COLUMN IF NOT EXISTS min_success_rate DOUBLE PRECISION NULL CHECK (min_success_rate >= 0 AND min_success_rate <= 1);
+ALTER TABLE bandit_policy
+    ADD COLUMN IF NOT EXISTS warm_start_strength DOUBLE PRECISION NOT NULL DEFAULT 20 CHECK (warm_start_strength >= 0);
@@ -0,0 +1,32 @@
+CREATE TABLE IF NOT EXISTS bandit_segment_definitions (
+    definition_id TEXT PRIMARY KEY,
+    dimensions TEXT[] NOT NULL CHECK (cardinality(dimensions) > 0),
+    fallback_definition_id TEXT NULL REFERENCES bandit_segment_definitions(definition_id),
+    min_observations DOUBLE PRECISION NOT NULL DEFAULT 0 CHECK (min_observations >= 0),
+    priority INT NOT NULL DEFAULT 100,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    CHECK (dimensions <@ ARRAY['PAYMENT_METHOD', 'AMOUNT_BUCKET', 'ISSUER', 'CARD_NETWORK', 'MERCHANT', 'HOUR_BAND']::TEXT[])
+);
+
+INSERT INTO bandit_segment_definitions (definition_id, dimensions, fallback_definition_id, min_observations, priority)
+VALUES ('method_amount', ARRAY['PAYMENT_METHOD', 'AMOUNT_BUCKET'], NULL, 0, 100)
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE bandit_policy
+    ADD COLUMN IF NOT EXISTS definition_id TEXT NOT NULL DEFAULT 'method_amount' REFERENCES bandit_segment_definitions(definition_id);
+ALTER TABLE bandit_state
+    ADD COLUMN IF NOT EXISTS definition_id TEXT NOT NULL DEFAULT 'method_amount' REFERENCES bandit_segment_definitions(definition_id);
+ALTER TABLE bandit_linear_models
+    ADD COLUMN IF NOT EXISTS definition_id TEXT NOT NULL DEFAULT 'method_amount' REFERENCES bandit_segment_definitions(definition_id);
+
+ALTER TABLE bandit_policy DROP CONSTRAINT IF EXISTS bandit_policy_pkey;
+ALTER TABLE bandit_policy ADD CONSTRAINT bandit_policy_pkey PRIMARY KEY (definition_id, segment);
+ALTER TABLE bandit_state DROP CONSTRAINT IF EXISTS bandit_state_pkey;
+ALTER TABLE bandit_state ADD CONSTRAINT bandit_state_pkey PRIMARY KEY (definition_id, segment, gateway_id);
+ALTER TABLE bandit_linear_models DROP CONSTRAINT IF EXISTS bandit_linear_models_pkey;
+ALTER TABLE bandit_linear_models ADD CONSTRAINT bandit_linear_models_pkey PRIMARY KEY (definition_id, segment, gateway_id);
+
+DROP TRIGGER IF EXISTS routing_config_notify ON bandit_segment_definitions;
+CREATE TRIGGER routing_config_notify AFTER INSERT OR UPDATE OR DELETE OR TRUNCATE ON bandit_segment_definitions
+FOR EACH STATEMENT EXECUTE FUNCTION notify_routing_config_changed();
@@ -0,0 +1,7 @@
+INSERT INTO experiment_variants (experiment_id, variant_name, traffic_pct, override_gateway)
+SELECT experiment_id, 'control', traffic_control_pct, NULL FROM experiments
+ON CONFLICT DO NOTHING;
+
+INSERT INTO experiment_variants (experiment_id, variant_name, traffic_pct, override_gateway)
+SELECT experiment_id, 'treatment', traffic_treatment_pct, treatment_gateway FROM experiments
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,8 @@
+ALTER TABLE experiment_variants ADD COLUMN IF NOT EXISTS scoring_profile_id TEXT NULL;
+ALTER TABLE experiment_variants ADD COLUMN IF NOT EXISTS bandit_enabled BOOLEAN NULL;
+ALTER TABLE experiment_variants ADD COLUMN IF NOT EXISTS rule_set TEXT NULL;
+ALTER TABLE experiment_variants ADD COLUMN IF NOT EXISTS retry_policy_json JSONB NULL;
+
+ALTER TABLE routing_rules ADD COLUMN IF NOT EXISTS rule_set TEXT NOT NULL DEFAULT 'default';
+
+CREATE INDEX IF NOT EXISTS idx_routing_rules_rule_set ON routing_rules(rule_set, enabled, priority);
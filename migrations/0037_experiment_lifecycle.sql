@@ -0,0 +1,19 @@
+ALTER TABLE experiments ADD COLUMN IF NOT EXISTS exposure_pct INT NOT NULL DEFAULT 100;
+ALTER TABLE experiments ADD COLUMN IF NOT EXISTS ramp_schedule_json JSONB NOT NULL DEFAULT '[]'::jsonb;
+ALTER TABLE experiments ADD COLUMN IF NOT EXISTS ramp_step INT NULL;
+ALTER TABLE experiments ADD COLUMN IF NOT EXISTS ramped_at TIMESTAMPTZ NULL;
+
+CREATE TABLE IF NOT EXISTS experiment_lifecycle_events (
+    event_id BIGSERIAL PRIMARY KEY,
+    experiment_id UUID NOT NULL,
+    action TEXT NOT NULL,
+    from_status TEXT NULL,
+    to_status TEXT NOT NULL,
+    exposure_pct INT NOT NULL,
+    reason TEXT NULL,
+    actor TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_experiment_lifecycle_events_lookup
+ON experiment_lifecycle_events(experiment_id, created_at);
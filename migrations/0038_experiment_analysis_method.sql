@@ -0,0 +1 @@
+ALTER TABLE experiments ADD COLUMN IF NOT EXISTS analysis_method TEXT NOT NULL DEFAULT 'FIXED_HORIZON';
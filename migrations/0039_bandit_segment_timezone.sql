@@ -0,0 +1 @@
+ALTER TABLE bandit_segment_definitions ADD COLUMN IF NOT EXISTS timezone TEXT NOT NULL DEFAULT 'UTC';
@@ -0,0 +1 @@
+ALTER TABLE routing_decisions ADD COLUMN IF NOT EXISTS penalized_gateways_json JSONB NOT NULL DEFAULT '[]'::jsonb;
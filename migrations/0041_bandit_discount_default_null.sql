@@ -0,0 +1 @@
+ALTER TABLE bandit_policy ALTER COLUMN discount_half_life_minutes SET DEFAULT NULL;
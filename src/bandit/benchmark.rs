@@ -0,0 +1,143 @@
+use crate::bandit::linucb::{LinearModel, FEATURE_DIM};
+use crate::bandit::thompson;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone)]
+pub struct LoggedDecision {
+    pub segment: String,
+    pub candidates: Vec<(String, Vec<f64>)>,
+    pub chosen_gateway: String,
+    pub success: bool,
+}
+
+impl LoggedDecision {
+    fn chosen_features(&self) -> Option<&[f64]> {
+        self.candidates
+            .iter()
+            .find(|(g, _)| g == &self.chosen_gateway)
+            .map(|(_, x)| x.as_slice())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayReport {
+    pub policy: String,
+    pub events: usize,
+    pub matched: usize,
+    pub successes: usize,
+    pub success_rate: Option<f64>,
+}
+
+pub trait ReplayPolicy {
+    fn name(&self) -> String;
+    fn choose(&mut self, decision: &LoggedDecision) -> Option<String>;
+    fn learn(&mut self, decision: &LoggedDecision);
+}
+
+pub fn replay(decisions: &[LoggedDecision], policy: &mut dyn ReplayPolicy) -> ReplayReport {
+    let mut matched = 0;
+    let mut successes = 0;
+    for decision in decisions {
+        if policy.choose(decision).as_deref() != Some(decision.chosen_gateway.as_str()) {
+            continue;
+        }
+        matched += 1;
+        if decision.success {
+            successes += 1;
+        }
+        policy.learn(decision);
+    }
+    ReplayReport {
+        policy: policy.name(),
+        events: decisions.len(),
+        matched,
+        successes,
+        success_rate: (matched > 0).then(|| successes as f64 / matched as f64),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ThompsonReplay {
+    posteriors: HashMap<(String, String), (f64, f64)>,
+}
+
+impl ReplayPolicy for ThompsonReplay {
+    fn name(&self) -> String {
+        "THOMPSON".to_string()
+    }
+
+    fn choose(&mut self, decision: &LoggedDecision) -> Option<String> {
+        decision
+            .candidates
+            .iter()
+            .map(|(gateway_id, _)| {
+                let (alpha, beta) = self
+                    .posteriors
+                    .get(&(decision.segment.clone(), gateway_id.clone()))
+                    .copied()
+                    .unwrap_or((1.0, 1.0));
+                (gateway_id, thompson::sample(alpha, beta))
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(g, _)| g.clone())
+    }
+
+    fn learn(&mut self, decision: &LoggedDecision) {
+        let entry = self
+            .posteriors
+            .entry((decision.segment.clone(), decision.chosen_gateway.clone()))
+            .or_insert((1.0, 1.0));
+        if decision.success {
+            entry.0 += 1.0;
+        } else {
+            entry.1 += 1.0;
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LinUcbReplay {
+    pub exploration: f64,
+    models: HashMap<(String, String), LinearModel>,
+}
+
+impl LinUcbReplay {
+    pub fn new(exploration: f64) -> Self {
+        Self {
+            exploration,
+            models: HashMap::new(),
+        }
+    }
+}
+
+impl ReplayPolicy for LinUcbReplay {
+    fn name(&self) -> String {
+        format!("LINUCB(exploration={})", self.exploration)
+    }
+
+    fn choose(&mut self, decision: &LoggedDecision) -> Option<String> {
+        decision
+            .candidates
+            .iter()
+            .map(|(gateway_id, x)| {
+                let score = match self.models.get(&(decision.segment.clone(), gateway_id.clone())) {
+                    Some(model) => model.ucb(x, self.exploration),
+                    None => LinearModel::new(FEATURE_DIM).ucb(x, self.exploration),
+                };
+                (gateway_id, score)
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(g, _)| g.clone())
+    }
+
+    fn learn(&mut self, decision: &LoggedDecision) {
+        let Some(x) = decision.chosen_features() else {
+            return;
+        };
+        self.models
+            .entry((decision.segment.clone(), decision.chosen_gateway.clone()))
+            .or_insert_with(|| LinearModel::new(FEATURE_DIM))
+            .update(x, if decision.success { 1.0 } else { 0.0 });
+    }
+}
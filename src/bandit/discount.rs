@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+pub const PRIOR_ALPHA: f64 = 1.0;
+pub const PRIOR_BETA: f64 = 1.0;
+const CREDIBLE_Z: f64 = 1.96;
+
+pub fn decay_factor(elapsed_minutes: f64, half_life_minutes: Option<f64>) -> f64 {
+    match half_life_minutes {
+        Some(h) if h > 0.0 && h.is_finite() => 0.5_f64.powf(elapsed_minutes.max(0.0) / h),
+        _ => 1.0,
+    }
+}
+
+pub fn decayed(alpha: f64, beta: f64, elapsed_minutes: f64, half_life_minutes: Option<f64>) -> (f64, f64) {
+    let factor = decay_factor(elapsed_minutes, half_life_minutes);
+    (
+        PRIOR_ALPHA + (alpha - PRIOR_ALPHA).max(0.0) * factor,
+        PRIOR_BETA + (beta - PRIOR_BETA).max(0.0) * factor,
+    )
+}
+
+pub fn validate_half_life(half_life_minutes: Option<f64>) -> Result<(), String> {
+    match half_life_minutes {
+        Some(h) if !h.is_finite() || h <= 0.0 => Err("half_life_minutes must be a positive number".to_string()),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PosteriorSummary {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub credible_width: f64,
+    pub effective_sample_size: f64,
+}
+
+pub fn summarize(alpha: f64, beta: f64) -> PosteriorSummary {
+    let total = alpha + beta;
+    let variance = alpha * beta / (total * total * (total + 1.0));
+    let std_dev = variance.sqrt();
+    PosteriorSummary {
+        mean: alpha / total,
+        std_dev,
+        credible_width: (2.0 * CREDIBLE_Z * std_dev).min(1.0),
+        effective_sample_size: (total - PRIOR_ALPHA - PRIOR_BETA).max(0.0),
+    }
+}
@@ -0,0 +1,219 @@
+use crate::bandit::linucb::{LinearModel, FEATURE_DIM};
+use crate::bandit::policy::{BanditAlgorithm, BanditPolicyConfig};
+use crate::bandit::safety::{mix_propensities, passes_floor};
+use crate::bandit::segments::BanditSegment;
+use crate::bandit::thompson;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::Serialize;
+use std::collections::HashMap;
+
+pub const MIN_PROPENSITY: f64 = 0.001;
+const EVALUATION_SEED: u64 = 7;
+
+#[derive(Debug, Clone)]
+pub struct EventCandidate {
+    pub gateway_id: String,
+    pub engine_score: f64,
+    pub features: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EvaluationEvent {
+    pub segment: BanditSegment,
+    pub state_segment: BanditSegment,
+    pub candidates: Vec<EventCandidate>,
+    pub logged_gateway: String,
+    pub propensity: f64,
+    pub reward: f64,
+}
+
+pub trait TargetPolicy {
+    fn name(&self) -> String;
+    fn probabilities(&self, event: &EvaluationEvent) -> HashMap<String, f64>;
+}
+
+pub fn greedy_probabilities(scores: &[(String, f64)]) -> HashMap<String, f64> {
+    let best = scores.iter().map(|(_, s)| *s).fold(f64::NEG_INFINITY, f64::max);
+    let winners = scores.iter().filter(|(_, s)| *s == best).count();
+    scores
+        .iter()
+        .map(|(g, s)| {
+            let p = if *s == best && winners > 0 { 1.0 / winners as f64 } else { 0.0 };
+            (g.clone(), p)
+        })
+        .collect()
+}
+
+pub struct ScoringEnginePolicy;
+
+impl TargetPolicy for ScoringEnginePolicy {
+    fn name(&self) -> String {
+        "SCORING_ENGINE".to_string()
+    }
+
+    fn probabilities(&self, event: &EvaluationEvent) -> HashMap<String, f64> {
+        let scores: Vec<(String, f64)> = event
+            .candidates
+            .iter()
+            .map(|c| (c.gateway_id.clone(), c.engine_score))
+            .collect();
+        greedy_probabilities(&scores)
+    }
+}
+
+pub struct BanditTarget {
+    pub name: String,
+    pub default: BanditPolicyConfig,
+    pub segments: HashMap<BanditSegment, BanditPolicyConfig>,
+    pub posteriors: HashMap<(BanditSegment, String), (f64, f64)>,
+    pub models: HashMap<(BanditSegment, String), LinearModel>,
+    pub draws: usize,
+}
+
+impl TargetPolicy for BanditTarget {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn probabilities(&self, event: &EvaluationEvent) -> HashMap<String, f64> {
+        let config = self.segments.get(&event.segment).unwrap_or(&self.default);
+        let arms: Vec<(String, f64, f64)> = event
+            .candidates
+            .iter()
+            .map(|c| {
+                let (alpha, beta) = self
+                    .posteriors
+                    .get(&(event.state_segment.clone(), c.gateway_id.clone()))
+                    .copied()
+                    .unwrap_or((1.0, 1.0));
+                (c.gateway_id.clone(), alpha, beta)
+            })
+            .collect();
+        let mut eligible: Vec<(String, f64, f64)> = arms
+            .iter()
+            .filter(|(_, alpha, beta)| passes_floor(*alpha, *beta, config.safety.min_success_rate))
+            .cloned()
+            .collect();
+        if eligible.is_empty() {
+            eligible = arms;
+        }
+
+        let bandit = match config.algorithm {
+            BanditAlgorithm::Thompson => {
+                let mut rng = StdRng::seed_from_u64(EVALUATION_SEED);
+                thompson::selection_probabilities(&eligible, self.draws, &mut rng)
+            }
+            BanditAlgorithm::LinUcb => {
+                let scores: Vec<(String, f64)> = event
+                    .candidates
+                    .iter()
+                    .filter(|c| eligible.iter().any(|(g, _, _)| g == &c.gateway_id))
+                    .map(|c| {
+                        let score = match self.models.get(&(event.state_segment.clone(), c.gateway_id.clone())) {
+                            Some(model) => model.ucb(&c.features, config.exploration),
+                            None => LinearModel::new(FEATURE_DIM).ucb(&c.features, config.exploration),
+                        };
+                        (c.gateway_id.clone(), score)
+                    })
+                    .collect();
+                greedy_probabilities(&scores)
+            }
+        };
+        match (config.safety.max_exploration_share, engine_top(event, &eligible)) {
+            (Some(share), Some(top)) => mix_propensities(&bandit, &top, share),
+            _ => bandit,
+        }
+    }
+}
+
+fn engine_top(event: &EvaluationEvent, eligible: &[(String, f64, f64)]) -> Option<String> {
+    event
+        .candidates
+        .iter()
+        .filter(|c| eligible.iter().any(|(g, _, _)| g == &c.gateway_id))
+        .fold(None, |best: Option<&EventCandidate>, c| match best {
+            Some(b) if b.engine_score >= c.engine_score => Some(b),
+            _ => Some(c),
+        })
+        .map(|c| c.gateway_id.clone())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RewardModel {
+    arms: HashMap<(BanditSegment, String), (f64, f64)>,
+}
+
+impl RewardModel {
+    pub fn fit(events: &[EvaluationEvent]) -> Self {
+        let mut arms: HashMap<(BanditSegment, String), (f64, f64)> = HashMap::new();
+        for event in events {
+            let entry = arms
+                .entry((event.segment.clone(), event.logged_gateway.clone()))
+                .or_insert((0.0, 0.0));
+            entry.0 += event.reward;
+            entry.1 += 1.0;
+        }
+        Self { arms }
+    }
+
+    pub fn predict(&self, segment: &BanditSegment, gateway_id: &str) -> f64 {
+        let (sum, count) = self
+            .arms
+            .get(&(segment.clone(), gateway_id.to_string()))
+            .copied()
+            .unwrap_or((0.0, 0.0));
+        (sum + 1.0) / (count + 2.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OffPolicyEstimate {
+    pub policy: String,
+    pub events: usize,
+    pub ips: Option<f64>,
+    pub snips: Option<f64>,
+    pub doubly_robust: Option<f64>,
+    pub effective_sample_size: f64,
+    pub agreement_rate: Option<f64>,
+}
+
+pub fn evaluate(events: &[EvaluationEvent], policy: &dyn TargetPolicy, model: &RewardModel) -> OffPolicyEstimate {
+    let mut ips_sum = 0.0;
+    let mut dr_sum = 0.0;
+    let mut weight_sum = 0.0;
+    let mut weight_sq_sum = 0.0;
+    let mut agreement = 0.0;
+    for event in events {
+        let probabilities = policy.probabilities(event);
+        let target = probabilities.get(&event.logged_gateway).copied().unwrap_or(0.0);
+        let weight = target / event.propensity.max(MIN_PROPENSITY);
+        let direct: f64 = event
+            .candidates
+            .iter()
+            .map(|c| probabilities.get(&c.gateway_id).copied().unwrap_or(0.0) * model.predict(&event.segment, &c.gateway_id))
+            .sum();
+        let predicted = model.predict(&event.segment, &event.logged_gateway);
+
+        ips_sum += weight * event.reward;
+        dr_sum += direct + weight * (event.reward - predicted);
+        weight_sum += weight;
+        weight_sq_sum += weight * weight;
+        agreement += target;
+    }
+
+    let n = events.len() as f64;
+    OffPolicyEstimate {
+        policy: policy.name(),
+        events: events.len(),
+        ips: (n > 0.0).then(|| ips_sum / n),
+        snips: (weight_sum > 0.0).then(|| ips_sum / weight_sum),
+        doubly_robust: (n > 0.0).then(|| dr_sum / n),
+        effective_sample_size: if weight_sq_sum > 0.0 { weight_sum * weight_sum / weight_sq_sum } else { 0.0 },
+        agreement_rate: (n > 0.0).then(|| agreement / n),
+    }
+}
+
+pub fn logged_reward(events: &[EvaluationEvent]) -> Option<f64> {
+    (!events.is_empty()).then(|| events.iter().map(|e| e.reward).sum::<f64>() / events.len() as f64)
+}
@@ -0,0 +1,78 @@
+use crate::scoring::types::ScoreBreakdown;
+use serde::{Deserialize, Serialize};
+
+pub const FEATURE_DIM: usize = 8;
+
+pub fn features(breakdown: &ScoreBreakdown) -> Vec<f64> {
+    vec![
+        1.0,
+        breakdown.success_rate_score,
+        breakdown.latency_score,
+        breakdown.method_affinity,
+        breakdown.bank_affinity,
+        breakdown.amount_fit,
+        breakdown.time_weight,
+        breakdown.cost_score,
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearModel {
+    pub a_inv: Vec<f64>,
+    pub b: Vec<f64>,
+    pub observations: i64,
+}
+
+impl LinearModel {
+    pub fn new(dim: usize) -> Self {
+        let mut a_inv = vec![0.0; dim * dim];
+        for i in 0..dim {
+            a_inv[i * dim + i] = 1.0;
+        }
+        Self {
+            a_inv,
+            b: vec![0.0; dim],
+            observations: 0,
+        }
+    }
+
+    pub fn dim(&self) -> usize {
+        self.b.len()
+    }
+
+    pub fn is_valid(&self, dim: usize) -> bool {
+        self.b.len() == dim && self.a_inv.len() == dim * dim && self.a_inv.iter().chain(&self.b).all(|v| v.is_finite())
+    }
+
+    fn a_inv_times(&self, x: &[f64]) -> Vec<f64> {
+        let d = self.dim();
+        (0..d)
+            .map(|i| (0..d).map(|j| self.a_inv[i * d + j] * x[j]).sum())
+            .collect()
+    }
+
+    pub fn theta(&self) -> Vec<f64> {
+        self.a_inv_times(&self.b)
+    }
+
+    pub fn ucb(&self, x: &[f64], exploration: f64) -> f64 {
+        let mean: f64 = self.theta().iter().zip(x).map(|(t, v)| t * v).sum();
+        let variance: f64 = self.a_inv_times(x).iter().zip(x).map(|(a, v)| a * v).sum();
+        mean + exploration * variance.max(0.0).sqrt()
+    }
+
+    pub fn update(&mut self, x: &[f64], reward: f64) {
+        let d = self.dim();
+        let a_inv_x = self.a_inv_times(x);
+        let denom = 1.0 + a_inv_x.iter().zip(x).map(|(a, v)| a * v).sum::<f64>();
+        for i in 0..d {
+            for j in 0..d {
+                self.a_inv[i * d + j] -= a_inv_x[i] * a_inv_x[j] / denom;
+            }
+        }
+        for (bi, xi) in self.b.iter_mut().zip(x) {
+            *bi += reward * xi;
+        }
+        self.observations += 1;
+    }
+}
@@ -1 +1,9 @@
+pub mod benchmark;
+pub mod discount;
+pub mod evaluation;
+pub mod linucb;
+pub mod policy;
+pub mod reward;
+pub mod safety;
+pub mod segments;
 pub mod thompson;
@@ -0,0 +1,80 @@
+use crate::bandit::reward::RewardConfig;
+use crate::bandit::safety::SafetyConfig;
+use crate::bandit::segments::{BanditSegment, DEFAULT_DEFINITION_ID};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_EXPLORATION: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BanditAlgorithm {
+    #[default]
+    #[serde(rename = "THOMPSON")]
+    Thompson,
+    #[serde(rename = "LINUCB")]
+    LinUcb,
+}
+
+impl BanditAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BanditAlgorithm::Thompson => "THOMPSON",
+            BanditAlgorithm::LinUcb => "LINUCB",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "THOMPSON" => Some(BanditAlgorithm::Thompson),
+            "LINUCB" => Some(BanditAlgorithm::LinUcb),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanditPolicyConfig {
+    #[serde(default = "default_definition_id")]
+    pub definition_id: String,
+    pub segment: String,
+    pub algorithm: BanditAlgorithm,
+    pub exploration: f64,
+    #[serde(default)]
+    pub reward: RewardConfig,
+    #[serde(default)]
+    pub safety: SafetyConfig,
+}
+
+fn default_definition_id() -> String {
+    DEFAULT_DEFINITION_ID.to_string()
+}
+
+impl BanditPolicyConfig {
+    pub fn thompson(segment: &str) -> Self {
+        Self {
+            definition_id: default_definition_id(),
+            segment: segment.to_string(),
+            algorithm: BanditAlgorithm::Thompson,
+            exploration: DEFAULT_EXPLORATION,
+            reward: RewardConfig::default(),
+            safety: SafetyConfig::default(),
+        }
+    }
+
+    pub fn for_segment(segment: &BanditSegment) -> Self {
+        Self {
+            definition_id: segment.definition_id.clone(),
+            ..Self::thompson(&segment.key)
+        }
+    }
+
+    pub fn bandit_segment(&self) -> BanditSegment {
+        BanditSegment::new(&self.definition_id, &self.segment)
+    }
+}
+
+pub fn validate_exploration(exploration: f64) -> Result<(), String> {
+    if !exploration.is_finite() || exploration < 0.0 {
+        return Err("exploration must be a non-negative number".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RewardConfig {
+    #[serde(default)]
+    pub latency_target_ms: Option<i32>,
+    #[serde(default)]
+    pub latency_penalty: f64,
+    #[serde(default)]
+    pub cost_penalty: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AttemptReward {
+    pub success: bool,
+    pub latency_ms: i32,
+    pub expected_fee_minor: Option<i64>,
+    pub amount_minor: i64,
+}
+
+pub fn validate_reward(config: &RewardConfig) -> Result<(), String> {
+    if config.latency_target_ms.is_some_and(|t| t <= 0) {
+        return Err("latency_target_ms must be positive".to_string());
+    }
+    if !(0.0..=1.0).contains(&config.latency_penalty) {
+        return Err("latency_penalty must be between 0 and 1".to_string());
+    }
+    if !config.cost_penalty.is_finite() || config.cost_penalty < 0.0 {
+        return Err("cost_penalty must be a non-negative number".to_string());
+    }
+    Ok(())
+}
+
+pub fn reward(config: &RewardConfig, attempt: &AttemptReward) -> f64 {
+    if !attempt.success {
+        return 0.0;
+    }
+    let latency_deduction = match config.latency_target_ms {
+        Some(target) if attempt.latency_ms > target => {
+            let overshoot = (attempt.latency_ms - target) as f64 / target as f64;
+            config.latency_penalty * overshoot.min(1.0)
+        }
+        _ => 0.0,
+    };
+    let cost_deduction = match attempt.expected_fee_minor {
+        Some(fee) if attempt.amount_minor > 0 => config.cost_penalty * fee.max(0) as f64 / attempt.amount_minor as f64,
+        _ => 0.0,
+    };
+    (1.0 - latency_deduction - cost_deduction).clamp(0.0, 1.0)
+}
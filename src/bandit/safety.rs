@@ -0,0 +1,73 @@
+use crate::bandit::discount::{PRIOR_ALPHA, PRIOR_BETA};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const DEFAULT_WARM_START_STRENGTH: f64 = 20.0;
+pub const WARM_START_LOOKBACK_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafetyConfig {
+    #[serde(default)]
+    pub max_exploration_share: Option<f64>,
+    #[serde(default)]
+    pub min_success_rate: Option<f64>,
+    #[serde(default = "default_warm_start_strength")]
+    pub warm_start_strength: f64,
+}
+
+fn default_warm_start_strength() -> f64 {
+    DEFAULT_WARM_START_STRENGTH
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            max_exploration_share: None,
+            min_success_rate: None,
+            warm_start_strength: DEFAULT_WARM_START_STRENGTH,
+        }
+    }
+}
+
+pub fn validate_safety(config: &SafetyConfig) -> Result<(), String> {
+    if config.max_exploration_share.is_some_and(|s| !(0.0..=1.0).contains(&s)) {
+        return Err("max_exploration_share must be between 0 and 1".to_string());
+    }
+    if config.min_success_rate.is_some_and(|s| !(0.0..=1.0).contains(&s)) {
+        return Err("min_success_rate must be between 0 and 1".to_string());
+    }
+    if !config.warm_start_strength.is_finite() || config.warm_start_strength < 0.0 {
+        return Err("warm_start_strength must be a non-negative number".to_string());
+    }
+    Ok(())
+}
+
+pub fn warm_start_prior(success_rate: Option<f64>, requests: f64, strength: f64) -> (f64, f64) {
+    match success_rate {
+        Some(rate) if rate.is_finite() && requests > 0.0 => {
+            let rate = rate.clamp(0.0, 1.0);
+            let n = strength.max(0.0).min(requests);
+            (PRIOR_ALPHA + n * rate, PRIOR_BETA + n * (1.0 - rate))
+        }
+        _ => (PRIOR_ALPHA, PRIOR_BETA),
+    }
+}
+
+pub fn passes_floor(alpha: f64, beta: f64, min_success_rate: Option<f64>) -> bool {
+    match min_success_rate {
+        Some(_) if alpha <= PRIOR_ALPHA && beta <= PRIOR_BETA => true,
+        Some(floor) => alpha / (alpha + beta) >= floor,
+        None => true,
+    }
+}
+
+pub fn mix_propensities(
+    bandit: &HashMap<String, f64>,
+    engine_top: &str,
+    exploration_share: f64,
+) -> HashMap<String, f64> {
+    let share = exploration_share.clamp(0.0, 1.0);
+    let mut mixed: HashMap<String, f64> = bandit.iter().map(|(g, p)| (g.clone(), share * p)).collect();
+    *mixed.entry(engine_top.to_string()).or_default() += 1.0 - share;
+    mixed
+}
@@ -0,0 +1,290 @@
+use crate::metrics::amount_bucket::AmountBucketer;
+use crate::scoring::schedule::{local_hour_and_day, parse_timezone};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+pub const DEFAULT_DEFINITION_ID: &str = "method_amount";
+pub const HOUR_BAND_HOURS: u32 = 6;
+const SEPARATOR: char = ':';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SegmentDimension {
+    PaymentMethod,
+    AmountBucket,
+    Issuer,
+    CardNetwork,
+    Merchant,
+    HourBand,
+}
+
+impl SegmentDimension {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SegmentDimension::PaymentMethod => "PAYMENT_METHOD",
+            SegmentDimension::AmountBucket => "AMOUNT_BUCKET",
+            SegmentDimension::Issuer => "ISSUER",
+            SegmentDimension::CardNetwork => "CARD_NETWORK",
+            SegmentDimension::Merchant => "MERCHANT",
+            SegmentDimension::HourBand => "HOUR_BAND",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "PAYMENT_METHOD" => Some(SegmentDimension::PaymentMethod),
+            "AMOUNT_BUCKET" => Some(SegmentDimension::AmountBucket),
+            "ISSUER" => Some(SegmentDimension::Issuer),
+            "CARD_NETWORK" => Some(SegmentDimension::CardNetwork),
+            "MERCHANT" => Some(SegmentDimension::Merchant),
+            "HOUR_BAND" => Some(SegmentDimension::HourBand),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SegmentContext<'a> {
+    pub payment_method: &'a str,
+    pub amount_bucket: &'a str,
+    pub issuer: &'a str,
+    pub card_network: Option<&'a str>,
+    pub merchant_id: &'a str,
+    pub at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BanditSegment {
+    pub definition_id: String,
+    pub key: String,
+}
+
+impl BanditSegment {
+    pub fn new(definition_id: &str, key: &str) -> Self {
+        Self {
+            definition_id: definition_id.to_string(),
+            key: key.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for BanditSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.definition_id, self.key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SegmentDefinition {
+    pub definition_id: String,
+    pub dimensions: Vec<SegmentDimension>,
+    #[serde(default)]
+    pub fallback_definition_id: Option<String>,
+    #[serde(default)]
+    pub min_observations: f64,
+    #[serde(default = "default_priority")]
+    pub priority: i32,
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+}
+
+fn default_priority() -> i32 {
+    100
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+pub fn hour_band(hour: u32) -> String {
+    format!("H{:02}", (hour % 24) / HOUR_BAND_HOURS * HOUR_BAND_HOURS)
+}
+
+fn clean(value: &str) -> String {
+    let value = value.trim().replace(SEPARATOR, "_");
+    if value.is_empty() {
+        "UNKNOWN".to_string()
+    } else {
+        value
+    }
+}
+
+impl SegmentDefinition {
+    pub fn default_definition() -> Self {
+        Self {
+            definition_id: DEFAULT_DEFINITION_ID.to_string(),
+            dimensions: vec![SegmentDimension::PaymentMethod, SegmentDimension::AmountBucket],
+            fallback_definition_id: None,
+            min_observations: 0.0,
+            priority: default_priority(),
+            timezone: default_timezone(),
+        }
+    }
+
+    pub fn local_hour(&self, at: DateTime<Utc>) -> u32 {
+        let timezone = parse_timezone(&self.timezone).unwrap_or(Tz::UTC);
+        local_hour_and_day(at, timezone).0 as u32
+    }
+
+    pub fn key(&self, ctx: &SegmentContext) -> String {
+        self.dimensions
+            .iter()
+            .map(|d| match d {
+                SegmentDimension::PaymentMethod => clean(&ctx.payment_method.to_uppercase()),
+                SegmentDimension::AmountBucket => clean(ctx.amount_bucket),
+                SegmentDimension::Issuer => clean(&ctx.issuer.to_uppercase()),
+                SegmentDimension::CardNetwork => clean(&ctx.card_network.unwrap_or("UNKNOWN").to_uppercase()),
+                SegmentDimension::Merchant => clean(ctx.merchant_id),
+                SegmentDimension::HourBand => hour_band(self.local_hour(ctx.at)),
+            })
+            .collect::<Vec<_>>()
+            .join(&SEPARATOR.to_string())
+    }
+
+    pub fn segment(&self, ctx: &SegmentContext) -> BanditSegment {
+        BanditSegment::new(&self.definition_id, &self.key(ctx))
+    }
+
+    pub fn value<'a>(&self, key: &'a str, dimension: SegmentDimension) -> Option<&'a str> {
+        let position = self.dimensions.iter().position(|d| *d == dimension)?;
+        key.split(SEPARATOR).nth(position)
+    }
+
+    pub fn validate_key(&self, key: &str, buckets: &AmountBucketer) -> Result<(), String> {
+        let parts: Vec<&str> = key.split(SEPARATOR).collect();
+        if parts.len() != self.dimensions.len() {
+            return Err(format!(
+                "segment for {} must have {} parts ({})",
+                self.definition_id,
+                self.dimensions.len(),
+                self.dimensions.iter().map(|d| d.as_str()).collect::<Vec<_>>().join(":")
+            ));
+        }
+        for (dimension, part) in self.dimensions.iter().zip(parts) {
+            if part.trim().is_empty() {
+                return Err(format!("{} must not be empty", dimension.as_str()));
+            }
+            match dimension {
+                SegmentDimension::AmountBucket => {
+                    let known = buckets
+                        .currencies()
+                        .iter()
+                        .any(|c| buckets.buckets(c).iter().any(|b| b.bucket_id == part));
+                    if !known {
+                        return Err(format!("unknown amount bucket {}", part));
+                    }
+                }
+                SegmentDimension::HourBand => {
+                    let valid = (0..24).step_by(HOUR_BAND_HOURS as usize).any(|h| hour_band(h) == part);
+                    if !valid {
+                        return Err(format!("unknown hour band {}", part));
+                    }
+                }
+                SegmentDimension::PaymentMethod | SegmentDimension::Issuer | SegmentDimension::CardNetwork => {
+                    if part != part.to_uppercase() {
+                        return Err(format!("{} must be upper case", dimension.as_str()));
+                    }
+                }
+                SegmentDimension::Merchant => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn validate_definitions(definitions: &[SegmentDefinition]) -> Result<(), String> {
+    let by_id: HashMap<&str, &SegmentDefinition> =
+        definitions.iter().map(|d| (d.definition_id.as_str(), d)).collect();
+    if by_id.len() != definitions.len() {
+        return Err("definition_id must be unique".to_string());
+    }
+    for definition in definitions {
+        let id = definition.definition_id.as_str();
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("definition_id {:?} may only contain letters, digits, '_' and '-'", id));
+        }
+        if definition.dimensions.is_empty() {
+            return Err(format!("{} must have at least one dimension", id));
+        }
+        let unique: HashSet<_> = definition.dimensions.iter().collect();
+        if unique.len() != definition.dimensions.len() {
+            return Err(format!("{} repeats a dimension", id));
+        }
+        if parse_timezone(&definition.timezone).is_none() {
+            return Err(format!("{} has unknown timezone {}", id, definition.timezone));
+        }
+        if !definition.min_observations.is_finite() || definition.min_observations < 0.0 {
+            return Err(format!("{} min_observations must be a non-negative number", id));
+        }
+
+        let mut seen = HashSet::from([id]);
+        let mut current = definition;
+        while let Some(fallback_id) = current.fallback_definition_id.as_deref() {
+            let Some(fallback) = by_id.get(fallback_id) else {
+                return Err(format!("{} falls back to unknown definition {}", current.definition_id, fallback_id));
+            };
+            if !fallback.dimensions.iter().all(|d| current.dimensions.contains(d)) {
+                return Err(format!(
+                    "{} must fall back to a coarser definition, but {} adds dimensions",
+                    current.definition_id, fallback_id
+                ));
+            }
+            if !seen.insert(fallback_id) {
+                return Err(format!("fallback chain of {} has a cycle", id));
+            }
+            current = fallback;
+        }
+    }
+    if !by_id.contains_key(DEFAULT_DEFINITION_ID) {
+        return Err(format!("the {} definition cannot be removed", DEFAULT_DEFINITION_ID));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct SegmentCatalog {
+    definitions: Vec<SegmentDefinition>,
+}
+
+impl Default for SegmentCatalog {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl SegmentCatalog {
+    pub fn new(mut definitions: Vec<SegmentDefinition>) -> Self {
+        if !definitions.iter().any(|d| d.definition_id == DEFAULT_DEFINITION_ID) {
+            definitions.push(SegmentDefinition::default_definition());
+        }
+        definitions.sort_by(|a, b| a.priority.cmp(&b.priority).then_with(|| a.definition_id.cmp(&b.definition_id)));
+        Self { definitions }
+    }
+
+    pub fn definitions(&self) -> &[SegmentDefinition] {
+        &self.definitions
+    }
+
+    pub fn get(&self, definition_id: &str) -> Option<&SegmentDefinition> {
+        self.definitions.iter().find(|d| d.definition_id == definition_id)
+    }
+
+    pub fn segments(&self, ctx: &SegmentContext) -> Vec<BanditSegment> {
+        self.definitions.iter().map(|d| d.segment(ctx)).collect()
+    }
+
+    pub fn fallback_chain(&self, definition_id: &str) -> Vec<&SegmentDefinition> {
+        let mut chain: Vec<&SegmentDefinition> = Vec::new();
+        let mut next = self.get(definition_id);
+        while let Some(definition) = next {
+            if chain.iter().any(|d| d.definition_id == definition.definition_id) {
+                break;
+            }
+            chain.push(definition);
+            next = definition.fallback_definition_id.as_deref().and_then(|id| self.get(id));
+        }
+        chain
+    }
+}
@@ -1,10 +1,42 @@
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
 use rand_distr::{Beta, Distribution};
+use std::collections::HashMap;
+
+pub const PROPENSITY_DRAWS: usize = 1000;
 
 pub fn sample(alpha: f64, beta: f64) -> f64 {
+    sample_with(alpha, beta, &mut thread_rng())
+}
+
+pub fn sample_with<R: Rng + ?Sized>(alpha: f64, beta: f64, rng: &mut R) -> f64 {
     if let Ok(dist) = Beta::new(alpha.max(0.001), beta.max(0.001)) {
-        dist.sample(&mut thread_rng())
+        dist.sample(rng)
     } else {
         0.5
     }
 }
+
+pub fn selection_probabilities<R: Rng + ?Sized>(
+    arms: &[(String, f64, f64)],
+    draws: usize,
+    rng: &mut R,
+) -> HashMap<String, f64> {
+    let mut wins: HashMap<String, f64> = arms.iter().map(|(g, _, _)| (g.clone(), 0.0)).collect();
+    if arms.is_empty() || draws == 0 {
+        return wins;
+    }
+    for _ in 0..draws {
+        let best = arms
+            .iter()
+            .map(|(g, alpha, beta)| (g, sample_with(*alpha, *beta, rng)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(g, _)| g.clone());
+        if let Some(best) = best {
+            *wins.entry(best).or_default() += 1.0;
+        }
+    }
+    for value in wins.values_mut() {
+        *value /= draws as f64;
+    }
+    wins
+}
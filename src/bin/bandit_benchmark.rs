@@ -0,0 +1,64 @@
+use anyhow::{Context, Result};
+use payments_gateway::bandit::benchmark::{replay, LinUcbReplay, ReplayPolicy, ThompsonReplay};
+use payments_gateway::config::AppConfig;
+use payments_gateway::metrics::amount_bucket::AmountBucketer;
+use payments_gateway::repo::amount_buckets_repo::AmountBucketsRepo;
+use payments_gateway::repo::backtest_repo::BacktestRepo;
+use sqlx::postgres::PgPoolOptions;
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let cfg = AppConfig::from_env();
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&cfg.database_url)
+        .await?;
+
+    let to = match std::env::var("BACKTEST_TO") {
+        Ok(v) => v.parse::<chrono::DateTime<chrono::Utc>>().context("BACKTEST_TO must be RFC3339")?,
+        Err(_) => chrono::Utc::now(),
+    };
+    let from = match std::env::var("BACKTEST_FROM") {
+        Ok(v) => v.parse::<chrono::DateTime<chrono::Utc>>().context("BACKTEST_FROM must be RFC3339")?,
+        Err(_) => to - chrono::Duration::days(7),
+    };
+    let limit = std::env::var("BACKTEST_LIMIT")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(200_000);
+    let explorations: Vec<f64> = std::env::var("LINUCB_EXPLORATION")
+        .unwrap_or_else(|_| "0.1,0.5,1.0".to_string())
+        .split(',')
+        .filter_map(|v| v.trim().parse().ok())
+        .collect();
+
+    let buckets = AmountBucketer::new(AmountBucketsRepo { pool: pool.clone() }.list_all().await?);
+    let decisions = BacktestRepo { pool }
+        .load_logged_decisions(from, to, limit, &buckets)
+        .await?;
+    tracing::info!("bandit_benchmark from={} to={} decisions={}", from, to, decisions.len());
+
+    let mut policies: Vec<Box<dyn ReplayPolicy>> = vec![Box::new(ThompsonReplay::default())];
+    for exploration in explorations {
+        policies.push(Box::new(LinUcbReplay::new(exploration)));
+    }
+    let reports: Vec<_> = policies.iter_mut().map(|p| replay(&decisions, p.as_mut())).collect();
+    let logged_successes = decisions.iter().filter(|d| d.success).count();
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&serde_json::json!({
+            "from": from,
+            "to": to,
+            "decisions": decisions.len(),
+            "logged_success_rate": (!decisions.is_empty()).then(|| logged_successes as f64 / decisions.len() as f64),
+            "policies": reports,
+        }))?
+    );
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use anyhow::Result;
+use payments_gateway::domain::experiment::{AnalysisMethod, ExperimentStatus};
 use payments_gateway::experiments::analyzer::{analyze, evaluate_guardrails, GuardrailConfig};
+use payments_gateway::experiments::lifecycle::{next_transition, step_results, LifecycleAction};
 use payments_gateway::repo::experiments_repo::ExperimentsRepo;
 use payments_gateway::repo::webhook_repo::WebhookRepo;
 use payments_gateway::service::webhook_dispatcher::WebhookDispatcher;
@@ -35,27 +37,66 @@ async fn main() -> Result<()> {
             .unwrap_or(1.5),
     };
 
+    let interval_secs = std::env::var("EXPERIMENT_ANALYZER_INTERVAL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(300);
+
     loop {
         let experiments = repo.list().await?;
-        for exp in experiments.into_iter().filter(|e| e.status == "RUNNING") {
-            let results = repo.results(exp.experiment_id).await?;
-            let out = analyze(&results, 100);
-            let guardrail = evaluate_guardrails(&results, &guardrails);
+        for exp in experiments {
+            let running = exp.status == ExperimentStatus::Running.as_str();
+            let guardrail = if running {
+                let results = repo.results(exp.experiment_id).await?;
+                let method = AnalysisMethod::parse(&exp.analysis_method).unwrap_or_default();
+                let out = analyze(&results, 100, method);
+                tracing::info!(
+                    "experiment={} method={} winner={:?} p_value={} probability_to_beat_control={:?} recommendation={}",
+                    exp.experiment_id,
+                    out.method,
+                    out.winner,
+                    out.p_value,
+                    out.probability_to_beat_control,
+                    out.recommendation
+                );
+                for comparison in &out.comparisons {
+                    tracing::info!(
+                        "experiment={} variant={} success_rate={:.4} p_value={} adjusted_p_value={} probability_to_beat_control={:?} expected_loss={:?}",
+                        exp.experiment_id,
+                        comparison.variant,
+                        comparison.success_rate,
+                        comparison.p_value,
+                        comparison.adjusted_p_value,
+                        comparison.probability_to_beat_control,
+                        comparison.expected_loss
+                    );
+                }
+                Some(evaluate_guardrails(&step_results(&exp, &results), &guardrails))
+            } else {
+                None
+            };
+
+            let Some(transition) = next_transition(&exp, guardrail.as_ref(), chrono::Utc::now()) else {
+                continue;
+            };
+            if !repo.transition(&exp, &transition, "experiment_analyzer").await? {
+                continue;
+            }
             tracing::info!(
-                "experiment={} winner={:?} p_value={} recommendation={}",
+                "experiment={} action={} status={} exposure_pct={}",
                 exp.experiment_id,
-                out.winner,
-                out.p_value,
-                out.recommendation
+                transition.action.as_str(),
+                transition.to_status.as_str(),
+                transition.exposure_pct.unwrap_or(exp.exposure_pct)
             );
-            if guardrail.should_pause {
-                repo.stop(exp.experiment_id).await?;
+            if let (LifecycleAction::GuardrailPaused, Some(guardrail)) = (transition.action, &guardrail) {
                 let _ = webhook_dispatcher
                     .emit(
                         "experiment.guardrail_violation",
                         serde_json::json!({
                             "experiment_id": exp.experiment_id,
                             "reason": guardrail.reason,
+                            "variant": guardrail.variant,
                             "control_success_rate": guardrail.control_success_rate,
                             "treatment_success_rate": guardrail.treatment_success_rate,
                             "control_p95_latency_ms": guardrail.control_p95_latency_ms,
@@ -66,6 +107,6 @@ async fn main() -> Result<()> {
             }
         }
 
-        tokio::time::sleep(std::time::Duration::from_secs(300)).await;
+        tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
     }
 }
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use chrono::{TimeZone, Utc};
+use chrono::{TimeZone, Timelike, Utc};
 use payments_gateway::config::AppConfig;
 use payments_gateway::metrics::aggregator::SlidingMetrics;
 use payments_gateway::metrics::event::PaymentEvent;
@@ -85,6 +85,9 @@ async fn main() -> Result<()> {
                     for window in windows {
                         if let Some(metric) = agg.compute(&key, window, now) {
                             hot_store.write_metric(&key, window, &metric).await?;
+                            if key.is_rollup() {
+                                continue;
+                            }
                             let minute = now.timestamp() - (now.second() as i64);
                             let snapshot = Utc.timestamp_opt(minute, 0).single().unwrap_or(now);
                             history_repo
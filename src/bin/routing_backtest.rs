@@ -0,0 +1,96 @@
+use anyhow::{Context, Result};
+use payments_gateway::config::AppConfig;
+use payments_gateway::repo::amount_buckets_repo::AmountBucketsRepo;
+use payments_gateway::repo::backtest_repo::BacktestRepo;
+use payments_gateway::repo::bandit_repo::BanditRepo;
+use payments_gateway::repo::bins_repo::BinsRepo;
+use payments_gateway::repo::circuit_breaker_config_repo::CircuitBreakerConfigRepo;
+use payments_gateway::repo::error_classification_repo::ErrorClassificationRepo;
+use payments_gateway::repo::gateways_repo::GatewaysRepo;
+use payments_gateway::repo::issuers_repo::IssuersRepo;
+use payments_gateway::repo::maintenance_windows_repo::MaintenanceWindowsRepo;
+use payments_gateway::repo::retry_policy_repo::RetryPolicyRepo;
+use payments_gateway::repo::routing_rules_repo::RoutingRulesRepo;
+use payments_gateway::repo::scoring_config_repo::ScoringConfigRepo;
+use payments_gateway::repo::scoring_profiles_repo::ScoringProfilesRepo;
+use payments_gateway::repo::volume_targets_repo::VolumeTargetsRepo;
+use payments_gateway::scoring::backtest::{BacktestScenario, Backtester};
+use payments_gateway::service::routing_config::RoutingConfigLoader;
+use sqlx::postgres::PgPoolOptions;
+use tracing_subscriber::EnvFilter;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let cfg = AppConfig::from_env();
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&cfg.database_url)
+        .await?;
+
+    let to = match std::env::var("BACKTEST_TO") {
+        Ok(v) => v.parse::<chrono::DateTime<chrono::Utc>>().context("BACKTEST_TO must be RFC3339")?,
+        Err(_) => chrono::Utc::now(),
+    };
+    let from = match std::env::var("BACKTEST_FROM") {
+        Ok(v) => v.parse::<chrono::DateTime<chrono::Utc>>().context("BACKTEST_FROM must be RFC3339")?,
+        Err(_) => to - chrono::Duration::days(1),
+    };
+    let limit = std::env::var("BACKTEST_LIMIT")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(50_000);
+
+    let scenarios: Vec<BacktestScenario> = match std::env::var("BACKTEST_SCENARIOS_FILE") {
+        Ok(path) => {
+            let raw = std::fs::read_to_string(&path).with_context(|| format!("reading {}", path))?;
+            serde_json::from_str(&raw).with_context(|| format!("parsing {}", path))?
+        }
+        Err(_) => vec![BacktestScenario::baseline()],
+    };
+
+    let backtest_repo = BacktestRepo { pool: pool.clone() };
+    let config = RoutingConfigLoader {
+        gateways_repo: GatewaysRepo { pool: pool.clone() },
+        scoring_config_repo: ScoringConfigRepo { pool: pool.clone() },
+        scoring_profiles_repo: ScoringProfilesRepo { pool: pool.clone() },
+        routing_rules_repo: RoutingRulesRepo { pool: pool.clone() },
+        volume_targets_repo: VolumeTargetsRepo { pool: pool.clone() },
+        retry_policy_repo: RetryPolicyRepo { pool: pool.clone() },
+        error_classification_repo: ErrorClassificationRepo { pool: pool.clone() },
+        circuit_breaker_config_repo: CircuitBreakerConfigRepo { pool: pool.clone() },
+        bandit_repo: BanditRepo { pool: pool.clone() },
+        issuers_repo: IssuersRepo { pool: pool.clone() },
+        bins_repo: BinsRepo { pool: pool.clone() },
+        amount_buckets_repo: AmountBucketsRepo { pool: pool.clone() },
+        maintenance_windows_repo: MaintenanceWindowsRepo { pool },
+    }
+    .load()
+    .await?;
+    let default_weights = config.weights_for(None);
+
+    let cases = backtest_repo.load_cases(from, to, limit, &config.amount_buckets).await?;
+    let metrics = backtest_repo.load_metric_history(from, to).await?;
+    tracing::info!("routing_backtest from={} to={} cases={} scenarios={}", from, to, cases.len(), scenarios.len());
+
+    let backtester = Backtester {
+        config: &config,
+        metrics: &metrics,
+    };
+    let reports: Vec<_> = scenarios.iter().map(|s| backtester.run(&cases, s)).collect();
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&serde_json::json!({
+            "from": from,
+            "to": to,
+            "cases": cases.len(),
+            "default_weights": default_weights,
+            "scenarios": reports,
+        }))?
+    );
+    Ok(())
+}
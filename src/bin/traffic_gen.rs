@@ -0,0 +1,364 @@
+use anyhow::Result;
+use payments_gateway::metrics::window::percentile;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, Semaphore};
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+const METHOD_MIX: [(&str, u32); 3] = [("UPI", 60), ("CARD", 30), ("NETBANKING", 10)];
+const AMOUNT_MIX: [((i64, i64), u32); 4] = [
+    ((1_000, 49_999), 50),
+    ((50_000, 199_999), 30),
+    ((200_000, 999_999), 15),
+    ((1_000_000, 5_000_000), 5),
+];
+const UPI_HANDLES: [(&str, u32); 6] = [
+    ("okhdfcbank", 30),
+    ("okicici", 20),
+    ("okaxis", 15),
+    ("oksbi", 20),
+    ("ybl", 10),
+    ("paytm", 5),
+];
+const CARD_BINS: [(&str, u32); 5] = [
+    ("411111", 35),
+    ("555555", 25),
+    ("444433", 20),
+    ("400000", 10),
+    ("607384", 10),
+];
+const NETBANKING_BANKS: [(&str, u32); 5] = [("HDFC", 30), ("ICICI", 25), ("SBI", 25), ("AXIS", 15), ("KOTAK", 5)];
+
+#[derive(Debug, Clone)]
+struct TrafficConfig {
+    target_url: String,
+    rps: f64,
+    duration_secs: u64,
+    concurrency: usize,
+    customers: u32,
+    merchants: u32,
+    client_ips: u32,
+    idempotency_retry_pct: f64,
+    internal_api_key: Option<String>,
+}
+
+impl TrafficConfig {
+    fn from_env() -> Self {
+        Self {
+            target_url: std::env::var("TRAFFIC_TARGET_URL").unwrap_or_else(|_| "http://localhost:3000".to_string()),
+            rps: env_parse("TRAFFIC_RPS", 20.0_f64).max(0.1),
+            duration_secs: env_parse("TRAFFIC_DURATION_SECS", 60_u64),
+            concurrency: env_parse("TRAFFIC_CONCURRENCY", 64_usize).max(1),
+            customers: env_parse("TRAFFIC_CUSTOMERS", 1_000_u32).max(1),
+            merchants: env_parse("TRAFFIC_MERCHANTS", 5_u32).max(1),
+            client_ips: env_parse("TRAFFIC_CLIENT_IPS", 250_u32).max(1),
+            idempotency_retry_pct: env_parse("TRAFFIC_IDEMPOTENCY_RETRY_PCT", 0.05_f64).clamp(0.0, 1.0),
+            internal_api_key: std::env::var("INTERNAL_API_KEY").ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SentRequest {
+    idempotency_key: String,
+    client_ip: String,
+    body: serde_json::Value,
+    payment_id: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Stats {
+    sent: u64,
+    replays: u64,
+    replay_mismatches: u64,
+    transport_errors: u64,
+    latencies_ms: Vec<i32>,
+    http_status: BTreeMap<u16, u64>,
+    payment_status: BTreeMap<String, u64>,
+    error_codes: BTreeMap<String, u64>,
+    gateway_used: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    target_url: String,
+    configured_rps: f64,
+    elapsed_secs: f64,
+    sent: u64,
+    completed: u64,
+    throughput_rps: f64,
+    idempotency_replays: u64,
+    idempotency_replay_mismatches: u64,
+    transport_errors: u64,
+    latency_ms: LatencyReport,
+    http_status: BTreeMap<u16, u64>,
+    payment_status: BTreeMap<String, u64>,
+    error_codes: BTreeMap<String, u64>,
+    gateway_share: BTreeMap<String, GatewayShare>,
+}
+
+#[derive(Debug, Serialize)]
+struct LatencyReport {
+    p50: i32,
+    p90: i32,
+    p95: i32,
+    p99: i32,
+    max: i32,
+}
+
+#[derive(Debug, Serialize)]
+struct GatewayShare {
+    count: u64,
+    share: f64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let cfg = TrafficConfig::from_env();
+    tracing::info!(
+        "traffic_gen target={} rps={} duration_secs={} customers={}",
+        cfg.target_url,
+        cfg.rps,
+        cfg.duration_secs,
+        cfg.customers
+    );
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let stats = Arc::new(Mutex::new(Stats::default()));
+    let history: Arc<Mutex<VecDeque<SentRequest>>> = Arc::new(Mutex::new(VecDeque::new()));
+    let permits = Arc::new(Semaphore::new(cfg.concurrency));
+
+    let started = Instant::now();
+    let deadline = started + Duration::from_secs(cfg.duration_secs);
+    let mut ticker = tokio::time::interval(Duration::from_secs_f64(1.0 / cfg.rps));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut last_progress = Instant::now();
+
+    while Instant::now() < deadline {
+        ticker.tick().await;
+
+        let replay = if rand::thread_rng().gen_bool(cfg.idempotency_retry_pct) {
+            let recent = history.lock().await;
+            let idx = if recent.is_empty() {
+                None
+            } else {
+                Some(rand::thread_rng().gen_range(0..recent.len()))
+            };
+            idx.and_then(|i| recent.get(i).cloned())
+        } else {
+            None
+        };
+        let is_replay = replay.is_some();
+        let request = replay.unwrap_or_else(|| generate_request(&cfg));
+
+        let permit = permits.clone().acquire_owned().await?;
+        let client = client.clone();
+        let url = format!("{}/payments", cfg.target_url.trim_end_matches('/'));
+        let api_key = cfg.internal_api_key.clone();
+        let task_stats = stats.clone();
+        let task_history = history.clone();
+
+        tokio::spawn(async move {
+            let _permit = permit;
+            let sent_at = Instant::now();
+            let mut http_req = client
+                .post(&url)
+                .header("Idempotency-Key", &request.idempotency_key)
+                .header("X-Forwarded-For", &request.client_ip)
+                .header("User-Agent", "payments-gateway-traffic-gen/0.1")
+                .json(&request.body);
+            if let Some(key) = &api_key {
+                http_req = http_req.header("X-Internal-Api-Key", key);
+            }
+            let resp = http_req.send().await;
+            let latency = sent_at.elapsed().as_millis() as i32;
+            let outcome = match resp {
+                Ok(r) => {
+                    let status = r.status().as_u16();
+                    Ok((status, r.json::<serde_json::Value>().await.unwrap_or_default()))
+                }
+                Err(e) => Err(e),
+            };
+
+            let mut s = task_stats.lock().await;
+            s.sent += 1;
+            if is_replay {
+                s.replays += 1;
+            }
+
+            let (status, body) = match outcome {
+                Ok(v) => v,
+                Err(e) => {
+                    s.transport_errors += 1;
+                    tracing::debug!("request failed: {}", e);
+                    return;
+                }
+            };
+
+            s.latencies_ms.push(latency);
+            *s.http_status.entry(status).or_insert(0) += 1;
+
+            if let Some(payment_status) = body.get("status").and_then(|v| v.as_str()) {
+                *s.payment_status.entry(payment_status.to_string()).or_insert(0) += 1;
+            }
+            if let Some(code) = body.pointer("/error/code").and_then(|v| v.as_str()) {
+                *s.error_codes.entry(code.to_string()).or_insert(0) += 1;
+            }
+            if let Some(gateway) = body.get("gateway_used").and_then(|v| v.as_str()) {
+                if !is_replay {
+                    *s.gateway_used.entry(gateway.to_string()).or_insert(0) += 1;
+                }
+            }
+
+            let payment_id = body
+                .get("payment_id")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            if is_replay {
+                if request.payment_id.is_some() && payment_id != request.payment_id {
+                    s.replay_mismatches += 1;
+                }
+                return;
+            }
+            drop(s);
+
+            if payment_id.is_some() {
+                let mut recent = task_history.lock().await;
+                recent.push_back(SentRequest {
+                    payment_id,
+                    ..request
+                });
+                if recent.len() > 1_000 {
+                    recent.pop_front();
+                }
+            }
+        });
+
+        if last_progress.elapsed() >= Duration::from_secs(10) {
+            let s = stats.lock().await;
+            tracing::info!(
+                "progress elapsed_secs={} completed={} transport_errors={}",
+                started.elapsed().as_secs(),
+                s.sent,
+                s.transport_errors
+            );
+            last_progress = Instant::now();
+        }
+    }
+
+    let _ = permits.acquire_many(cfg.concurrency as u32).await?;
+    let elapsed = started.elapsed().as_secs_f64();
+    let mut s = stats.lock().await;
+    let report = build_report(&cfg, &mut s, elapsed);
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+fn generate_request(cfg: &TrafficConfig) -> SentRequest {
+    let mut rng = rand::thread_rng();
+    let method = weighted(&METHOD_MIX);
+    let (low, high) = weighted(&AMOUNT_MIX);
+    let amount_minor = rng.gen_range(low..=high);
+
+    let instrument = match method {
+        "UPI" => serde_json::json!({
+            "type": "UPI",
+            "vpa": format!("user{}@{}", rng.gen_range(0..100_000), weighted(&UPI_HANDLES)),
+        }),
+        "CARD" => serde_json::json!({
+            "type": "CARD",
+            "number": format!("{}{:010}", weighted(&CARD_BINS), rng.gen_range(0..10_000_000_000_u64)),
+            "exp_month": rng.gen_range(1..=12),
+            "exp_year": rng.gen_range(2027..=2032),
+            "cvv": format!("{:03}", rng.gen_range(0..1000)),
+            "name": "Load Test",
+        }),
+        _ => serde_json::json!({
+            "type": "NETBANKING",
+            "bank_code": weighted(&NETBANKING_BANKS),
+        }),
+    };
+
+    let body = serde_json::json!({
+        "amount_minor": amount_minor,
+        "currency": "INR",
+        "payment_method": method,
+        "merchant_id": format!("m_{:03}", rng.gen_range(1..=cfg.merchants)),
+        "customer_id": format!("cust_{:06}", rng.gen_range(0..cfg.customers)),
+        "instrument": instrument,
+    });
+
+    let client = rng.gen_range(0..cfg.client_ips);
+    SentRequest {
+        idempotency_key: format!("tg-{}", Uuid::new_v4()),
+        client_ip: format!("10.{}.{}.{}", (client >> 16) & 0xff, (client >> 8) & 0xff, client & 0xff),
+        body,
+        payment_id: None,
+    }
+}
+
+fn weighted<T: Copy>(items: &[(T, u32)]) -> T {
+    items
+        .choose_weighted(&mut rand::thread_rng(), |item| item.1)
+        .map(|item| item.0)
+        .unwrap_or(items[0].0)
+}
+
+fn build_report(cfg: &TrafficConfig, s: &mut Stats, elapsed_secs: f64) -> Report {
+    s.latencies_ms.sort_unstable();
+    let completed = s.latencies_ms.len() as u64;
+    let routed: u64 = s.gateway_used.values().sum();
+
+    Report {
+        target_url: cfg.target_url.clone(),
+        configured_rps: cfg.rps,
+        elapsed_secs,
+        sent: s.sent,
+        completed,
+        throughput_rps: if elapsed_secs > 0.0 { completed as f64 / elapsed_secs } else { 0.0 },
+        idempotency_replays: s.replays,
+        idempotency_replay_mismatches: s.replay_mismatches,
+        transport_errors: s.transport_errors,
+        latency_ms: LatencyReport {
+            p50: percentile(&s.latencies_ms, 0.50),
+            p90: percentile(&s.latencies_ms, 0.90),
+            p95: percentile(&s.latencies_ms, 0.95),
+            p99: percentile(&s.latencies_ms, 0.99),
+            max: s.latencies_ms.last().copied().unwrap_or(0),
+        },
+        http_status: s.http_status.clone(),
+        payment_status: s.payment_status.clone(),
+        error_codes: s.error_codes.clone(),
+        gateway_share: s
+            .gateway_used
+            .iter()
+            .map(|(gateway, count)| {
+                (
+                    gateway.clone(),
+                    GatewayShare {
+                        count: *count,
+                        share: if routed > 0 { *count as f64 / routed as f64 } else { 0.0 },
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse::<T>().ok())
+        .unwrap_or(default)
+}
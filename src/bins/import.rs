@@ -0,0 +1,85 @@
+use crate::bins::index::{BinRecord, CardType, CARD_NETWORKS};
+use std::collections::HashMap;
+
+pub const CSV_HEADER: &str = "bin_start,bin_end,card_network,card_type,issuer_country,issuer_id";
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ImportError {
+    pub line: usize,
+    pub error: String,
+}
+
+pub fn parse_csv(text: &str) -> (Vec<BinRecord>, Vec<ImportError>) {
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    let mut seen: HashMap<(String, String), usize> = HashMap::new();
+    for (idx, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if idx == 0 && line.eq_ignore_ascii_case(CSV_HEADER) {
+            continue;
+        }
+        match parse_line(line) {
+            Ok(record) => {
+                let key = (record.bin_start.clone(), record.bin_end.clone());
+                if let Some(first) = seen.insert(key, idx + 1) {
+                    errors.push(ImportError {
+                        line: idx + 1,
+                        error: format!("duplicate BIN range, first seen on line {}", first),
+                    });
+                    continue;
+                }
+                records.push(record)
+            }
+            Err(error) => errors.push(ImportError { line: idx + 1, error }),
+        }
+    }
+    (records, errors)
+}
+
+pub fn parse_line(line: &str) -> Result<BinRecord, String> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    if fields.len() != 6 {
+        return Err(format!("expected 6 columns, got {}", fields.len()));
+    }
+    let bin_start = fields[0].to_string();
+    let bin_end = if fields[1].is_empty() { bin_start.clone() } else { fields[1].to_string() };
+    validate_bin(&bin_start)?;
+    validate_bin(&bin_end)?;
+    if bin_start.len() != bin_end.len() {
+        return Err("bin_start and bin_end must have the same length".to_string());
+    }
+    if bin_start > bin_end {
+        return Err("bin_start must not be greater than bin_end".to_string());
+    }
+
+    let card_network = fields[2].to_uppercase();
+    if !CARD_NETWORKS.contains(&card_network.as_str()) {
+        return Err(format!("unknown card_network {}", fields[2]));
+    }
+    let card_type = CardType::parse(fields[3]).ok_or_else(|| format!("unknown card_type {}", fields[3]))?;
+    let issuer_country = match fields[4] {
+        "" => None,
+        c if c.len() == 2 && c.chars().all(|ch| ch.is_ascii_alphabetic()) => Some(c.to_uppercase()),
+        c => return Err(format!("issuer_country {} must be an ISO 3166 alpha-2 code", c)),
+    };
+    let issuer_id = Some(fields[5].to_uppercase()).filter(|s| !s.is_empty());
+
+    Ok(BinRecord {
+        bin_start,
+        bin_end,
+        card_network,
+        card_type,
+        issuer_country,
+        issuer_id,
+    })
+}
+
+fn validate_bin(bin: &str) -> Result<(), String> {
+    if !(bin.len() == 6 || bin.len() == 8) || !bin.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("BIN {} must be 6 or 8 digits", bin));
+    }
+    Ok(())
+}
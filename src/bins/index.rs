@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+pub const BIN_LENGTHS: [usize; 2] = [8, 6];
+pub const CARD_NETWORKS: [&str; 5] = ["VISA", "MASTERCARD", "RUPAY", "AMEX", "DINERS"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CardType {
+    Credit,
+    Debit,
+    Prepaid,
+}
+
+impl CardType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardType::Credit => "CREDIT",
+            CardType::Debit => "DEBIT",
+            CardType::Prepaid => "PREPAID",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_uppercase().as_str() {
+            "CREDIT" => Some(CardType::Credit),
+            "DEBIT" => Some(CardType::Debit),
+            "PREPAID" => Some(CardType::Prepaid),
+            _ => None,
+        }
+    }
+}
+
+pub fn validate_card_filters(card_network: Option<&str>, card_type: Option<&str>) -> Result<(), String> {
+    if let Some(network) = card_network {
+        if !CARD_NETWORKS.contains(&network.to_uppercase().as_str()) {
+            return Err(format!("card_network must be one of {:?}", CARD_NETWORKS));
+        }
+    }
+    if let Some(card_type) = card_type {
+        if CardType::parse(card_type).is_none() {
+            return Err("card_type must be CREDIT, DEBIT or PREPAID".to_string());
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CardAttributes {
+    pub card_network: Option<String>,
+    pub card_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BinRecord {
+    pub bin_start: String,
+    pub bin_end: String,
+    pub card_network: String,
+    pub card_type: CardType,
+    pub issuer_country: Option<String>,
+    pub issuer_id: Option<String>,
+}
+
+impl BinRecord {
+    fn bounds(&self) -> Option<(u64, u64)> {
+        Some((self.bin_start.parse().ok()?, self.bin_end.parse().ok()?))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct LengthIndex {
+    starts: Vec<u64>,
+    ends: Vec<u64>,
+    max_end: Vec<u64>,
+    records: Vec<BinRecord>,
+}
+
+impl LengthIndex {
+    fn build(mut entries: Vec<(u64, u64, BinRecord)>) -> Self {
+        entries.sort_by_key(|(start, end, _)| (*start, *end));
+        let mut out = LengthIndex::default();
+        let mut running_max = 0;
+        for (start, end, record) in entries {
+            running_max = running_max.max(end);
+            out.starts.push(start);
+            out.ends.push(end);
+            out.max_end.push(running_max);
+            out.records.push(record);
+        }
+        out
+    }
+
+    fn lookup(&self, key: u64) -> Option<&BinRecord> {
+        let upto = self.starts.partition_point(|start| *start <= key);
+        let mut best: Option<usize> = None;
+        for i in (0..upto).rev() {
+            if self.max_end[i] < key {
+                break;
+            }
+            if self.ends[i] >= key
+                && best.is_none_or(|b| self.ends[i] - self.starts[i] < self.ends[b] - self.starts[b])
+            {
+                best = Some(i);
+            }
+        }
+        best.map(|i| &self.records[i])
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BinIndex {
+    eight: LengthIndex,
+    six: LengthIndex,
+    len: usize,
+}
+
+impl BinIndex {
+    pub fn build(records: Vec<BinRecord>) -> Self {
+        let mut eight = Vec::new();
+        let mut six = Vec::new();
+        for record in records {
+            let Some((start, end)) = record.bounds() else {
+                continue;
+            };
+            match record.bin_start.len() {
+                8 => eight.push((start, end, record)),
+                6 => six.push((start, end, record)),
+                _ => {}
+            }
+        }
+        let len = eight.len() + six.len();
+        Self {
+            eight: LengthIndex::build(eight),
+            six: LengthIndex::build(six),
+            len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn lookup(&self, card_number: &str) -> Option<&BinRecord> {
+        let digits: String = card_number.chars().filter(|c| c.is_ascii_digit()).take(8).collect();
+        for len in BIN_LENGTHS {
+            let Some(key) = digits.get(..len).and_then(|p| p.parse::<u64>().ok()) else {
+                continue;
+            };
+            let index = if len == 8 { &self.eight } else { &self.six };
+            if let Some(record) = index.lookup(key) {
+                return Some(record);
+            }
+        }
+        None
+    }
+}
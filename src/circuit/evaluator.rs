@@ -25,3 +25,15 @@ pub fn pre_call_decision(
         }
     }
 }
+
+pub fn rejects_all_calls(
+    override_state: Option<&str>,
+    snapshot: &CircuitSnapshot,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    match override_state {
+        Some("FORCE_OPEN") => true,
+        Some("FORCE_CLOSED") => false,
+        _ => snapshot.state == CircuitState::Open && snapshot.cooldown_until.is_none_or(|t| now < t),
+    }
+}
@@ -1,16 +1,35 @@
 use crate::circuit::state::CircuitSnapshot;
+use crate::redis_conn::SharedRedis;
 use anyhow::Result;
 use chrono::Timelike;
 use redis::AsyncCommands;
+use std::collections::HashMap;
+
+pub const FAILURE_WINDOW_MINUTES: usize = 2;
+pub const TIMEOUT_WINDOW_MINUTES: usize = 5;
+const STATS_TTL_SECS: i64 = 600;
 
 #[derive(Clone)]
 pub struct CircuitStoreRedis {
-    pub client: redis::Client,
+    pub redis: SharedRedis,
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitView {
+    pub override_state: Option<String>,
+    pub snapshot: CircuitSnapshot,
+}
+
+#[derive(Debug, Clone)]
+pub struct CircuitObservation {
+    pub snapshot: CircuitSnapshot,
+    pub failure_rate_2m: f64,
+    pub timeout_rate_5m: f64,
 }
 
 impl CircuitStoreRedis {
-    pub fn new(client: redis::Client) -> Self {
-        Self { client }
+    pub fn new(redis: SharedRedis) -> Self {
+        Self { redis }
     }
 
     fn state_key(gateway_id: &str, method: &str) -> String {
@@ -30,73 +49,102 @@ impl CircuitStoreRedis {
         format!("circuit:manual_override:{}:{}", gateway_id.to_lowercase(), method.to_lowercase())
     }
 
+    fn window_keys(gateway_id: &str, method: &str, minutes: usize, now: chrono::DateTime<chrono::Utc>) -> Vec<String> {
+        let current = now.timestamp() - (now.second() as i64);
+        (0..minutes as i64)
+            .map(|i| Self::minute_stats_key(gateway_id, method, current - (i * 60)))
+            .collect()
+    }
+
     pub async fn get_snapshot(&self, gateway_id: &str, method: &str) -> Result<CircuitSnapshot> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.conn().await?;
         let key = Self::state_key(gateway_id, method);
         let payload: Option<String> = conn.get(key).await?;
-        if let Some(payload) = payload {
-            let parsed = serde_json::from_str::<CircuitSnapshot>(&payload)?;
-            return Ok(parsed);
+        parse_snapshot(payload, gateway_id, method)
+    }
+
+    pub async fn read_states(&self, method: &str, gateway_ids: &[String]) -> Result<HashMap<String, CircuitView>> {
+        if gateway_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let keys = Self::view_keys(method, gateway_ids);
+        let mut conn = self.redis.conn().await?;
+        let values: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+        Self::views_from_values(method, gateway_ids, &values)
+    }
+
+    pub fn view_keys(method: &str, gateway_ids: &[String]) -> Vec<String> {
+        gateway_ids
+            .iter()
+            .flat_map(|g| [Self::override_key(g, method), Self::state_key(g, method)])
+            .collect()
+    }
+
+    pub fn views_from_values(
+        method: &str,
+        gateway_ids: &[String],
+        values: &[Option<String>],
+    ) -> Result<HashMap<String, CircuitView>> {
+        let mut out = HashMap::new();
+        for (gateway_id, pair) in gateway_ids.iter().zip(values.chunks(2)) {
+            out.insert(
+                gateway_id.clone(),
+                CircuitView {
+                    override_state: pair[0].clone(),
+                    snapshot: parse_snapshot(pair.get(1).cloned().flatten(), gateway_id, method)?,
+                },
+            );
         }
-        Ok(CircuitSnapshot::new(gateway_id, method))
+        Ok(out)
     }
 
     pub async fn save_snapshot(&self, snapshot: &CircuitSnapshot) -> Result<()> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.conn().await?;
         let key = Self::state_key(&snapshot.gateway_id, &snapshot.payment_method);
         let payload = serde_json::to_string(snapshot)?;
         let _: () = conn.set(key, payload).await?;
         Ok(())
     }
 
-    pub async fn write_result(
+    pub async fn record_result(
         &self,
         gateway_id: &str,
         method: &str,
         status: &str,
         now: chrono::DateTime<chrono::Utc>,
-    ) -> Result<()> {
-        let minute = now.timestamp() - (now.second() as i64);
-        let key = Self::minute_stats_key(gateway_id, method, minute);
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let _: f64 = redis::cmd("HINCRBYFLOAT")
-            .arg(&key)
-            .arg("total")
-            .arg(1.0)
-            .query_async(&mut conn)
-            .await?;
-
-        if status == "SUCCESS" {
-            let _: f64 = redis::cmd("HINCRBYFLOAT")
-                .arg(&key)
-                .arg("success")
-                .arg(1.0)
-                .query_async(&mut conn)
-                .await?;
-        } else if status == "TIMEOUT" {
-            let _: f64 = redis::cmd("HINCRBYFLOAT")
-                .arg(&key)
-                .arg("failed")
-                .arg(1.0)
-                .query_async(&mut conn)
-                .await?;
-            let _: f64 = redis::cmd("HINCRBYFLOAT")
-                .arg(&key)
-                .arg("timeout")
-                .arg(1.0)
-                .query_async(&mut conn)
-                .await?;
-        } else {
-            let _: f64 = redis::cmd("HINCRBYFLOAT")
-                .arg(&key)
-                .arg("failed")
-                .arg(1.0)
-                .query_async(&mut conn)
-                .await?;
+    ) -> Result<CircuitObservation> {
+        let window_keys = Self::window_keys(gateway_id, method, TIMEOUT_WINDOW_MINUTES, now);
+        let current_key = &window_keys[0];
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for field in result_fields(status) {
+            pipe.cmd("HINCRBYFLOAT").arg(current_key).arg(*field).arg(1.0).ignore();
+        }
+        pipe.expire(current_key, STATS_TTL_SECS).ignore();
+        pipe.get(Self::state_key(gateway_id, method));
+        for key in &window_keys {
+            pipe.hgetall(key);
         }
 
-        let _: bool = conn.expire(&key, 600).await?;
-        Ok(())
+        let mut conn = self.redis.conn().await?;
+        let values: Vec<redis::Value> = pipe.query_async(&mut conn).await?;
+        let mut values = values.into_iter();
+        let payload: Option<String> = match values.next() {
+            Some(v) => redis::from_redis_value(&v)?,
+            None => None,
+        };
+        let buckets = values
+            .map(|v| redis::from_redis_value::<HashMap<String, String>>(&v))
+            .collect::<redis::RedisResult<Vec<_>>>()?;
+
+        let (failure_rate_2m, _) = window_rates(&buckets[..FAILURE_WINDOW_MINUTES.min(buckets.len())]);
+        let (_, timeout_rate_5m) = window_rates(&buckets);
+        Ok(CircuitObservation {
+            snapshot: parse_snapshot(payload, gateway_id, method)?,
+            failure_rate_2m,
+            timeout_rate_5m,
+        })
     }
 
     pub async fn aggregate_window(
@@ -106,45 +154,65 @@ impl CircuitStoreRedis {
         minutes: i64,
         now: chrono::DateTime<chrono::Utc>,
     ) -> Result<(f64, f64)> {
-        let mut total = 0.0;
-        let mut failed = 0.0;
-        let mut timeout = 0.0;
-
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let current = now.timestamp() - (now.second() as i64);
-        for i in 0..minutes {
-            let key = Self::minute_stats_key(gateway_id, method, current - (i * 60));
-            let values: std::collections::HashMap<String, String> = conn.hgetall(key).await.unwrap_or_default();
-            total += values.get("total").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
-            failed += values.get("failed").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
-            timeout += values.get("timeout").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
-        }
-
-        if total <= 0.0 {
+        let keys = Self::window_keys(gateway_id, method, minutes.max(0) as usize, now);
+        if keys.is_empty() {
             return Ok((0.0, 0.0));
         }
-
-        Ok((failed / total, timeout / total))
+        let mut pipe = redis::pipe();
+        for key in &keys {
+            pipe.hgetall(key);
+        }
+        let mut conn = self.redis.conn().await?;
+        let buckets: Vec<HashMap<String, String>> = pipe.query_async(&mut conn).await?;
+        Ok(window_rates(&buckets))
     }
 
     pub async fn get_override(&self, gateway_id: &str, method: &str) -> Result<Option<String>> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.conn().await?;
         let key = Self::override_key(gateway_id, method);
         let val: Option<String> = conn.get(key).await?;
         Ok(val)
     }
 
     pub async fn set_override(&self, gateway_id: &str, method: &str, value: &str) -> Result<()> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.conn().await?;
         let key = Self::override_key(gateway_id, method);
         let _: () = conn.set(key, value).await?;
         Ok(())
     }
 
     pub async fn clear_override(&self, gateway_id: &str, method: &str) -> Result<()> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.conn().await?;
         let key = Self::override_key(gateway_id, method);
         let _: usize = conn.del(key).await?;
         Ok(())
     }
 }
+
+pub fn result_fields(status: &str) -> &'static [&'static str] {
+    match status {
+        "SUCCESS" => &["total", "success"],
+        "TIMEOUT" => &["total", "failed", "timeout"],
+        _ => &["total", "failed"],
+    }
+}
+
+pub fn window_rates(buckets: &[HashMap<String, String>]) -> (f64, f64) {
+    let field = |bucket: &HashMap<String, String>, name: &str| {
+        bucket.get(name).and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0)
+    };
+    let total: f64 = buckets.iter().map(|b| field(b, "total")).sum();
+    if total <= 0.0 {
+        return (0.0, 0.0);
+    }
+    let failed: f64 = buckets.iter().map(|b| field(b, "failed")).sum();
+    let timeout: f64 = buckets.iter().map(|b| field(b, "timeout")).sum();
+    (failed / total, timeout / total)
+}
+
+fn parse_snapshot(payload: Option<String>, gateway_id: &str, method: &str) -> Result<CircuitSnapshot> {
+    match payload {
+        Some(payload) => Ok(serde_json::from_str::<CircuitSnapshot>(&payload)?),
+        None => Ok(CircuitSnapshot::new(gateway_id, method)),
+    }
+}
@@ -12,6 +12,145 @@ pub struct Experiment {
     pub start_date: chrono::DateTime<chrono::Utc>,
     pub end_date: Option<chrono::DateTime<chrono::Utc>>,
     pub created_by: String,
+    #[serde(default)]
+    pub variants: Vec<ExperimentVariant>,
+    #[serde(default = "full_exposure")]
+    pub exposure_pct: i32,
+    #[serde(default)]
+    pub ramp_schedule: Vec<RampStep>,
+    #[serde(default)]
+    pub ramp_step: Option<i32>,
+    #[serde(default)]
+    pub ramped_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default = "fixed_horizon")]
+    pub analysis_method: String,
+}
+
+fn full_exposure() -> i32 {
+    100
+}
+
+fn fixed_horizon() -> String {
+    AnalysisMethod::FixedHorizon.as_str().to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExperimentStatus {
+    Scheduled,
+    Running,
+    Paused,
+    Completed,
+    Stopped,
+}
+
+impl ExperimentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExperimentStatus::Scheduled => "SCHEDULED",
+            ExperimentStatus::Running => "RUNNING",
+            ExperimentStatus::Paused => "PAUSED",
+            ExperimentStatus::Completed => "COMPLETED",
+            ExperimentStatus::Stopped => "STOPPED",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "SCHEDULED" => Some(ExperimentStatus::Scheduled),
+            "RUNNING" => Some(ExperimentStatus::Running),
+            "PAUSED" => Some(ExperimentStatus::Paused),
+            "COMPLETED" => Some(ExperimentStatus::Completed),
+            "STOPPED" => Some(ExperimentStatus::Stopped),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, ExperimentStatus::Completed | ExperimentStatus::Stopped)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AnalysisMethod {
+    #[default]
+    FixedHorizon,
+    Sequential,
+    Bayesian,
+}
+
+impl AnalysisMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnalysisMethod::FixedHorizon => "FIXED_HORIZON",
+            AnalysisMethod::Sequential => "SEQUENTIAL",
+            AnalysisMethod::Bayesian => "BAYESIAN",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "FIXED_HORIZON" => Some(AnalysisMethod::FixedHorizon),
+            "SEQUENTIAL" => Some(AnalysisMethod::Sequential),
+            "BAYESIAN" => Some(AnalysisMethod::Bayesian),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RampStep {
+    pub exposure_pct: i32,
+    #[serde(default)]
+    pub hold_minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    pub event_id: i64,
+    pub experiment_id: Uuid,
+    pub action: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub exposure_pct: i32,
+    pub reason: Option<String>,
+    pub actor: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentVariant {
+    pub variant_name: String,
+    pub traffic_pct: i32,
+    #[serde(default)]
+    pub override_gateway: Option<String>,
+    #[serde(default)]
+    pub scoring_profile_id: Option<String>,
+    #[serde(default)]
+    pub bandit_enabled: Option<bool>,
+    #[serde(default)]
+    pub rule_set: Option<String>,
+    #[serde(default)]
+    pub retry_policy: Option<VariantRetryPolicy>,
+}
+
+impl ExperimentVariant {
+    pub fn changes_routing(&self) -> bool {
+        self.override_gateway.is_some()
+            || self.scoring_profile_id.is_some()
+            || self.bandit_enabled.is_some()
+            || self.rule_set.is_some()
+            || self.retry_policy.is_some()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantRetryPolicy {
+    pub max_attempts: i32,
+    pub latency_budget_ms: i32,
+    #[serde(default)]
+    pub retry_on_timeout: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +161,10 @@ pub struct ExperimentFilter {
     pub max_amount_minor: Option<i64>,
     pub merchant_id: Option<String>,
     pub amount_bucket: Option<String>,
+    #[serde(default)]
+    pub card_network: Option<String>,
+    #[serde(default)]
+    pub card_type: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
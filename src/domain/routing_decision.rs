@@ -1,4 +1,7 @@
+use crate::bandit::policy::BanditAlgorithm;
+use crate::bandit::segments::{BanditSegment, DEFAULT_DEFINITION_ID};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,5 +15,72 @@ pub struct RoutingDecisionRecord {
     pub reason_summary: String,
     pub score_breakdown_json: serde_json::Value,
     pub ranked_gateways_json: serde_json::Value,
+    pub matched_rule_id: Option<Uuid>,
+    pub matched_rule_ids: serde_json::Value,
+    pub dropped_gateways_json: serde_json::Value,
+    #[serde(default)]
+    pub penalized_gateways_json: serde_json::Value,
+    pub scoring_profile_id: Option<String>,
+    pub scoring_profile_version: Option<i32>,
+    pub bandit_propensity: Option<f64>,
+    pub bandit_selection_json: Option<serde_json::Value>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedGateway {
+    pub gateway_id: String,
+    pub reason: String,
+    pub maintenance_window_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PenalizedGateway {
+    pub gateway_id: String,
+    pub maintenance_window_id: Uuid,
+    pub penalty_multiplier: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanditSelection {
+    #[serde(default = "default_definition_id")]
+    pub definition_id: String,
+    pub segment: String,
+    #[serde(default)]
+    pub state_definition_id: Option<String>,
+    #[serde(default)]
+    pub state_segment: Option<String>,
+    pub algorithm: BanditAlgorithm,
+    pub chosen_gateway: String,
+    pub scores: HashMap<String, f64>,
+    pub propensities: HashMap<String, f64>,
+    #[serde(default = "default_explored")]
+    pub explored: bool,
+    #[serde(default)]
+    pub excluded: Vec<String>,
+}
+
+fn default_explored() -> bool {
+    true
+}
+
+fn default_definition_id() -> String {
+    DEFAULT_DEFINITION_ID.to_string()
+}
+
+impl BanditSelection {
+    pub fn propensity(&self) -> f64 {
+        self.propensities.get(&self.chosen_gateway).copied().unwrap_or(0.0)
+    }
+
+    pub fn policy_segment(&self) -> BanditSegment {
+        BanditSegment::new(&self.definition_id, &self.segment)
+    }
+
+    pub fn state_segment(&self) -> BanditSegment {
+        BanditSegment::new(
+            self.state_definition_id.as_deref().unwrap_or(&self.definition_id),
+            self.state_segment.as_deref().unwrap_or(&self.segment),
+        )
+    }
+}
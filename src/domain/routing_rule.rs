@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_RULE_SET: &str = "default";
+
+pub fn default_rule_set() -> String {
+    DEFAULT_RULE_SET.to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RuleAction {
+    Force,
+    Exclude,
+    Boost,
+}
+
+impl RuleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleAction::Force => "FORCE",
+            RuleAction::Exclude => "EXCLUDE",
+            RuleAction::Boost => "BOOST",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_uppercase().as_str() {
+            "FORCE" => Some(RuleAction::Force),
+            "EXCLUDE" => Some(RuleAction::Exclude),
+            "BOOST" => Some(RuleAction::Boost),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RuleConditions {
+    pub payment_method: Option<String>,
+    pub merchant_id: Option<String>,
+    pub issuing_bank: Option<String>,
+    pub min_amount_minor: Option<i64>,
+    pub max_amount_minor: Option<i64>,
+    pub amount_bucket: Option<String>,
+    #[serde(default)]
+    pub card_network: Option<String>,
+    #[serde(default)]
+    pub card_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingRule {
+    pub rule_id: Uuid,
+    pub name: String,
+    pub priority: i32,
+    pub enabled: bool,
+    pub conditions: RuleConditions,
+    pub action: RuleAction,
+    pub gateway_id: String,
+    pub boost: Option<f64>,
+    #[serde(default = "default_rule_set")]
+    pub rule_set: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
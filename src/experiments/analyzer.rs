@@ -1,4 +1,29 @@
-use crate::domain::experiment::ExperimentResultRow;
+use crate::domain::experiment::{AnalysisMethod, ExperimentResultRow};
+use crate::experiments::assigner::CONTROL_VARIANT;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Beta, Distribution};
+use statrs::distribution::{ContinuousCDF, Normal};
+
+pub const SIGNIFICANCE_LEVEL: f64 = 0.05;
+pub const MSPRT_MIXING_VARIANCE: f64 = 0.0001;
+pub const PROBABILITY_TO_BEAT_THRESHOLD: f64 = 0.95;
+pub const EXPECTED_LOSS_THRESHOLD: f64 = 0.001;
+const POSTERIOR_DRAWS: usize = 20_000;
+const POSTERIOR_SEED: u64 = 42;
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct VariantComparison {
+    pub variant: String,
+    pub total_requests: i64,
+    pub success_rate: f64,
+    pub z_score: f64,
+    pub p_value: f64,
+    pub adjusted_p_value: f64,
+    pub is_significant: bool,
+    pub probability_to_beat_control: Option<f64>,
+    pub expected_loss: Option<f64>,
+}
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct WinnerAnalysis {
@@ -9,6 +34,11 @@ pub struct WinnerAnalysis {
     pub is_significant: bool,
     pub winner: Option<String>,
     pub recommendation: String,
+    pub method: String,
+    pub correction: String,
+    pub probability_to_beat_control: Option<f64>,
+    pub expected_loss: Option<f64>,
+    pub comparisons: Vec<VariantComparison>,
 }
 
 #[derive(Debug, Clone)]
@@ -32,129 +62,268 @@ impl Default for GuardrailConfig {
 pub struct GuardrailDecision {
     pub should_pause: bool,
     pub reason: Option<String>,
+    pub variant: Option<String>,
     pub control_success_rate: f64,
     pub treatment_success_rate: f64,
     pub control_p95_latency_ms: i32,
     pub treatment_p95_latency_ms: i32,
     pub treatment_total_requests: i64,
+    pub sufficient_samples: bool,
 }
 
-pub fn analyze(results: &[ExperimentResultRow], min_samples: i64) -> WinnerAnalysis {
-    let (c_total, c_success) = aggregate_variant(results, "control");
-    let (t_total, t_success) = aggregate_variant(results, "treatment");
+pub fn treatment_variants(results: &[ExperimentResultRow]) -> Vec<String> {
+    let mut variants: Vec<String> = results
+        .iter()
+        .map(|r| r.variant.clone())
+        .filter(|v| v != CONTROL_VARIANT)
+        .collect();
+    variants.sort();
+    variants.dedup();
+    variants
+}
 
-    if c_total < min_samples || t_total < min_samples || c_total == 0 || t_total == 0 {
-        return WinnerAnalysis {
-            control_success_rate: ratio(c_success, c_total),
-            treatment_success_rate: ratio(t_success, t_total),
-            z_score: 0.0,
-            p_value: 1.0,
-            is_significant: false,
-            winner: None,
-            recommendation: "insufficient sample size".to_string(),
-        };
+pub fn holm_adjust(p_values: &[f64]) -> Vec<f64> {
+    let m = p_values.len();
+    let mut order: Vec<usize> = (0..m).collect();
+    order.sort_by(|a, b| p_values[*a].total_cmp(&p_values[*b]));
+    let mut adjusted = vec![1.0; m];
+    let mut running = 0.0_f64;
+    for (rank, index) in order.into_iter().enumerate() {
+        running = running.max(((m - rank) as f64 * p_values[index]).min(1.0));
+        adjusted[index] = running;
     }
+    adjusted
+}
 
+fn compare(results: &[ExperimentResultRow], variant: &str, min_samples: i64) -> VariantComparison {
+    let (c_total, c_success) = aggregate_variant(results, CONTROL_VARIANT);
+    let (t_total, t_success) = aggregate_variant(results, variant);
     let p1 = ratio(c_success, c_total);
     let p2 = ratio(t_success, t_total);
+    let mut comparison = VariantComparison {
+        variant: variant.to_string(),
+        total_requests: t_total,
+        success_rate: p2,
+        z_score: 0.0,
+        p_value: 1.0,
+        adjusted_p_value: 1.0,
+        is_significant: false,
+        probability_to_beat_control: None,
+        expected_loss: None,
+    };
+    if c_total < min_samples || t_total < min_samples || c_total == 0 || t_total == 0 {
+        return comparison;
+    }
+
     let pooled = ratio(c_success + t_success, c_total + t_total);
     let se = (pooled * (1.0 - pooled) * ((1.0 / c_total as f64) + (1.0 / t_total as f64))).sqrt();
+    if se > 0.0 {
+        comparison.z_score = (p2 - p1) / se;
+        comparison.p_value = two_sided_p_value(comparison.z_score);
+    }
+    comparison
+}
 
-    if se == 0.0 {
-        return WinnerAnalysis {
-            control_success_rate: p1,
-            treatment_success_rate: p2,
-            z_score: 0.0,
-            p_value: 1.0,
-            is_significant: false,
-            winner: None,
-            recommendation: "unable to compute significance".to_string(),
-        };
+pub fn two_sided_p_value(z_score: f64) -> f64 {
+    (2.0 * Normal::standard().sf(z_score.abs())).min(1.0)
+}
+
+pub fn always_valid_p_value(results: &[ExperimentResultRow], variant: &str, min_samples: i64) -> f64 {
+    let mut hours: Vec<chrono::DateTime<chrono::Utc>> = results.iter().map(|r| r.date_hour).collect();
+    hours.sort();
+    hours.dedup();
+
+    let mut p_value = 1.0_f64;
+    let (mut c_total, mut c_success, mut t_total, mut t_success) = (0_i64, 0_i64, 0_i64, 0_i64);
+    for hour in hours {
+        for row in results.iter().filter(|r| r.date_hour == hour) {
+            if row.variant == CONTROL_VARIANT {
+                c_total += row.total_requests;
+                c_success += row.successful_requests;
+            } else if row.variant == variant {
+                t_total += row.total_requests;
+                t_success += row.successful_requests;
+            }
+        }
+        if c_total < min_samples || t_total < min_samples || c_total == 0 || t_total == 0 {
+            continue;
+        }
+        let p1 = ratio(c_success, c_total);
+        let p2 = ratio(t_success, t_total);
+        let variance = p1 * (1.0 - p1) / c_total as f64 + p2 * (1.0 - p2) / t_total as f64;
+        if variance <= 0.0 {
+            continue;
+        }
+        let tau = MSPRT_MIXING_VARIANCE;
+        let log_likelihood_ratio = 0.5 * (variance / (variance + tau)).ln()
+            + (p2 - p1).powi(2) * tau / (2.0 * variance * (variance + tau));
+        p_value = p_value.min((-log_likelihood_ratio).exp());
+    }
+    p_value
+}
+
+pub fn bayesian_comparison(results: &[ExperimentResultRow], variant: &str) -> (f64, f64) {
+    let mut rng = StdRng::seed_from_u64(POSTERIOR_SEED);
+    let control = posterior_draws(&mut rng, aggregate_variant(results, CONTROL_VARIANT));
+    let treatment = posterior_draws(&mut rng, aggregate_variant(results, variant));
+    let mut wins = 0_usize;
+    let mut loss = 0.0_f64;
+    for (c, t) in control.iter().zip(&treatment) {
+        if t > c {
+            wins += 1;
+        }
+        loss += (c - t).max(0.0);
     }
+    (wins as f64 / POSTERIOR_DRAWS as f64, loss / POSTERIOR_DRAWS as f64)
+}
+
+fn posterior_draws(rng: &mut StdRng, (total, success): (i64, i64)) -> Vec<f64> {
+    let failures = (total - success).max(0);
+    let beta = Beta::new(1.0 + success.max(0) as f64, 1.0 + failures as f64).expect("positive beta parameters");
+    (0..POSTERIOR_DRAWS).map(|_| beta.sample(rng)).collect()
+}
+
+pub fn analyze(results: &[ExperimentResultRow], min_samples: i64, method: AnalysisMethod) -> WinnerAnalysis {
+    let (c_total, c_success) = aggregate_variant(results, CONTROL_VARIANT);
+    let control_success_rate = ratio(c_success, c_total);
+    let mut comparisons: Vec<VariantComparison> = treatment_variants(results)
+        .iter()
+        .map(|v| compare(results, v, min_samples))
+        .collect();
+    let sufficient = |c: &VariantComparison| c.total_requests >= min_samples && c.total_requests > 0;
+    let insufficient = c_total < min_samples || c_total == 0 || !comparisons.iter().any(sufficient);
 
-    let z = (p2 - p1) / se;
-    let p = 2.0 * (1.0 - normal_cdf(z.abs()));
-    let significant = p < 0.05;
-    let winner = if significant {
-        if p2 > p1 {
-            Some("treatment".to_string())
-        } else {
-            Some("control".to_string())
+    if method == AnalysisMethod::Bayesian {
+        for comparison in comparisons.iter_mut() {
+            comparison.adjusted_p_value = comparison.p_value;
+            if insufficient || !sufficient(comparison) {
+                continue;
+            }
+            let (probability, loss) = bayesian_comparison(results, &comparison.variant);
+            comparison.probability_to_beat_control = Some(probability);
+            comparison.expected_loss = Some(loss);
+            comparison.is_significant = (probability >= PROBABILITY_TO_BEAT_THRESHOLD && loss <= EXPECTED_LOSS_THRESHOLD)
+                || probability <= 1.0 - PROBABILITY_TO_BEAT_THRESHOLD;
+        }
+    } else {
+        if method == AnalysisMethod::Sequential {
+            for comparison in comparisons.iter_mut() {
+                comparison.p_value = always_valid_p_value(results, &comparison.variant, min_samples);
+            }
+        }
+        let p_values: Vec<f64> = comparisons.iter().map(|c| c.p_value).collect();
+        for (comparison, adjusted) in comparisons.iter_mut().zip(holm_adjust(&p_values)) {
+            comparison.adjusted_p_value = adjusted;
+            comparison.is_significant = adjusted < SIGNIFICANCE_LEVEL;
         }
+    }
+
+    let best = comparisons
+        .iter()
+        .filter(|c| c.is_significant && c.z_score > 0.0)
+        .max_by(|a, b| a.success_rate.total_cmp(&b.success_rate));
+    let all_worse = !comparisons.is_empty() && comparisons.iter().all(|c| c.is_significant && c.z_score < 0.0);
+    let leading = best.or_else(|| match method {
+        AnalysisMethod::Bayesian => comparisons.iter().max_by(|a, b| {
+            a.probability_to_beat_control
+                .unwrap_or(0.0)
+                .total_cmp(&b.probability_to_beat_control.unwrap_or(0.0))
+        }),
+        _ => comparisons
+            .iter()
+            .min_by(|a, b| a.adjusted_p_value.total_cmp(&b.adjusted_p_value)),
+    });
+
+    let winner = match best {
+        Some(c) => Some(c.variant.clone()),
+        None if all_worse => Some(CONTROL_VARIANT.to_string()),
+        None => None,
+    };
+    let recommendation = if insufficient {
+        "insufficient sample size".to_string()
     } else {
-        None
+        match &winner {
+            Some(w) if w == CONTROL_VARIANT => "keep control".to_string(),
+            Some(w) => format!("promote {}", w),
+            None if comparisons.iter().filter(|c| sufficient(c)).all(|c| no_variance(results, &c.variant)) => {
+                "unable to compute significance".to_string()
+            }
+            None => "continue experiment".to_string(),
+        }
     };
 
     WinnerAnalysis {
-        control_success_rate: p1,
-        treatment_success_rate: p2,
-        z_score: z,
-        p_value: p,
-        is_significant: significant,
-        winner: winner.clone(),
-        recommendation: match winner {
-            Some(ref w) if w == "treatment" => "promote treatment".to_string(),
-            Some(_) => "keep control".to_string(),
-            None => "continue experiment".to_string(),
-        },
+        control_success_rate,
+        treatment_success_rate: leading.map(|c| c.success_rate).unwrap_or(0.0),
+        z_score: leading.map(|c| c.z_score).unwrap_or(0.0),
+        p_value: leading.map(|c| c.adjusted_p_value).unwrap_or(1.0),
+        is_significant: winner.is_some(),
+        winner,
+        recommendation,
+        method: method.as_str().to_string(),
+        correction: if method == AnalysisMethod::Bayesian { "NONE" } else { "HOLM" }.to_string(),
+        probability_to_beat_control: leading.and_then(|c| c.probability_to_beat_control),
+        expected_loss: leading.and_then(|c| c.expected_loss),
+        comparisons,
     }
 }
 
-pub fn evaluate_guardrails(results: &[ExperimentResultRow], config: &GuardrailConfig) -> GuardrailDecision {
-    let (control_total, control_success) = aggregate_variant(results, "control");
-    let (treatment_total, treatment_success) = aggregate_variant(results, "treatment");
+fn guardrail_for(results: &[ExperimentResultRow], variant: &str, config: &GuardrailConfig) -> GuardrailDecision {
+    let (control_total, control_success) = aggregate_variant(results, CONTROL_VARIANT);
+    let (treatment_total, treatment_success) = aggregate_variant(results, variant);
     let control_success_rate = ratio(control_success, control_total);
     let treatment_success_rate = ratio(treatment_success, treatment_total);
-    let control_p95 = max_p95(results, "control");
-    let treatment_p95 = max_p95(results, "treatment");
+    let control_p95 = max_p95(results, CONTROL_VARIANT);
+    let treatment_p95 = max_p95(results, variant);
+    let decision = |should_pause: bool, reason: Option<&str>| GuardrailDecision {
+        should_pause,
+        reason: reason.map(str::to_string),
+        variant: Some(variant.to_string()),
+        control_success_rate,
+        treatment_success_rate,
+        control_p95_latency_ms: control_p95,
+        treatment_p95_latency_ms: treatment_p95,
+        treatment_total_requests: treatment_total,
+        sufficient_samples: control_total >= config.min_samples && treatment_total >= config.min_samples,
+    };
 
     if treatment_total < config.min_samples {
-        return GuardrailDecision {
-            should_pause: false,
-            reason: None,
-            control_success_rate,
-            treatment_success_rate,
-            control_p95_latency_ms: control_p95,
-            treatment_p95_latency_ms: treatment_p95,
-            treatment_total_requests: treatment_total,
-        };
+        return decision(false, None);
     }
 
     if treatment_success_rate + config.max_success_rate_drop < control_success_rate {
-        return GuardrailDecision {
-            should_pause: true,
-            reason: Some("treatment_success_rate_below_guardrail".to_string()),
-            control_success_rate,
-            treatment_success_rate,
-            control_p95_latency_ms: control_p95,
-            treatment_p95_latency_ms: treatment_p95,
-            treatment_total_requests: treatment_total,
-        };
+        return decision(true, Some("treatment_success_rate_below_guardrail"));
     }
 
     if control_p95 > 0
         && treatment_p95 > 0
         && (treatment_p95 as f64) > (control_p95 as f64 * config.max_latency_multiplier)
     {
-        return GuardrailDecision {
-            should_pause: true,
-            reason: Some("treatment_latency_above_guardrail".to_string()),
-            control_success_rate,
-            treatment_success_rate,
-            control_p95_latency_ms: control_p95,
-            treatment_p95_latency_ms: treatment_p95,
-            treatment_total_requests: treatment_total,
-        };
+        return decision(true, Some("treatment_latency_above_guardrail"));
     }
 
+    decision(false, None)
+}
+
+pub fn evaluate_guardrails(results: &[ExperimentResultRow], config: &GuardrailConfig) -> GuardrailDecision {
+    let decisions: Vec<GuardrailDecision> = treatment_variants(results)
+        .iter()
+        .map(|v| guardrail_for(results, v, config))
+        .collect();
+    if let Some(paused) = decisions.iter().find(|d| d.should_pause) {
+        return paused.clone();
+    }
+    let sufficient_samples = !decisions.is_empty() && decisions.iter().all(|d| d.sufficient_samples);
+    let decision = decisions
+        .into_iter()
+        .min_by(|a, b| a.treatment_success_rate.total_cmp(&b.treatment_success_rate))
+        .unwrap_or_else(|| GuardrailDecision {
+            variant: None,
+            ..guardrail_for(results, "treatment", config)
+        });
     GuardrailDecision {
-        should_pause: false,
-        reason: None,
-        control_success_rate,
-        treatment_success_rate,
-        control_p95_latency_ms: control_p95,
-        treatment_p95_latency_ms: treatment_p95,
-        treatment_total_requests: treatment_total,
+        sufficient_samples,
+        ..decision
     }
 }
 
@@ -170,6 +339,13 @@ fn aggregate_variant(results: &[ExperimentResultRow], variant: &str) -> (i64, i6
     (total, success)
 }
 
+fn no_variance(results: &[ExperimentResultRow], variant: &str) -> bool {
+    let (c_total, c_success) = aggregate_variant(results, CONTROL_VARIANT);
+    let (t_total, t_success) = aggregate_variant(results, variant);
+    let pooled = ratio(c_success + t_success, c_total + t_total);
+    pooled == 0.0 || pooled == 1.0
+}
+
 fn max_p95(results: &[ExperimentResultRow], variant: &str) -> i32 {
     results
         .iter()
@@ -186,13 +362,3 @@ fn ratio(a: i64, b: i64) -> f64 {
         a as f64 / b as f64
     }
 }
-
-fn normal_cdf(x: f64) -> f64 {
-    let t = 1.0 / (1.0 + 0.2316419 * x.abs());
-    let d = 0.3989423 * (-x * x / 2.0).exp();
-    let prob = 1.0
-        - d * t
-            * (0.3193815
-                + t * (-0.3565638 + t * (1.781478 + t * (-1.821256 + t * 1.330274))));
-    if x >= 0.0 { prob } else { 1.0 - prob }
-}
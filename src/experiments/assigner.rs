@@ -1,23 +1,125 @@
+use crate::domain::experiment::ExperimentVariant;
 use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use uuid::Uuid;
 
+pub const CONTROL_VARIANT: &str = "control";
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct Assignment {
     pub variant: String,
     pub bucket: i32,
+    pub override_gateway: Option<String>,
+}
+
+pub fn bucket(customer_id: &str, experiment_id: Uuid) -> i32 {
+    let mut hasher = Sha256::new();
+    hasher.update(customer_id.as_bytes());
+    hasher.update(experiment_id.as_bytes());
+    let hash = hasher.finalize();
+
+    ((hash[0] as u16 * 256 + hash[1] as u16) % 100) as i32
 }
 
-pub fn assign_variant(customer_id: &str, experiment_id: Uuid, control_pct: i32) -> Assignment {
+pub fn exposure_bucket(customer_id: &str, experiment_id: Uuid) -> i32 {
     let mut hasher = Sha256::new();
+    hasher.update(b"exposure");
     hasher.update(customer_id.as_bytes());
     hasher.update(experiment_id.as_bytes());
     let hash = hasher.finalize();
 
-    let bucket = ((hash[0] as u16 * 256 + hash[1] as u16) % 100) as i32;
-    let variant = if bucket < control_pct { "control" } else { "treatment" };
+    ((hash[0] as u16 * 256 + hash[1] as u16) % 100) as i32
+}
+
+pub fn is_exposed(customer_id: &str, experiment_id: Uuid, exposure_pct: i32) -> bool {
+    exposure_bucket(customer_id, experiment_id) < exposure_pct
+}
+
+pub fn assign_variant(customer_id: &str, experiment_id: Uuid, variants: &[ExperimentVariant]) -> Assignment {
+    let bucket = bucket(customer_id, experiment_id);
+    let mut upper = 0;
+    let chosen = variants
+        .iter()
+        .find(|v| {
+            upper += v.traffic_pct;
+            bucket < upper
+        })
+        .or_else(|| variants.last());
 
     Assignment {
-        variant: variant.to_string(),
+        variant: chosen.map(|v| v.variant_name.clone()).unwrap_or_else(|| CONTROL_VARIANT.to_string()),
         bucket,
+        override_gateway: chosen.and_then(|v| v.override_gateway.clone()),
+    }
+}
+
+pub fn legacy_variants(control_pct: i32, treatment_pct: i32, treatment_gateway: &str) -> Vec<ExperimentVariant> {
+    vec![
+        ExperimentVariant {
+            variant_name: CONTROL_VARIANT.to_string(),
+            traffic_pct: control_pct,
+            override_gateway: None,
+            scoring_profile_id: None,
+            bandit_enabled: None,
+            rule_set: None,
+            retry_policy: None,
+        },
+        ExperimentVariant {
+            variant_name: "treatment".to_string(),
+            traffic_pct: treatment_pct,
+            override_gateway: Some(treatment_gateway.to_string()),
+            scoring_profile_id: None,
+            bandit_enabled: None,
+            rule_set: None,
+            retry_policy: None,
+        },
+    ]
+}
+
+pub fn validate_variants(variants: &[ExperimentVariant]) -> Result<(), String> {
+    if variants.len() < 2 {
+        return Err("an experiment needs a control and at least one other variant".to_string());
+    }
+    let mut names = HashSet::new();
+    for variant in variants {
+        let name = variant.variant_name.as_str();
+        if name.trim().is_empty() {
+            return Err("variant_name must not be empty".to_string());
+        }
+        if !names.insert(name) {
+            return Err(format!("variant {} is listed twice", name));
+        }
+        if !(0..=100).contains(&variant.traffic_pct) {
+            return Err(format!("traffic_pct of {} must be between 0 and 100", name));
+        }
+        if variant.override_gateway.as_deref().is_some_and(|g| g.trim().is_empty()) {
+            return Err(format!("override_gateway of {} must not be empty", name));
+        }
+        if variant.scoring_profile_id.as_deref().is_some_and(|p| p.trim().is_empty()) {
+            return Err(format!("scoring_profile_id of {} must not be empty", name));
+        }
+        if variant.rule_set.as_deref().is_some_and(|r| r.trim().is_empty()) {
+            return Err(format!("rule_set of {} must not be empty", name));
+        }
+        if let Some(policy) = &variant.retry_policy {
+            if policy.max_attempts < 1 {
+                return Err(format!("retry_policy.max_attempts of {} must be at least 1", name));
+            }
+            if policy.latency_budget_ms <= 0 {
+                return Err(format!("retry_policy.latency_budget_ms of {} must be positive", name));
+            }
+        }
+    }
+    match variants.iter().find(|v| v.variant_name == CONTROL_VARIANT) {
+        None => return Err(format!("one variant must be named {}", CONTROL_VARIANT)),
+        Some(control) if control.changes_routing() => {
+            return Err(format!("{} must use the production routing policy", CONTROL_VARIANT))
+        }
+        Some(_) => {}
+    }
+    let total: i32 = variants.iter().map(|v| v.traffic_pct).sum();
+    if total != 100 {
+        return Err(format!("variant traffic_pct must add up to 100, got {}", total));
     }
+    Ok(())
 }
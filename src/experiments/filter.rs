@@ -6,6 +6,8 @@ pub struct MatchInput {
     pub amount_minor: i64,
     pub merchant_id: String,
     pub amount_bucket: String,
+    pub card_network: Option<String>,
+    pub card_type: Option<String>,
 }
 
 pub fn matches(filter: &ExperimentFilter, input: &MatchInput) -> bool {
@@ -39,5 +41,17 @@ pub fn matches(filter: &ExperimentFilter, input: &MatchInput) -> bool {
         }
     }
 
+    if let Some(network) = &filter.card_network {
+        if !input.card_network.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(network)) {
+            return false;
+        }
+    }
+
+    if let Some(card_type) = &filter.card_type {
+        if !input.card_type.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(card_type)) {
+            return false;
+        }
+    }
+
     true
 }
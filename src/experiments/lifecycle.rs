@@ -0,0 +1,159 @@
+use crate::domain::experiment::{Experiment, ExperimentResultRow, ExperimentStatus, RampStep};
+use crate::experiments::analyzer::GuardrailDecision;
+use serde::Serialize;
+
+pub const FULL_EXPOSURE_PCT: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LifecycleAction {
+    Created,
+    Started,
+    Paused,
+    Resumed,
+    Ramped,
+    GuardrailPaused,
+    Completed,
+    Stopped,
+}
+
+impl LifecycleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifecycleAction::Created => "CREATED",
+            LifecycleAction::Started => "STARTED",
+            LifecycleAction::Paused => "PAUSED",
+            LifecycleAction::Resumed => "RESUMED",
+            LifecycleAction::Ramped => "RAMPED",
+            LifecycleAction::GuardrailPaused => "GUARDRAIL_PAUSED",
+            LifecycleAction::Completed => "COMPLETED",
+            LifecycleAction::Stopped => "STOPPED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Transition {
+    pub action: LifecycleAction,
+    pub to_status: ExperimentStatus,
+    pub exposure_pct: Option<i32>,
+    pub ramp_step: Option<i32>,
+    pub restart_ramp_clock: bool,
+    pub reason: Option<String>,
+}
+
+impl Transition {
+    pub fn new(action: LifecycleAction, to_status: ExperimentStatus) -> Self {
+        Self {
+            action,
+            to_status,
+            exposure_pct: None,
+            ramp_step: None,
+            restart_ramp_clock: false,
+            reason: None,
+        }
+    }
+}
+
+pub fn validate_ramp(steps: &[RampStep]) -> Result<(), String> {
+    let mut previous = 0;
+    for step in steps {
+        if !(1..=FULL_EXPOSURE_PCT).contains(&step.exposure_pct) {
+            return Err("ramp exposure_pct must be between 1 and 100".to_string());
+        }
+        if step.exposure_pct <= previous {
+            return Err("ramp exposure_pct must increase at every step".to_string());
+        }
+        if step.hold_minutes < 0 {
+            return Err("ramp hold_minutes must not be negative".to_string());
+        }
+        previous = step.exposure_pct;
+    }
+    Ok(())
+}
+
+pub fn initial_exposure(steps: &[RampStep]) -> (i32, Option<i32>) {
+    match steps.first() {
+        Some(step) => (step.exposure_pct, Some(0)),
+        None => (FULL_EXPOSURE_PCT, None),
+    }
+}
+
+pub fn initial_status(start_date: chrono::DateTime<chrono::Utc>, now: chrono::DateTime<chrono::Utc>) -> ExperimentStatus {
+    if start_date > now {
+        ExperimentStatus::Scheduled
+    } else {
+        ExperimentStatus::Running
+    }
+}
+
+pub fn manual_transition(status: ExperimentStatus, action: LifecycleAction) -> Result<Transition, String> {
+    match (status, action) {
+        (ExperimentStatus::Running, LifecycleAction::Paused) => {
+            Ok(Transition::new(LifecycleAction::Paused, ExperimentStatus::Paused))
+        }
+        (ExperimentStatus::Paused, LifecycleAction::Resumed) => Ok(Transition {
+            restart_ramp_clock: true,
+            ..Transition::new(LifecycleAction::Resumed, ExperimentStatus::Running)
+        }),
+        (status, LifecycleAction::Stopped) if !status.is_terminal() => {
+            Ok(Transition::new(LifecycleAction::Stopped, ExperimentStatus::Stopped))
+        }
+        (status, action) => Err(format!("cannot {} an experiment that is {}", action.as_str(), status.as_str())),
+    }
+}
+
+pub fn step_results(experiment: &Experiment, results: &[ExperimentResultRow]) -> Vec<ExperimentResultRow> {
+    results
+        .iter()
+        .filter(|r| experiment.ramped_at.is_none_or(|since| r.date_hour >= since))
+        .cloned()
+        .collect()
+}
+
+pub fn next_transition(
+    experiment: &Experiment,
+    guardrail: Option<&GuardrailDecision>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<Transition> {
+    let status = ExperimentStatus::parse(&experiment.status)?;
+    if status.is_terminal() {
+        return None;
+    }
+    if experiment.end_date.is_some_and(|end| end <= now) {
+        return Some(Transition::new(LifecycleAction::Completed, ExperimentStatus::Completed));
+    }
+
+    match status {
+        ExperimentStatus::Scheduled if experiment.start_date <= now => Some(Transition {
+            restart_ramp_clock: true,
+            ..Transition::new(LifecycleAction::Started, ExperimentStatus::Running)
+        }),
+        ExperimentStatus::Running => {
+            if let Some(decision) = guardrail.filter(|g| g.should_pause) {
+                return Some(Transition {
+                    reason: decision.reason.clone(),
+                    ..Transition::new(LifecycleAction::GuardrailPaused, ExperimentStatus::Paused)
+                });
+            }
+            if !guardrail.is_some_and(|g| g.sufficient_samples) {
+                return None;
+            }
+            let step = experiment.ramp_step? as usize;
+            let current = experiment.ramp_schedule.get(step)?;
+            let next = experiment.ramp_schedule.get(step + 1)?;
+            let since = experiment.ramped_at.unwrap_or(experiment.start_date);
+            if now < since + chrono::Duration::minutes(current.hold_minutes) {
+                return None;
+            }
+            Some(Transition {
+                exposure_pct: Some(next.exposure_pct),
+                ramp_step: Some(step as i32 + 1),
+                restart_ramp_clock: true,
+                reason: Some(format!("{}% -> {}%", current.exposure_pct, next.exposure_pct)),
+                ..Transition::new(LifecycleAction::Ramped, ExperimentStatus::Running)
+            })
+        }
+        _ => None,
+    }
+}
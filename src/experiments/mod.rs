@@ -1,3 +1,4 @@
 pub mod assigner;
 pub mod filter;
 pub mod analyzer;
+pub mod lifecycle;
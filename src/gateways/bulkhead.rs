@@ -0,0 +1,154 @@
+use crate::gateways::GatewayConfig;
+use crate::redis_conn::SharedRedis;
+use anyhow::Result;
+use uuid::Uuid;
+
+const LEASE_GRACE_MS: i64 = 5_000;
+
+const ACQUIRE_SCRIPT: &str = r#"
+local now_ms = tonumber(ARGV[1])
+local max_in_flight = tonumber(ARGV[4])
+local max_tps = tonumber(ARGV[5])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now_ms)
+if max_in_flight > 0 and redis.call('ZCARD', KEYS[1]) >= max_in_flight then
+    return 1
+end
+if max_tps > 0 then
+    local sent = tonumber(redis.call('GET', KEYS[2]) or '0')
+    if sent >= max_tps then
+        return 2
+    end
+    redis.call('INCR', KEYS[2])
+    redis.call('EXPIRE', KEYS[2], 2)
+end
+if max_in_flight > 0 then
+    redis.call('ZADD', KEYS[1], ARGV[2], ARGV[3])
+    redis.call('PEXPIRE', KEYS[1], ARGV[6])
+end
+return 0
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BulkheadLimits {
+    pub max_in_flight: i64,
+    pub max_tps: i64,
+}
+
+impl BulkheadLimits {
+    pub fn from_config(gateway: &GatewayConfig) -> Self {
+        Self {
+            max_in_flight: gateway.max_in_flight.unwrap_or(0).max(0) as i64,
+            max_tps: gateway.max_tps.unwrap_or(0).max(0) as i64,
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_in_flight == 0 && self.max_tps == 0
+    }
+
+    pub fn admission_code(&self, live_in_flight: i64, sent_this_second: i64) -> i64 {
+        if self.max_in_flight > 0 && live_in_flight >= self.max_in_flight {
+            return 1;
+        }
+        if self.max_tps > 0 && sent_this_second >= self.max_tps {
+            return 2;
+        }
+        0
+    }
+}
+
+pub fn live_leases(lease_expiries_ms: &[i64], now_ms: i64) -> i64 {
+    lease_expiries_ms.iter().filter(|expires| **expires > now_ms).count() as i64
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkheadDecision {
+    Acquired(Option<String>),
+    InFlightFull,
+    TpsExceeded,
+}
+
+impl BulkheadDecision {
+    pub fn from_code(code: i64, lease: Option<String>) -> Self {
+        match code {
+            1 => BulkheadDecision::InFlightFull,
+            2 => BulkheadDecision::TpsExceeded,
+            _ => BulkheadDecision::Acquired(lease),
+        }
+    }
+
+    pub fn or_fail_open(result: Result<Self>, gateway_id: &str) -> Self {
+        result.unwrap_or_else(|e| {
+            tracing::warn!("bulkhead check failed for {}: {}", gateway_id, e);
+            BulkheadDecision::Acquired(None)
+        })
+    }
+
+    pub fn skip_reason(&self) -> Option<&'static str> {
+        match self {
+            BulkheadDecision::Acquired(_) => None,
+            BulkheadDecision::InFlightFull | BulkheadDecision::TpsExceeded => Some("bulkhead_full"),
+        }
+    }
+}
+
+pub fn lease_ttl_ms(timeout_ms: i32) -> i64 {
+    timeout_ms.max(100) as i64 + LEASE_GRACE_MS
+}
+
+#[derive(Clone)]
+pub struct GatewayBulkhead {
+    pub redis: SharedRedis,
+}
+
+impl GatewayBulkhead {
+    pub fn new(redis: SharedRedis) -> Self {
+        Self { redis }
+    }
+
+    fn in_flight_key(gateway_id: &str) -> String {
+        format!("bulkhead:inflight:{}", gateway_id.to_lowercase())
+    }
+
+    pub fn tps_key(gateway_id: &str, epoch_sec: i64) -> String {
+        format!("bulkhead:tps:{}:{}", gateway_id.to_lowercase(), epoch_sec)
+    }
+
+    pub async fn try_acquire(&self, gateway: &GatewayConfig) -> Result<BulkheadDecision> {
+        let limits = BulkheadLimits::from_config(gateway);
+        if limits.is_unlimited() {
+            return Ok(BulkheadDecision::Acquired(None));
+        }
+
+        let mut conn = self.redis.conn().await?;
+        let now = chrono::Utc::now();
+        let now_ms = now.timestamp_millis();
+        let ttl_ms = lease_ttl_ms(gateway.timeout_ms);
+        let lease = Uuid::new_v4().to_string();
+
+        let code: i64 = redis::Script::new(ACQUIRE_SCRIPT)
+            .key(Self::in_flight_key(&gateway.gateway_id))
+            .key(Self::tps_key(&gateway.gateway_id, now.timestamp()))
+            .arg(now_ms)
+            .arg(now_ms + ttl_ms)
+            .arg(&lease)
+            .arg(limits.max_in_flight)
+            .arg(limits.max_tps)
+            .arg(ttl_ms)
+            .invoke_async(&mut conn)
+            .await?;
+
+        let lease = (limits.max_in_flight > 0).then_some(lease);
+        Ok(BulkheadDecision::from_code(code, lease))
+    }
+
+    pub async fn release(&self, gateway_id: &str, lease: &str) -> Result<()> {
+        let mut conn = self.redis.conn().await?;
+        let _: i64 = redis::cmd("ZREM")
+            .arg(Self::in_flight_key(gateway_id))
+            .arg(lease)
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+}
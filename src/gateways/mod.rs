@@ -3,6 +3,7 @@ use crate::domain::payment::PaymentStatus;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
+pub mod bulkhead;
 pub mod mock;
 pub mod razorpay;
 
@@ -39,6 +40,8 @@ pub struct GatewayConfig {
     pub supported_methods: Vec<String>,
     pub timeout_ms: i32,
     pub mock_behavior: Option<String>,
+    pub max_in_flight: Option<i32>,
+    pub max_tps: Option<i32>,
 }
 
 #[async_trait::async_trait]
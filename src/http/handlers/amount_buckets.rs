@@ -0,0 +1,79 @@
+use crate::metrics::amount_bucket::{validate_buckets, AmountBucket};
+use crate::repo::amount_buckets_repo::ReplaceOutcome;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct ReplaceBucketsRequest {
+    pub buckets: Vec<AmountBucket>,
+    #[serde(default)]
+    pub remap: HashMap<String, String>,
+}
+
+pub async fn list_buckets(State(state): State<AppState>) -> impl IntoResponse {
+    let config = state.routing_config.current();
+    let buckets: HashMap<&str, &[AmountBucket]> = config
+        .amount_buckets
+        .currencies()
+        .into_iter()
+        .map(|currency| (currency, config.amount_buckets.buckets(currency)))
+        .collect();
+    (
+        axum::http::StatusCode::OK,
+        Json(serde_json::json!({"buckets": buckets, "config_version": config.version})),
+    )
+        .into_response()
+}
+
+pub async fn replace_buckets(
+    State(state): State<AppState>,
+    Path(currency): Path<String>,
+    Json(mut req): Json<ReplaceBucketsRequest>,
+) -> impl IntoResponse {
+    let currency = currency.to_uppercase();
+    req.buckets.sort_by_key(|b| b.min_amount_minor);
+    if let Err(e) = validate_buckets(&req.buckets) {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e})),
+        )
+            .into_response();
+    }
+
+    match state.amount_buckets_repo.replace(&currency, &req.buckets, &req.remap).await {
+        Ok(ReplaceOutcome::Applied { renamed }) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({
+                "currency": currency,
+                "buckets": req.buckets,
+                "renamed": renamed
+                    .into_iter()
+                    .map(|(from, to)| serde_json::json!({"from": from, "to": to}))
+                    .collect::<Vec<_>>(),
+            })),
+        )
+            .into_response(),
+        Ok(ReplaceOutcome::Invalid(e)) => (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": e})),
+        )
+            .into_response(),
+        Ok(ReplaceOutcome::Orphaned(references)) => (
+            axum::http::StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": "removed buckets are still referenced; add them to remap",
+                "references": references,
+            })),
+        )
+            .into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
@@ -1,14 +1,51 @@
+use crate::bandit::discount::validate_half_life;
+use crate::bandit::evaluation::{evaluate as estimate, logged_reward, BanditTarget, RewardModel, ScoringEnginePolicy, TargetPolicy};
+use crate::bandit::policy::{validate_exploration, BanditAlgorithm, BanditPolicyConfig, DEFAULT_EXPLORATION};
+use crate::bandit::reward::{validate_reward, RewardConfig};
+use crate::bandit::safety::{validate_safety, SafetyConfig};
+use crate::bandit::segments::{validate_definitions, BanditSegment, SegmentDefinition, SegmentDimension, DEFAULT_DEFINITION_ID};
+use crate::bandit::thompson::PROPENSITY_DRAWS;
 use crate::AppState;
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::response::IntoResponse;
 use axum::Json;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct DiscountRequest {
+    pub half_life_minutes: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SegmentQuery {
+    pub definition_id: Option<String>,
+}
+
+fn resolve_segment(state: &AppState, definition_id: Option<&str>, key: &str) -> Result<BanditSegment, String> {
+    let config = state.routing_config.current();
+    let definition_id = definition_id.unwrap_or(DEFAULT_DEFINITION_ID);
+    let definition = config
+        .bandit_definitions
+        .get(definition_id)
+        .ok_or_else(|| format!("unknown segment definition {}", definition_id))?;
+    definition.validate_key(key, &config.amount_buckets)?;
+    Ok(BanditSegment::new(definition_id, key))
+}
 
 pub async fn enable_segment(
     State(state): State<AppState>,
     Path(segment): Path<String>,
+    Query(query): Query<SegmentQuery>,
 ) -> impl IntoResponse {
+    let segment = match resolve_segment(&state, query.definition_id.as_deref(), &segment) {
+        Ok(segment) => segment,
+        Err(msg) => {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    };
     match state.bandit_repo.set_enabled(&segment, true).await {
-        Ok(_) => (axum::http::StatusCode::OK, Json(serde_json::json!({"segment": segment, "enabled": true}))).into_response(),
+        Ok(_) => (axum::http::StatusCode::OK, Json(serde_json::json!({"definition_id": segment.definition_id, "segment": segment.key, "enabled": true}))).into_response(),
         Err(e) => (
             axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({"error": e.to_string()})),
@@ -20,9 +57,207 @@ pub async fn enable_segment(
 pub async fn disable_segment(
     State(state): State<AppState>,
     Path(segment): Path<String>,
+    Query(query): Query<SegmentQuery>,
 ) -> impl IntoResponse {
+    let segment = match resolve_segment(&state, query.definition_id.as_deref(), &segment) {
+        Ok(segment) => segment,
+        Err(msg) => {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    };
     match state.bandit_repo.set_enabled(&segment, false).await {
-        Ok(_) => (axum::http::StatusCode::OK, Json(serde_json::json!({"segment": segment, "enabled": false}))).into_response(),
+        Ok(_) => (axum::http::StatusCode::OK, Json(serde_json::json!({"definition_id": segment.definition_id, "segment": segment.key, "enabled": false}))).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AlgorithmRequest {
+    pub algorithm: BanditAlgorithm,
+    pub exploration: Option<f64>,
+}
+
+pub async fn set_algorithm(
+    State(state): State<AppState>,
+    Path(segment): Path<String>,
+    Query(query): Query<SegmentQuery>,
+    Json(req): Json<AlgorithmRequest>,
+) -> impl IntoResponse {
+    let segment = match resolve_segment(&state, query.definition_id.as_deref(), &segment) {
+        Ok(segment) => segment,
+        Err(msg) => {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    };
+    let exploration = req.exploration.unwrap_or(DEFAULT_EXPLORATION);
+    if let Err(msg) = validate_exploration(exploration) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+
+    match state.bandit_repo.set_algorithm(&segment, req.algorithm, exploration).await {
+        Ok(_) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({"definition_id": segment.definition_id, "segment": segment.key, "algorithm": req.algorithm, "exploration": exploration})),
+        )
+            .into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn set_reward(
+    State(state): State<AppState>,
+    Path(segment): Path<String>,
+    Query(query): Query<SegmentQuery>,
+    Json(req): Json<RewardConfig>,
+) -> impl IntoResponse {
+    let segment = match resolve_segment(&state, query.definition_id.as_deref(), &segment) {
+        Ok(segment) => segment,
+        Err(msg) => {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    };
+    if let Err(msg) = validate_reward(&req) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+
+    match state.bandit_repo.set_reward(&segment, &req).await {
+        Ok(_) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({"definition_id": segment.definition_id, "segment": segment.key, "reward": req})),
+        )
+            .into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn set_safety(
+    State(state): State<AppState>,
+    Path(segment): Path<String>,
+    Query(query): Query<SegmentQuery>,
+    Json(req): Json<SafetyConfig>,
+) -> impl IntoResponse {
+    let segment = match resolve_segment(&state, query.definition_id.as_deref(), &segment) {
+        Ok(segment) => segment,
+        Err(msg) => {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    };
+    if let Err(msg) = validate_safety(&req) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+
+    match state.bandit_repo.set_safety(&segment, &req).await {
+        Ok(_) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({"definition_id": segment.definition_id, "segment": segment.key, "safety": req})),
+        )
+            .into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn reset_segment(
+    State(state): State<AppState>,
+    Path(segment): Path<String>,
+    Query(query): Query<SegmentQuery>,
+) -> impl IntoResponse {
+    let segment = match resolve_segment(&state, query.definition_id.as_deref(), &segment) {
+        Ok(segment) => segment,
+        Err(msg) => {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    };
+    match state.bandit_repo.reset_segment(&segment).await {
+        Ok(arms) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({"definition_id": segment.definition_id, "segment": segment.key, "reset_arms": arms})),
+        )
+            .into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn reseed_segment(
+    State(state): State<AppState>,
+    Path(segment): Path<String>,
+    Query(query): Query<SegmentQuery>,
+) -> impl IntoResponse {
+    let segment = match resolve_segment(&state, query.definition_id.as_deref(), &segment) {
+        Ok(segment) => segment,
+        Err(msg) => {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    };
+    let config = state.routing_config.current();
+    let gateways: Vec<String> = config
+        .bandit_definitions
+        .get(&segment.definition_id)
+        .and_then(|d| d.value(&segment.key, SegmentDimension::PaymentMethod))
+        .map(|method| config.enabled_gateways(method).into_iter().map(|g| g.gateway_id).collect())
+        .unwrap_or_default();
+
+    match state.bandit_repo.reseed_segment(&segment, &gateways).await {
+        Ok(arms) => {
+            let arms: Vec<_> = arms
+                .into_iter()
+                .map(|(gateway_id, alpha, beta)| serde_json::json!({"gateway_id": gateway_id, "alpha": alpha, "beta": beta}))
+                .collect();
+            (
+                axum::http::StatusCode::OK,
+                Json(serde_json::json!({"definition_id": segment.definition_id, "segment": segment.key, "arms": arms})),
+            )
+                .into_response()
+        }
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn set_discount(
+    State(state): State<AppState>,
+    Path(segment): Path<String>,
+    Query(query): Query<SegmentQuery>,
+    Json(req): Json<DiscountRequest>,
+) -> impl IntoResponse {
+    let segment = match resolve_segment(&state, query.definition_id.as_deref(), &segment) {
+        Ok(segment) => segment,
+        Err(msg) => {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+        }
+    };
+    if let Err(msg) = validate_half_life(req.half_life_minutes) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+
+    match state.bandit_repo.set_half_life(&segment, req.half_life_minutes).await {
+        Ok(_) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({"definition_id": segment.definition_id, "segment": segment.key, "half_life_minutes": req.half_life_minutes})),
+        )
+            .into_response(),
         Err(e) => (
             axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({"error": e.to_string()})),
@@ -41,3 +276,231 @@ pub async fn get_state(State(state): State<AppState>) -> impl IntoResponse {
             .into_response(),
     }
 }
+
+#[derive(Debug, Deserialize)]
+pub struct CandidatePolicy {
+    pub algorithm: BanditAlgorithm,
+    pub exploration: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EvaluateRequest {
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<i64>,
+    pub segment: Option<String>,
+    pub definition_id: Option<String>,
+    #[serde(default)]
+    pub candidates: Vec<CandidatePolicy>,
+}
+
+pub async fn evaluate(State(state): State<AppState>, Json(req): Json<EvaluateRequest>) -> impl IntoResponse {
+    for candidate in &req.candidates {
+        if let Err(msg) = validate_exploration(candidate.exploration.unwrap_or(DEFAULT_EXPLORATION)) {
+            return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+        }
+    }
+    let to = req.to.unwrap_or_else(chrono::Utc::now);
+    let from = req.from.unwrap_or(to - chrono::Duration::days(7));
+    if from >= to {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "from must be before to"})),
+        )
+            .into_response();
+    }
+    let segment = match req.segment.as_deref() {
+        Some(key) => match resolve_segment(&state, req.definition_id.as_deref(), key) {
+            Ok(segment) => Some(segment),
+            Err(msg) => {
+                return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response()
+            }
+        },
+        None => None,
+    };
+
+    match run_evaluation(&state, from, to, segment, req).await {
+        Ok(report) => (axum::http::StatusCode::OK, Json(report)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+async fn run_evaluation(
+    state: &AppState,
+    from: chrono::DateTime<chrono::Utc>,
+    to: chrono::DateTime<chrono::Utc>,
+    segment: Option<BanditSegment>,
+    req: EvaluateRequest,
+) -> anyhow::Result<serde_json::Value> {
+    let (events, skipped) = state
+        .backtest_repo
+        .load_bandit_events(from, to, req.limit.unwrap_or(100_000), segment.as_ref())
+        .await?;
+
+    let posteriors: HashMap<(BanditSegment, String), (f64, f64)> = state
+        .bandit_repo
+        .list_state()
+        .await?
+        .into_iter()
+        .map(|row| {
+            (
+                (BanditSegment::new(&row.definition_id, &row.segment), row.gateway_id),
+                (row.alpha, row.beta),
+            )
+        })
+        .collect();
+    let mut arms: HashMap<BanditSegment, Vec<String>> = HashMap::new();
+    for event in &events {
+        let gateways = arms.entry(event.state_segment.clone()).or_default();
+        for candidate in &event.candidates {
+            if !gateways.contains(&candidate.gateway_id) {
+                gateways.push(candidate.gateway_id.clone());
+            }
+        }
+    }
+    let mut models = HashMap::new();
+    for (segment, gateways) in &arms {
+        for (gateway_id, model) in state.bandit_repo.linear_models(segment, gateways).await? {
+            models.insert((segment.clone(), gateway_id), model);
+        }
+    }
+
+    let config = state.routing_config.current();
+    let mut policies: Vec<Box<dyn TargetPolicy>> = vec![
+        Box::new(ScoringEnginePolicy),
+        Box::new(BanditTarget {
+            name: "CURRENT_BANDIT".to_string(),
+            default: BanditPolicyConfig::thompson("*"),
+            segments: events
+                .iter()
+                .map(|e| (e.segment.clone(), config.bandit_policy(&e.segment)))
+                .collect(),
+            posteriors: posteriors.clone(),
+            models: models.clone(),
+            draws: PROPENSITY_DRAWS,
+        }),
+    ];
+    for candidate in req.candidates {
+        let exploration = candidate.exploration.unwrap_or(DEFAULT_EXPLORATION);
+        let name = match candidate.algorithm {
+            BanditAlgorithm::Thompson => "THOMPSON".to_string(),
+            BanditAlgorithm::LinUcb => format!("LINUCB(exploration={})", exploration),
+        };
+        policies.push(Box::new(BanditTarget {
+            name,
+            default: BanditPolicyConfig {
+                algorithm: candidate.algorithm,
+                exploration,
+                ..BanditPolicyConfig::thompson("*")
+            },
+            segments: HashMap::new(),
+            posteriors: posteriors.clone(),
+            models: models.clone(),
+            draws: PROPENSITY_DRAWS,
+        }));
+    }
+
+    let model = RewardModel::fit(&events);
+    let estimates: Vec<_> = policies.iter().map(|p| estimate(&events, p.as_ref(), &model)).collect();
+    Ok(serde_json::json!({
+        "from": from,
+        "to": to,
+        "events": events.len(),
+        "skipped": skipped,
+        "logged_success_rate": logged_reward(&events),
+        "estimates": estimates,
+    }))
+}
+
+pub async fn list_definitions(State(state): State<AppState>) -> impl IntoResponse {
+    match state.bandit_repo.list_definitions().await {
+        Ok(definitions) => (axum::http::StatusCode::OK, Json(definitions)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn upsert_definition(
+    State(state): State<AppState>,
+    Path(definition_id): Path<String>,
+    Json(mut req): Json<SegmentDefinition>,
+) -> impl IntoResponse {
+    req.definition_id = definition_id;
+    let mut definitions: Vec<SegmentDefinition> = state
+        .routing_config
+        .current()
+        .bandit_definitions
+        .definitions()
+        .iter()
+        .filter(|d| d.definition_id != req.definition_id)
+        .cloned()
+        .collect();
+    definitions.push(req.clone());
+    if let Err(msg) = validate_definitions(&definitions) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+
+    match state.bandit_repo.upsert_definition(&req).await {
+        Ok(_) => (axum::http::StatusCode::OK, Json(req)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn delete_definition(State(state): State<AppState>, Path(definition_id): Path<String>) -> impl IntoResponse {
+    let remaining: Vec<SegmentDefinition> = state
+        .routing_config
+        .current()
+        .bandit_definitions
+        .definitions()
+        .iter()
+        .filter(|d| d.definition_id != definition_id)
+        .cloned()
+        .collect();
+    if let Err(msg) = validate_definitions(&remaining) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+    match state.bandit_repo.definition_policies(&definition_id).await {
+        Ok(0) => {}
+        Ok(policies) => {
+            return (
+                axum::http::StatusCode::CONFLICT,
+                Json(serde_json::json!({
+                    "error": format!("{} is still used by {} bandit policies", definition_id, policies)
+                })),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response()
+        }
+    }
+
+    match state.bandit_repo.delete_definition(&definition_id).await {
+        Ok(true) => (axum::http::StatusCode::OK, Json(serde_json::json!({"deleted": true}))).into_response(),
+        Ok(false) => (
+            axum::http::StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("unknown segment definition {}", definition_id)})),
+        )
+            .into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
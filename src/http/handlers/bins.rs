@@ -0,0 +1,57 @@
+use crate::bins::import::parse_csv;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+
+pub async fn import_bins(State(state): State<AppState>, body: String) -> impl IntoResponse {
+    let (records, errors) = parse_csv(&body);
+    if !errors.is_empty() {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "invalid BIN rows", "rows": errors})),
+        )
+            .into_response();
+    }
+    if records.is_empty() {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "no BIN rows in request body"})),
+        )
+            .into_response();
+    }
+
+    match state.bins_repo.import(&records).await {
+        Ok(upserted) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({"parsed": records.len(), "upserted": upserted})),
+        )
+            .into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn lookup_bin(State(state): State<AppState>, Path(bin): Path<String>) -> impl IntoResponse {
+    let config = state.routing_config.current();
+    match config.bin_info(&bin) {
+        Some(record) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({
+                "bin": record,
+                "issuer": config.bank_from_bin(&bin),
+                "index_size": config.bins.len(),
+                "config_version": config.version,
+            })),
+        )
+            .into_response(),
+        None => (
+            axum::http::StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "BIN not found"})),
+        )
+            .into_response(),
+    }
+}
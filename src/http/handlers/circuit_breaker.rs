@@ -1,5 +1,4 @@
 use crate::circuit::state::{CircuitSnapshot, CircuitState};
-use crate::circuit::store_redis::CircuitStoreRedis;
 use crate::AppState;
 use axum::extract::{Path, State};
 use axum::response::IntoResponse;
@@ -21,7 +20,7 @@ pub struct CircuitStatusItem {
 }
 
 pub async fn status(State(state): State<AppState>) -> impl IntoResponse {
-    let store = CircuitStoreRedis::new(state.redis_client.clone());
+    let store = &state.payment_service.circuit_store;
     let gateways = match state.gateways_repo.list_all().await {
         Ok(v) => v,
         Err(e) => {
@@ -64,7 +63,7 @@ pub async fn force_open(
     State(state): State<AppState>,
     Path((gateway, method)): Path<(String, String)>,
 ) -> impl IntoResponse {
-    let store = CircuitStoreRedis::new(state.redis_client.clone());
+    let store = &state.payment_service.circuit_store;
     let m = method.to_uppercase();
     if let Err(e) = store.set_override(&gateway, &m, "FORCE_OPEN").await {
         return (
@@ -95,7 +94,7 @@ pub async fn force_close(
     State(state): State<AppState>,
     Path((gateway, method)): Path<(String, String)>,
 ) -> impl IntoResponse {
-    let store = CircuitStoreRedis::new(state.redis_client.clone());
+    let store = &state.payment_service.circuit_store;
     let m = method.to_uppercase();
     if let Err(e) = store.set_override(&gateway, &m, "FORCE_CLOSED").await {
         return (
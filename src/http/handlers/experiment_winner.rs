@@ -1,3 +1,4 @@
+use crate::domain::experiment::AnalysisMethod;
 use crate::experiments::analyzer::analyze;
 use crate::AppState;
 use axum::extract::{Path, State};
@@ -9,6 +10,23 @@ pub async fn get_experiment_winner(
     State(state): State<AppState>,
     Path(experiment_id): Path<Uuid>,
 ) -> impl IntoResponse {
+    let experiment = match state.experiments_repo.get(experiment_id).await {
+        Ok(Some(exp)) => exp,
+        Ok(None) => {
+            return (
+                axum::http::StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "experiment not found"})),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response();
+        }
+    };
     let results = match state.experiments_repo.results(experiment_id).await {
         Ok(v) => v,
         Err(e) => {
@@ -20,6 +38,7 @@ pub async fn get_experiment_winner(
         }
     };
 
-    let analysis = analyze(&results, 100);
+    let method = AnalysisMethod::parse(&experiment.analysis_method).unwrap_or_default();
+    let analysis = analyze(&results, 100, method);
     (axum::http::StatusCode::OK, Json(analysis)).into_response()
 }
@@ -1,3 +1,7 @@
+use crate::bins::index::validate_card_filters;
+use crate::domain::experiment::{AnalysisMethod, ExperimentStatus, ExperimentVariant, RampStep};
+use crate::experiments::assigner::{legacy_variants, validate_variants, CONTROL_VARIANT};
+use crate::experiments::lifecycle::{manual_transition, validate_ramp, LifecycleAction};
 use crate::repo::experiments_repo::{CreateExperimentFilterInput, CreateExperimentInput};
 use crate::AppState;
 use axum::extract::{Path, State};
@@ -8,9 +12,18 @@ use uuid::Uuid;
 #[derive(Debug, serde::Deserialize)]
 pub struct CreateExperimentRequest {
     pub name: String,
-    pub traffic_control_pct: i32,
-    pub traffic_treatment_pct: i32,
-    pub treatment_gateway: String,
+    #[serde(default)]
+    pub traffic_control_pct: Option<i32>,
+    #[serde(default)]
+    pub traffic_treatment_pct: Option<i32>,
+    #[serde(default)]
+    pub treatment_gateway: Option<String>,
+    #[serde(default)]
+    pub variants: Vec<ExperimentVariant>,
+    #[serde(default)]
+    pub ramp_schedule: Vec<RampStep>,
+    #[serde(default)]
+    pub analysis_method: Option<String>,
     pub start_date: chrono::DateTime<chrono::Utc>,
     pub end_date: Option<chrono::DateTime<chrono::Utc>>,
     pub created_by: String,
@@ -24,26 +37,111 @@ pub struct CreateExperimentFilterRequest {
     pub max_amount_minor: Option<i64>,
     pub merchant_id: Option<String>,
     pub amount_bucket: Option<String>,
+    #[serde(default)]
+    pub card_network: Option<String>,
+    #[serde(default)]
+    pub card_type: Option<String>,
 }
 
 pub async fn create_experiment(
     State(state): State<AppState>,
     Json(req): Json<CreateExperimentRequest>,
 ) -> impl IntoResponse {
+    if let Err(msg) = validate_card_filters(req.filter.card_network.as_deref(), req.filter.card_type.as_deref()) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+    let variants = if req.variants.is_empty() {
+        match (req.traffic_control_pct, req.traffic_treatment_pct, req.treatment_gateway.as_deref()) {
+            (Some(control), Some(treatment), Some(gateway)) => legacy_variants(control, treatment, gateway),
+            _ => {
+                return (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": "pass variants, or traffic_control_pct, traffic_treatment_pct and treatment_gateway"
+                    })),
+                )
+                    .into_response()
+            }
+        }
+    } else {
+        req.variants
+    };
+    if let Err(msg) = validate_variants(&variants) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+    if let Err(msg) = validate_ramp(&req.ramp_schedule) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+    let analysis_method = match req.analysis_method.as_deref() {
+        None => AnalysisMethod::default(),
+        Some(value) => match AnalysisMethod::parse(value) {
+            Some(method) => method,
+            None => {
+                return (
+                    axum::http::StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({"error": format!("unknown analysis_method {}", value)})),
+                )
+                    .into_response()
+            }
+        },
+    };
+    if req.end_date.is_some_and(|end| end <= req.start_date) {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "end_date must be after start_date"})),
+        )
+            .into_response();
+    }
+    let config = state.routing_config.current();
+    if let Some(profile_id) = variants
+        .iter()
+        .filter_map(|v| v.scoring_profile_id.as_ref())
+        .find(|id| !config.profiles.contains_key(*id))
+    {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("unknown scoring profile {}", profile_id)})),
+        )
+            .into_response();
+    }
+    if let Some(rule_set) = variants
+        .iter()
+        .filter_map(|v| v.rule_set.as_ref())
+        .find(|rs| !config.has_rule_set(rs))
+    {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": format!("unknown rule set {}", rule_set)})),
+        )
+            .into_response();
+    }
+    let control_pct = variants
+        .iter()
+        .find(|v| v.variant_name == CONTROL_VARIANT)
+        .map(|v| v.traffic_pct)
+        .unwrap_or_default();
     let input = CreateExperimentInput {
         name: req.name,
-        traffic_control_pct: req.traffic_control_pct,
-        traffic_treatment_pct: req.traffic_treatment_pct,
-        treatment_gateway: req.treatment_gateway,
+        traffic_control_pct: control_pct,
+        traffic_treatment_pct: 100 - control_pct,
+        treatment_gateway: variants
+            .iter()
+            .find_map(|v| v.override_gateway.clone())
+            .unwrap_or_default(),
         start_date: req.start_date,
         end_date: req.end_date,
         created_by: req.created_by,
+        variants,
+        ramp_schedule: req.ramp_schedule,
+        analysis_method,
         filter: CreateExperimentFilterInput {
             payment_method: req.filter.payment_method,
             min_amount_minor: req.filter.min_amount_minor,
             max_amount_minor: req.filter.max_amount_minor,
             merchant_id: req.filter.merchant_id,
             amount_bucket: req.filter.amount_bucket,
+            card_network: req.filter.card_network,
+            card_type: req.filter.card_type,
         },
     };
 
@@ -82,12 +180,86 @@ pub async fn get_results(
     }
 }
 
+pub async fn get_history(
+    State(state): State<AppState>,
+    Path(experiment_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.experiments_repo.history(experiment_id).await {
+        Ok(rows) => (axum::http::StatusCode::OK, Json(rows)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn pause_experiment(
+    State(state): State<AppState>,
+    Path(experiment_id): Path<Uuid>,
+) -> impl IntoResponse {
+    change_status(&state, experiment_id, LifecycleAction::Paused).await
+}
+
+pub async fn resume_experiment(
+    State(state): State<AppState>,
+    Path(experiment_id): Path<Uuid>,
+) -> impl IntoResponse {
+    change_status(&state, experiment_id, LifecycleAction::Resumed).await
+}
+
 pub async fn stop_experiment(
     State(state): State<AppState>,
     Path(experiment_id): Path<Uuid>,
 ) -> impl IntoResponse {
-    match state.experiments_repo.stop(experiment_id).await {
-        Ok(_) => (axum::http::StatusCode::OK, Json(serde_json::json!({"stopped": true}))).into_response(),
+    change_status(&state, experiment_id, LifecycleAction::Stopped).await
+}
+
+async fn change_status(state: &AppState, experiment_id: Uuid, action: LifecycleAction) -> axum::response::Response {
+    let experiment = match state.experiments_repo.get(experiment_id).await {
+        Ok(Some(experiment)) => experiment,
+        Ok(None) => {
+            return (
+                axum::http::StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "experiment not found"})),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response()
+        }
+    };
+    let Some(status) = ExperimentStatus::parse(&experiment.status) else {
+        return (
+            axum::http::StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": format!("unknown experiment status {}", experiment.status)})),
+        )
+            .into_response();
+    };
+    let transition = match manual_transition(status, action) {
+        Ok(transition) => transition,
+        Err(msg) => return (axum::http::StatusCode::CONFLICT, Json(serde_json::json!({"error": msg}))).into_response(),
+    };
+
+    match state.experiments_repo.transition(&experiment, &transition, "api").await {
+        Ok(true) => (
+            axum::http::StatusCode::OK,
+            Json(serde_json::json!({
+                "experiment_id": experiment_id,
+                "action": transition.action,
+                "status": transition.to_status,
+            })),
+        )
+            .into_response(),
+        Ok(false) => (
+            axum::http::StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "experiment status changed concurrently, retry"})),
+        )
+            .into_response(),
         Err(e) => (
             axum::http::StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({"error": e.to_string()})),
@@ -13,6 +13,8 @@ pub struct GatewayView {
     pub priority: i32,
     pub supported_methods: Vec<String>,
     pub timeout_ms: i32,
+    pub max_in_flight: Option<i32>,
+    pub max_tps: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -20,6 +22,8 @@ pub struct UpdateGatewayRequest {
     pub is_enabled: bool,
     pub priority: i32,
     pub supported_methods: Vec<String>,
+    pub max_in_flight: Option<i32>,
+    pub max_tps: Option<i32>,
 }
 
 pub async fn list_gateways(State(state): State<AppState>) -> impl IntoResponse {
@@ -35,6 +39,8 @@ pub async fn list_gateways(State(state): State<AppState>) -> impl IntoResponse {
                     priority: g.priority,
                     supported_methods: g.supported_methods,
                     timeout_ms: g.timeout_ms,
+                    max_in_flight: g.max_in_flight,
+                    max_tps: g.max_tps,
                 })
                 .collect();
             (axum::http::StatusCode::OK, Json(resp)).into_response()
@@ -59,6 +65,8 @@ pub async fn update_gateway(
             req.is_enabled,
             req.priority,
             req.supported_methods,
+            req.max_in_flight,
+            req.max_tps,
         )
         .await
     {
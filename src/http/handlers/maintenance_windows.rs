@@ -0,0 +1,75 @@
+use crate::scoring::schedule::{validate_window, MaintenanceWindowInput};
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+
+pub async fn list_windows(State(state): State<AppState>) -> impl IntoResponse {
+    match state.maintenance_windows_repo.list().await {
+        Ok(rows) => (axum::http::StatusCode::OK, Json(rows)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn create_window(
+    State(state): State<AppState>,
+    Json(input): Json<MaintenanceWindowInput>,
+) -> impl IntoResponse {
+    if let Err(msg) = validate_window(&input) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+
+    match state.maintenance_windows_repo.create(input).await {
+        Ok(window) => (axum::http::StatusCode::CREATED, Json(window)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn update_window(
+    State(state): State<AppState>,
+    Path(window_id): Path<Uuid>,
+    Json(input): Json<MaintenanceWindowInput>,
+) -> impl IntoResponse {
+    if let Err(msg) = validate_window(&input) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+
+    match state.maintenance_windows_repo.update(window_id, input).await {
+        Ok(Some(window)) => (axum::http::StatusCode::OK, Json(window)).into_response(),
+        Ok(None) => not_found(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn delete_window(State(state): State<AppState>, Path(window_id): Path<Uuid>) -> impl IntoResponse {
+    match state.maintenance_windows_repo.delete(window_id).await {
+        Ok(true) => (axum::http::StatusCode::OK, Json(serde_json::json!({"deleted": true}))).into_response(),
+        Ok(false) => not_found(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+fn not_found() -> axum::response::Response {
+    (
+        axum::http::StatusCode::NOT_FOUND,
+        Json(serde_json::json!({"error": "maintenance window not found"})),
+    )
+        .into_response()
+}
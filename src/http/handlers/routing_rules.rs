@@ -0,0 +1,107 @@
+use crate::repo::routing_rules_repo::RoutingRuleInput;
+use crate::router::rules::validate_rule;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+
+pub async fn list_rules(State(state): State<AppState>) -> impl IntoResponse {
+    match state.routing_rules_repo.list().await {
+        Ok(rows) => (axum::http::StatusCode::OK, Json(rows)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn get_rule(State(state): State<AppState>, Path(rule_id): Path<Uuid>) -> impl IntoResponse {
+    match state.routing_rules_repo.get(rule_id).await {
+        Ok(Some(rule)) => (axum::http::StatusCode::OK, Json(rule)).into_response(),
+        Ok(None) => not_found(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn create_rule(
+    State(state): State<AppState>,
+    Json(input): Json<RoutingRuleInput>,
+) -> impl IntoResponse {
+    if let Some(resp) = invalid_rule(&state, &input).await {
+        return resp;
+    }
+
+    match state.routing_rules_repo.create(input).await {
+        Ok(rule) => (axum::http::StatusCode::CREATED, Json(rule)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn update_rule(
+    State(state): State<AppState>,
+    Path(rule_id): Path<Uuid>,
+    Json(input): Json<RoutingRuleInput>,
+) -> impl IntoResponse {
+    if let Some(resp) = invalid_rule(&state, &input).await {
+        return resp;
+    }
+
+    match state.routing_rules_repo.update(rule_id, input).await {
+        Ok(Some(rule)) => (axum::http::StatusCode::OK, Json(rule)).into_response(),
+        Ok(None) => not_found(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+async fn invalid_rule(state: &AppState, input: &RoutingRuleInput) -> Option<axum::response::Response> {
+    let gateways = match state.gateways_repo.list_all().await {
+        Ok(v) => v,
+        Err(e) => {
+            return Some(
+                (
+                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": e.to_string()})),
+                )
+                    .into_response(),
+            )
+        }
+    };
+    let known: Vec<String> = gateways.into_iter().map(|g| g.gateway_id).collect();
+    validate_rule(input.action, &input.gateway_id, input.boost, &input.conditions, &known)
+        .err()
+        .map(|msg| (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response())
+}
+
+pub async fn delete_rule(State(state): State<AppState>, Path(rule_id): Path<Uuid>) -> impl IntoResponse {
+    match state.routing_rules_repo.delete(rule_id).await {
+        Ok(true) => (axum::http::StatusCode::OK, Json(serde_json::json!({"deleted": true}))).into_response(),
+        Ok(false) => not_found(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+fn not_found() -> axum::response::Response {
+    (
+        axum::http::StatusCode::NOT_FOUND,
+        Json(serde_json::json!({"error": "routing rule not found"})),
+    )
+        .into_response()
+}
@@ -1,7 +1,10 @@
-use crate::metrics::amount_bucket::from_amount_minor;
+use crate::domain::routing_decision::{DroppedGateway, PenalizedGateway};
+use crate::metrics::amount_bucket::DEFAULT_CURRENCY;
+use crate::scoring::cost::{cost_score, expected_fee_minor};
 use crate::scoring::engine::rank_gateways;
-use crate::scoring::metrics_reader::read_metric_for_gateway;
-use crate::scoring::types::{GatewayCandidate, ScoreInputs, ScoreWeights};
+use crate::scoring::metrics_reader::read_metrics_for_gateways;
+use crate::scoring::schedule::{apply_penalties, MaintenanceMode};
+use crate::scoring::types::{GatewayCandidate, ScoreInputs};
 use crate::AppState;
 use axum::extract::{Query, State};
 use axum::response::IntoResponse;
@@ -11,35 +14,59 @@ use serde::Deserialize;
 #[derive(Debug, Deserialize)]
 pub struct DebugQuery {
     pub amount_minor: i64,
+    pub currency: Option<String>,
     pub payment_method: String,
     pub issuing_bank: String,
+    pub card_network: Option<String>,
+    pub card_type: Option<String>,
+    pub card_bin: Option<String>,
+    pub merchant_id: Option<String>,
 }
 
 pub async fn scoring_debug(
     State(state): State<AppState>,
     Query(query): Query<DebugQuery>,
 ) -> impl IntoResponse {
+    let config = state.payment_service.routing_config.current();
     let method = query.payment_method.to_uppercase();
-    let available = match state.gateways_repo.list_enabled_by_method(&method).await {
-        Ok(v) => v,
-        Err(e) => {
-            return (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": e.to_string()})),
-            )
-                .into_response()
-        }
-    };
+    let mut available = config.enabled_gateways(&method);
 
-    let weights = match state.payment_service.scoring_config_repo.load_weights().await {
-        Ok(w) => ScoreWeights {
-            success_rate_weight: w.success_rate_weight,
-            latency_weight: w.latency_weight,
-            method_affinity_weight: w.method_affinity_weight,
-            bank_affinity_weight: w.bank_affinity_weight,
-            amount_fit_weight: w.amount_fit_weight,
-            time_weight: w.time_weight,
-        },
+    let issuer = config.canonical_issuer(None, Some(&query.issuing_bank));
+    let amount_bucket = config.amount_bucket(query.currency.as_deref().unwrap_or(DEFAULT_CURRENCY), query.amount_minor);
+    let scoring_profile = config.resolve_profile(query.merchant_id.as_deref().unwrap_or_default(), &method, &amount_bucket);
+    let weights = config.weights_for(scoring_profile.as_ref());
+    let signal_blend = config.blend_for(scoring_profile.as_ref());
+    let now = chrono::Utc::now();
+
+    let mut card = query
+        .card_bin
+        .as_deref()
+        .map(|bin| config.card_attributes(bin))
+        .unwrap_or_default();
+    if let Some(network) = &query.card_network {
+        card.card_network = Some(network.to_uppercase());
+    }
+    if let Some(card_type) = &query.card_type {
+        card.card_type = Some(card_type.to_uppercase());
+    }
+    let mut dropped = Vec::new();
+    available.retain(|g| match config.maintenance_window(&g.gateway_id, &method, &issuer, now) {
+        Some(window) if window.mode == MaintenanceMode::Exclude => {
+            dropped.push(DroppedGateway {
+                gateway_id: g.gateway_id.clone(),
+                reason: "maintenance".to_string(),
+                maintenance_window_id: Some(window.window_id),
+            });
+            false
+        }
+        _ => true,
+    });
+    let mut candidates = Vec::new();
+    let mut expected_fees = serde_json::Map::new();
+    let mut penalized = Vec::new();
+    let gateway_ids: Vec<String> = available.iter().map(|g| g.gateway_id.clone()).collect();
+    let metrics = match read_metrics_for_gateways(&state.metrics_hot_store, &gateway_ids, &method, &issuer, &signal_blend).await {
+        Ok(m) => m,
         Err(e) => {
             return (
                 axum::http::StatusCode::INTERNAL_SERVER_ERROR,
@@ -48,52 +75,34 @@ pub async fn scoring_debug(
                 .into_response()
         }
     };
-
-    let amount_bucket = from_amount_minor(query.amount_minor);
-    let mut candidates = Vec::new();
     for gateway in available {
-        let metric = match read_metric_for_gateway(
-            &state.metrics_hot_store,
-            &gateway.gateway_id,
-            &method,
-            &query.issuing_bank,
-        )
-        .await
+        let metric = metrics.get(&gateway.gateway_id).cloned().unwrap_or_default();
+
+        let method_affinity = config.method_affinity(&gateway.gateway_id, &method);
+        let amount_fit = config.amount_fit(&gateway.gateway_id, &amount_bucket);
+        let time_multiplier = config.time_multiplier(&gateway.gateway_id, now);
+        if let Some(window) = config
+            .maintenance_window(&gateway.gateway_id, &method, &issuer, now)
+            .filter(|w| w.mode == MaintenanceMode::Penalize)
         {
-            Ok(m) => m,
-            Err(e) => {
-                return (
-                    axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({"error": e.to_string()})),
-                )
-                    .into_response()
-            }
-        };
+            penalized.push(PenalizedGateway {
+                gateway_id: gateway.gateway_id.clone(),
+                maintenance_window_id: window.window_id,
+                penalty_multiplier: window.penalty_multiplier,
+            });
+        }
 
-        let method_affinity = state
-            .payment_service
-            .scoring_config_repo
-            .method_affinity(&gateway.gateway_id, &method)
-            .await
-            .unwrap_or(0.7);
-        let amount_fit = state
-            .payment_service
-            .scoring_config_repo
-            .amount_fit(&gateway.gateway_id, &amount_bucket)
-            .await
-            .unwrap_or(0.7);
-        let time_multiplier = state
-            .payment_service
-            .scoring_config_repo
-            .time_multiplier(&gateway.gateway_id, chrono::Utc::now())
-            .await
-            .unwrap_or(1.0);
+        let bank_affinity = config.issuer_affinity(&gateway.gateway_id, &issuer);
 
-        let bank_affinity = if gateway.gateway_name.to_uppercase() == query.issuing_bank.to_uppercase() {
-            1.0
-        } else {
-            0.5
-        };
+        let fee_slabs = config.fee_slabs(&gateway.gateway_id, &method);
+        let expected_fee = expected_fee_minor(
+            fee_slabs,
+            &method,
+            card.card_network.as_deref(),
+            card.card_type.as_deref(),
+            query.amount_minor,
+        );
+        expected_fees.insert(gateway.gateway_id.clone(), serde_json::json!(expected_fee));
 
         candidates.push(GatewayCandidate {
             gateway,
@@ -104,11 +113,18 @@ pub async fn scoring_debug(
                 bank_affinity,
                 amount_fit,
                 time_multiplier,
+                cost_score: cost_score(expected_fee, query.amount_minor),
+                metric_level: metric.level,
+                signal_windows: metric.windows,
             },
         });
     }
 
-    let ranked = rank_gateways(&candidates, &weights);
+    let penalties = penalized
+        .iter()
+        .map(|p: &PenalizedGateway| (p.gateway_id.clone(), p.penalty_multiplier))
+        .collect();
+    let ranked = apply_penalties(rank_gateways(&candidates, &weights), &penalties);
     (
         axum::http::StatusCode::OK,
         Json(serde_json::json!({
@@ -116,7 +132,18 @@ pub async fn scoring_debug(
                 "amount_minor": query.amount_minor,
                 "payment_method": method,
                 "issuing_bank": query.issuing_bank,
+                "issuer": issuer,
+                "card_network": card.card_network,
+                "card_type": card.card_type,
+                "card_bin": query.card_bin,
+                "merchant_id": query.merchant_id,
             },
+            "scoring_profile": scoring_profile,
+            "signal_blend": signal_blend,
+            "config_version": config.version,
+            "expected_fee_minor": expected_fees,
+            "dropped_gateways": dropped,
+            "penalized_gateways": penalized,
             "ranked": ranked
         })),
     )
@@ -0,0 +1,141 @@
+use crate::repo::scoring_profiles_repo::{AssignmentInput, NewProfileVersion};
+use crate::scoring::blend::validate_blend;
+use crate::scoring::profiles::validate_weights;
+use crate::scoring::types::ScoreWeights;
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+use uuid::Uuid;
+
+pub async fn list_profiles(State(state): State<AppState>) -> impl IntoResponse {
+    match state.scoring_profiles_repo.list().await {
+        Ok(rows) => (axum::http::StatusCode::OK, Json(rows)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn list_versions(
+    State(state): State<AppState>,
+    Path(profile_id): Path<String>,
+) -> impl IntoResponse {
+    match state.scoring_profiles_repo.versions(&profile_id).await {
+        Ok(rows) if rows.is_empty() => (
+            axum::http::StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "scoring profile not found"})),
+        )
+            .into_response(),
+        Ok(rows) => (axum::http::StatusCode::OK, Json(rows)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn validate_profile(Json(weights): Json<ScoreWeights>) -> impl IntoResponse {
+    match validate_weights(&weights) {
+        Ok(_) => (axum::http::StatusCode::OK, Json(serde_json::json!({"valid": true}))).into_response(),
+        Err(msg) => (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"valid": false, "error": msg})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn create_version(
+    State(state): State<AppState>,
+    Path(profile_id): Path<String>,
+    Json(input): Json<NewProfileVersion>,
+) -> impl IntoResponse {
+    if let Err(msg) = validate_weights(&input.weights) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+    if let Some(Err(msg)) = input.signal_blend.as_ref().map(validate_blend) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+    if input.author.trim().is_empty() {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "author is required"})),
+        )
+            .into_response();
+    }
+
+    match state.scoring_profiles_repo.create_version(&profile_id, input).await {
+        Ok(version) => (axum::http::StatusCode::CREATED, Json(version)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn list_assignments(State(state): State<AppState>) -> impl IntoResponse {
+    match state.scoring_profiles_repo.list_assignments().await {
+        Ok(rows) => (axum::http::StatusCode::OK, Json(rows)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn upsert_assignment(
+    State(state): State<AppState>,
+    Json(input): Json<AssignmentInput>,
+) -> impl IntoResponse {
+    match state.scoring_profiles_repo.current(&input.profile_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "unknown profile_id"})),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response()
+        }
+    }
+
+    match state.scoring_profiles_repo.upsert_assignment(input).await {
+        Ok(row) => (axum::http::StatusCode::OK, Json(row)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn delete_assignment(
+    State(state): State<AppState>,
+    Path(assignment_id): Path<Uuid>,
+) -> impl IntoResponse {
+    match state.scoring_profiles_repo.delete_assignment(assignment_id).await {
+        Ok(true) => (axum::http::StatusCode::OK, Json(serde_json::json!({"deleted": true}))).into_response(),
+        Ok(false) => (
+            axum::http::StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "assignment not found"})),
+        )
+            .into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
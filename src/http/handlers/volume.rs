@@ -0,0 +1,67 @@
+use crate::volume::pacing::{progress as volume_progress, validate_target, VolumeTarget};
+use crate::AppState;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use axum::Json;
+
+pub async fn list_targets(State(state): State<AppState>) -> impl IntoResponse {
+    match state.volume_targets_repo.list().await {
+        Ok(rows) => (axum::http::StatusCode::OK, Json(rows)).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn upsert_target(
+    State(state): State<AppState>,
+    Path(gateway_id): Path<String>,
+    Json(mut target): Json<VolumeTarget>,
+) -> impl IntoResponse {
+    target.gateway_id = gateway_id;
+    if let Err(msg) = validate_target(&target) {
+        return (axum::http::StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": msg}))).into_response();
+    }
+
+    match state.volume_targets_repo.upsert(target).await {
+        Ok(_) => (axum::http::StatusCode::OK, Json(serde_json::json!({"updated": true}))).into_response(),
+        Err(e) => (
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+    }
+}
+
+pub async fn progress(State(state): State<AppState>) -> impl IntoResponse {
+    let targets = match state.volume_targets_repo.list().await {
+        Ok(v) => v,
+        Err(e) => {
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response()
+        }
+    };
+
+    let now = chrono::Utc::now();
+    let gateway_ids: Vec<String> = targets.iter().map(|t| t.gateway_id.clone()).collect();
+    let usages = state
+        .payment_service
+        .volume_store
+        .read_usages(&gateway_ids, now)
+        .await
+        .unwrap_or_default();
+    let items: Vec<_> = targets
+        .iter()
+        .map(|target| {
+            let usage = usages.get(&target.gateway_id).copied().unwrap_or_default();
+            volume_progress(target, &usage, now)
+        })
+        .collect();
+
+    (axum::http::StatusCode::OK, Json(items)).into_response()
+}
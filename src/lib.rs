@@ -4,6 +4,7 @@ pub mod domain {
     pub mod experiment;
     pub mod payment;
     pub mod routing_decision;
+    pub mod routing_rule;
 }
 pub mod gateways;
 pub mod http {
@@ -12,48 +13,67 @@ pub mod http {
         pub mod rate_limit;
     }
     pub mod handlers {
+        pub mod amount_buckets;
         pub mod bandit;
+        pub mod bins;
         pub mod circuit_breaker;
         pub mod experiment_winner;
         pub mod experiments;
         pub mod gateways;
+        pub mod maintenance_windows;
         pub mod metrics;
         pub mod ops;
         pub mod payment_attempts;
         pub mod payments;
         pub mod retry_policy;
         pub mod routing_decisions;
+        pub mod routing_rules;
         pub mod scoring_debug;
+        pub mod scoring_profiles;
+        pub mod volume;
     }
 }
 pub mod metrics;
+pub mod redis_conn;
 pub mod repo {
+    pub mod amount_buckets_repo;
+    pub mod backtest_repo;
     pub mod bandit_repo;
+    pub mod bins_repo;
     pub mod circuit_breaker_config_repo;
     pub mod error_classification_repo;
     pub mod gateways_repo;
+    pub mod issuers_repo;
+    pub mod maintenance_windows_repo;
     pub mod outbox_repo;
     pub mod payment_attempts_repo;
     pub mod payment_verification_repo;
     pub mod payments_repo;
     pub mod experiments_repo;
     pub mod routing_decisions_repo;
+    pub mod routing_rules_repo;
     pub mod retry_policy_repo;
     pub mod scoring_config_repo;
+    pub mod scoring_profiles_repo;
+    pub mod volume_targets_repo;
     pub mod webhook_repo;
 }
 pub mod router {
     pub mod round_robin;
+    pub mod rules;
 }
 pub mod scoring;
 pub mod circuit;
 pub mod bandit;
+pub mod bins;
 pub mod experiments;
+pub mod volume;
 pub mod service {
-    pub mod config_cache;
     pub mod outbox_relay;
     pub mod payment_service;
     pub mod retry_orchestrator;
+    pub mod routing_config;
+    pub mod volume_reconciler;
     pub mod webhook_dispatcher;
 }
 
@@ -64,13 +84,20 @@ pub struct AppState {
     pub metrics_hot_store: metrics::store_redis::MetricsHotStore,
     pub experiments_repo: repo::experiments_repo::ExperimentsRepo,
     pub routing_decisions_repo: repo::routing_decisions_repo::RoutingDecisionsRepo,
+    pub routing_rules_repo: repo::routing_rules_repo::RoutingRulesRepo,
     pub circuit_breaker_config_repo: repo::circuit_breaker_config_repo::CircuitBreakerConfigRepo,
     pub payment_attempts_repo: repo::payment_attempts_repo::PaymentAttemptsRepo,
     pub retry_policy_repo: repo::retry_policy_repo::RetryPolicyRepo,
     pub payment_verification_repo: repo::payment_verification_repo::PaymentVerificationRepo,
     pub bandit_repo: repo::bandit_repo::BanditRepo,
+    pub amount_buckets_repo: repo::amount_buckets_repo::AmountBucketsRepo,
+    pub backtest_repo: repo::backtest_repo::BacktestRepo,
+    pub bins_repo: repo::bins_repo::BinsRepo,
+    pub maintenance_windows_repo: repo::maintenance_windows_repo::MaintenanceWindowsRepo,
+    pub volume_targets_repo: repo::volume_targets_repo::VolumeTargetsRepo,
+    pub scoring_profiles_repo: repo::scoring_profiles_repo::ScoringProfilesRepo,
     pub redis_client: redis::Client,
     pub webhook_dispatcher: service::webhook_dispatcher::WebhookDispatcher,
-    pub config_cache: service::config_cache::ConfigCache,
+    pub routing_config: service::routing_config::RoutingConfigStore,
     pub stream_key: String,
 }
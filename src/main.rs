@@ -1,32 +1,47 @@
+use axum::extract::DefaultBodyLimit;
 use axum::middleware::from_fn_with_state;
 use axum::routing::{get, patch, post, put};
 use axum::Router;
 use payments_gateway::config::AppConfig;
 use payments_gateway::circuit::store_redis::CircuitStoreRedis;
+use payments_gateway::gateways::bulkhead::GatewayBulkhead;
 use payments_gateway::gateways::razorpay::RazorpayGateway;
 use payments_gateway::metrics::store_redis::MetricsHotStore;
+use payments_gateway::redis_conn::SharedRedis;
 use payments_gateway::repo::circuit_breaker_config_repo::CircuitBreakerConfigRepo;
+use payments_gateway::repo::amount_buckets_repo::AmountBucketsRepo;
 use payments_gateway::repo::bandit_repo::BanditRepo;
+use payments_gateway::repo::bins_repo::BinsRepo;
 use payments_gateway::repo::error_classification_repo::ErrorClassificationRepo;
 use payments_gateway::repo::experiments_repo::ExperimentsRepo;
 use payments_gateway::repo::gateways_repo::GatewaysRepo;
+use payments_gateway::repo::issuers_repo::IssuersRepo;
+use payments_gateway::repo::maintenance_windows_repo::MaintenanceWindowsRepo;
+use payments_gateway::repo::backtest_repo::BacktestRepo;
 use payments_gateway::repo::outbox_repo::OutboxRepo;
 use payments_gateway::repo::payment_attempts_repo::PaymentAttemptsRepo;
 use payments_gateway::repo::payment_verification_repo::PaymentVerificationRepo;
 use payments_gateway::repo::payments_repo::PaymentsRepo;
 use payments_gateway::repo::retry_policy_repo::RetryPolicyRepo;
 use payments_gateway::repo::routing_decisions_repo::RoutingDecisionsRepo;
+use payments_gateway::repo::routing_rules_repo::RoutingRulesRepo;
 use payments_gateway::repo::scoring_config_repo::ScoringConfigRepo;
+use payments_gateway::repo::scoring_profiles_repo::ScoringProfilesRepo;
+use payments_gateway::repo::volume_targets_repo::VolumeTargetsRepo;
 use payments_gateway::repo::webhook_repo::WebhookRepo;
-use payments_gateway::service::config_cache::ConfigCache;
 use payments_gateway::service::outbox_relay::OutboxRelay;
 use payments_gateway::service::payment_service::PaymentService;
+use payments_gateway::service::routing_config::{RoutingConfigLoader, RoutingConfigReloader, RoutingConfigStore};
+use payments_gateway::service::volume_reconciler::VolumeReconciler;
 use payments_gateway::service::webhook_dispatcher::WebhookDispatcher;
+use payments_gateway::volume::store_redis::VolumeStoreRedis;
 use payments_gateway::AppState;
 use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
 use tracing_subscriber::EnvFilter;
 
+const BIN_IMPORT_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt()
@@ -43,13 +58,16 @@ async fn main() -> anyhow::Result<()> {
     sqlx::migrate!("./migrations").run(&pool).await?;
 
     let redis_client = redis::Client::open(cfg.redis_url.clone())?;
-    let metrics_hot_store = MetricsHotStore::new(&cfg.redis_url)?;
+    let shared_redis = SharedRedis::open(&cfg.redis_url)?;
+    let metrics_hot_store = MetricsHotStore::with_redis(shared_redis.clone());
 
     let gateways_repo = GatewaysRepo { pool: pool.clone() };
     let payments_repo = PaymentsRepo { pool: pool.clone() };
     let outbox_repo = OutboxRepo { pool: pool.clone() };
     let scoring_config_repo = ScoringConfigRepo { pool: pool.clone() };
+    let scoring_profiles_repo = ScoringProfilesRepo { pool: pool.clone() };
     let routing_decisions_repo = RoutingDecisionsRepo { pool: pool.clone() };
+    let routing_rules_repo = RoutingRulesRepo { pool: pool.clone() };
     let circuit_breaker_config_repo = CircuitBreakerConfigRepo { pool: pool.clone() };
     let payment_attempts_repo = PaymentAttemptsRepo { pool: pool.clone() };
     let retry_policy_repo = RetryPolicyRepo { pool: pool.clone() };
@@ -57,12 +75,34 @@ async fn main() -> anyhow::Result<()> {
     let payment_verification_repo = PaymentVerificationRepo { pool: pool.clone() };
     let experiments_repo = ExperimentsRepo { pool: pool.clone() };
     let bandit_repo = BanditRepo { pool: pool.clone() };
+    let volume_targets_repo = VolumeTargetsRepo { pool: pool.clone() };
+    let issuers_repo = IssuersRepo { pool: pool.clone() };
+    let bins_repo = BinsRepo { pool: pool.clone() };
+    let amount_buckets_repo = AmountBucketsRepo { pool: pool.clone() };
+    let maintenance_windows_repo = MaintenanceWindowsRepo { pool: pool.clone() };
+    let backtest_repo = BacktestRepo { pool: pool.clone() };
+    let volume_store = VolumeStoreRedis::new(shared_redis.clone());
     let webhook_repo = WebhookRepo { pool: pool.clone() };
     let webhook_dispatcher = WebhookDispatcher {
         webhook_repo: webhook_repo.clone(),
         client: reqwest::Client::new(),
     };
-    let config_cache = ConfigCache::new(scoring_config_repo.clone(), std::time::Duration::from_secs(300));
+    let routing_config_loader = RoutingConfigLoader {
+        gateways_repo: gateways_repo.clone(),
+        scoring_config_repo: scoring_config_repo.clone(),
+        scoring_profiles_repo: scoring_profiles_repo.clone(),
+        routing_rules_repo: routing_rules_repo.clone(),
+        volume_targets_repo: volume_targets_repo.clone(),
+        retry_policy_repo: retry_policy_repo.clone(),
+        error_classification_repo: error_classification_repo.clone(),
+        circuit_breaker_config_repo: circuit_breaker_config_repo.clone(),
+        bandit_repo: bandit_repo.clone(),
+        issuers_repo,
+        bins_repo: bins_repo.clone(),
+        amount_buckets_repo: amount_buckets_repo.clone(),
+        maintenance_windows_repo: maintenance_windows_repo.clone(),
+    };
+    let routing_config = RoutingConfigStore::new(routing_config_loader.load().await?);
     let razorpay = Arc::new(RazorpayGateway {
         base_url: std::env::var("RAZORPAY_BASE_URL")
             .unwrap_or_else(|_| "https://api.razorpay.com".to_string()),
@@ -79,17 +119,15 @@ async fn main() -> anyhow::Result<()> {
         pool: pool.clone(),
         payments_repo,
         outbox_repo: outbox_repo.clone(),
-        gateways_repo: gateways_repo.clone(),
         experiments_repo: experiments_repo.clone(),
         bandit_repo: bandit_repo.clone(),
-        scoring_config_repo,
+        routing_config: routing_config.clone(),
         routing_decisions_repo: routing_decisions_repo.clone(),
-        circuit_breaker_config_repo: circuit_breaker_config_repo.clone(),
         metrics_hot_store: metrics_hot_store.clone(),
-        circuit_store: CircuitStoreRedis::new(redis::Client::open(cfg.redis_url.clone())?),
+        circuit_store: CircuitStoreRedis::new(shared_redis.clone()),
+        bulkhead: GatewayBulkhead::new(shared_redis),
+        volume_store: volume_store.clone(),
         payment_attempts_repo: payment_attempts_repo.clone(),
-        retry_policy_repo: retry_policy_repo.clone(),
-        error_classification_repo: error_classification_repo.clone(),
         payment_verification_repo: payment_verification_repo.clone(),
         webhook_dispatcher: webhook_dispatcher.clone(),
         razorpay,
@@ -102,20 +140,52 @@ async fn main() -> anyhow::Result<()> {
     };
     tokio::spawn(relay.run());
 
+    let volume_reconciler = VolumeReconciler {
+        volume_targets_repo: volume_targets_repo.clone(),
+        volume_store,
+        interval: std::time::Duration::from_secs(
+            std::env::var("VOLUME_RECONCILE_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(60),
+        ),
+    };
+    tokio::spawn(volume_reconciler.run());
+
+    let routing_config_reloader = RoutingConfigReloader {
+        pool: pool.clone(),
+        loader: routing_config_loader,
+        store: routing_config.clone(),
+        fallback_interval: std::time::Duration::from_secs(
+            std::env::var("ROUTING_CONFIG_RELOAD_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(300),
+        ),
+    };
+    tokio::spawn(routing_config_reloader.run());
+
     let state = AppState {
         payment_service,
         gateways_repo,
         metrics_hot_store,
         experiments_repo: experiments_repo.clone(),
         routing_decisions_repo,
+        routing_rules_repo,
         circuit_breaker_config_repo,
         payment_attempts_repo,
         retry_policy_repo,
         payment_verification_repo,
         bandit_repo: bandit_repo.clone(),
+        amount_buckets_repo,
+        backtest_repo,
+        bins_repo,
+        maintenance_windows_repo,
+        volume_targets_repo,
+        scoring_profiles_repo,
         redis_client: redis::Client::open(cfg.redis_url.clone())?,
         webhook_dispatcher: webhook_dispatcher.clone(),
-        config_cache,
+        routing_config,
         stream_key: cfg.stream_key.clone(),
     };
 
@@ -141,6 +211,43 @@ async fn main() -> anyhow::Result<()> {
             "/experiments/:id/stop",
             post(payments_gateway::http::handlers::experiments::stop_experiment),
         )
+        .route(
+            "/experiments/:id/pause",
+            post(payments_gateway::http::handlers::experiments::pause_experiment),
+        )
+        .route(
+            "/experiments/:id/resume",
+            post(payments_gateway::http::handlers::experiments::resume_experiment),
+        )
+        .route(
+            "/routing-rules",
+            post(payments_gateway::http::handlers::routing_rules::create_rule),
+        )
+        .route(
+            "/routing-rules/:rule_id",
+            put(payments_gateway::http::handlers::routing_rules::update_rule)
+                .delete(payments_gateway::http::handlers::routing_rules::delete_rule),
+        )
+        .route(
+            "/scoring/profiles/validate",
+            post(payments_gateway::http::handlers::scoring_profiles::validate_profile),
+        )
+        .route(
+            "/scoring/profiles/:profile_id/versions",
+            post(payments_gateway::http::handlers::scoring_profiles::create_version),
+        )
+        .route(
+            "/scoring/profile-assignments",
+            put(payments_gateway::http::handlers::scoring_profiles::upsert_assignment),
+        )
+        .route(
+            "/scoring/profile-assignments/:assignment_id",
+            axum::routing::delete(payments_gateway::http::handlers::scoring_profiles::delete_assignment),
+        )
+        .route(
+            "/volume/targets/:gateway_id",
+            put(payments_gateway::http::handlers::volume::upsert_target),
+        )
         .route(
             "/bandit/policy/:segment/enable",
             post(payments_gateway::http::handlers::bandit::enable_segment),
@@ -149,6 +256,57 @@ async fn main() -> anyhow::Result<()> {
             "/bandit/policy/:segment/disable",
             post(payments_gateway::http::handlers::bandit::disable_segment),
         )
+        .route(
+            "/bandit/policy/:segment/algorithm",
+            put(payments_gateway::http::handlers::bandit::set_algorithm),
+        )
+        .route(
+            "/bandit/evaluate",
+            post(payments_gateway::http::handlers::bandit::evaluate),
+        )
+        .route(
+            "/bandit/policy/:segment/safety",
+            put(payments_gateway::http::handlers::bandit::set_safety),
+        )
+        .route(
+            "/bandit/policy/:segment/reset",
+            post(payments_gateway::http::handlers::bandit::reset_segment),
+        )
+        .route(
+            "/bandit/policy/:segment/reseed",
+            post(payments_gateway::http::handlers::bandit::reseed_segment),
+        )
+        .route(
+            "/bandit/policy/:segment/reward",
+            put(payments_gateway::http::handlers::bandit::set_reward),
+        )
+        .route(
+            "/bandit/policy/:segment/discount",
+            put(payments_gateway::http::handlers::bandit::set_discount),
+        )
+        .route(
+            "/bandit/segment-definitions/:definition_id",
+            put(payments_gateway::http::handlers::bandit::upsert_definition)
+                .delete(payments_gateway::http::handlers::bandit::delete_definition),
+        )
+        .route(
+            "/bins/import",
+            post(payments_gateway::http::handlers::bins::import_bins).layer(DefaultBodyLimit::max(BIN_IMPORT_BODY_LIMIT)),
+        )
+        .route("/bins/:bin", get(payments_gateway::http::handlers::bins::lookup_bin))
+        .route(
+            "/maintenance-windows",
+            post(payments_gateway::http::handlers::maintenance_windows::create_window),
+        )
+        .route(
+            "/maintenance-windows/:window_id",
+            put(payments_gateway::http::handlers::maintenance_windows::update_window)
+                .delete(payments_gateway::http::handlers::maintenance_windows::delete_window),
+        )
+        .route(
+            "/amount-buckets/:currency",
+            put(payments_gateway::http::handlers::amount_buckets::replace_buckets),
+        )
         .layer(from_fn_with_state(
             admin_key,
             payments_gateway::http::middleware::admin_auth::require_internal_api_key,
@@ -182,6 +340,18 @@ async fn main() -> anyhow::Result<()> {
             "/scoring/debug",
             get(payments_gateway::http::handlers::scoring_debug::scoring_debug),
         )
+        .route(
+            "/scoring/profiles",
+            get(payments_gateway::http::handlers::scoring_profiles::list_profiles),
+        )
+        .route(
+            "/scoring/profiles/:profile_id/versions",
+            get(payments_gateway::http::handlers::scoring_profiles::list_versions),
+        )
+        .route(
+            "/scoring/profile-assignments",
+            get(payments_gateway::http::handlers::scoring_profiles::list_assignments),
+        )
         .route(
             "/circuit-breaker/status",
             get(payments_gateway::http::handlers::circuit_breaker::status),
@@ -199,7 +369,33 @@ async fn main() -> anyhow::Result<()> {
             "/experiments/:id/winner",
             get(payments_gateway::http::handlers::experiment_winner::get_experiment_winner),
         )
+        .route(
+            "/experiments/:id/history",
+            get(payments_gateway::http::handlers::experiments::get_history),
+        )
+        .route(
+            "/routing-rules",
+            get(payments_gateway::http::handlers::routing_rules::list_rules),
+        )
+        .route(
+            "/routing-rules/:rule_id",
+            get(payments_gateway::http::handlers::routing_rules::get_rule),
+        )
+        .route("/volume/targets", get(payments_gateway::http::handlers::volume::list_targets))
+        .route("/volume/progress", get(payments_gateway::http::handlers::volume::progress))
+        .route(
+            "/maintenance-windows",
+            get(payments_gateway::http::handlers::maintenance_windows::list_windows),
+        )
+        .route(
+            "/amount-buckets",
+            get(payments_gateway::http::handlers::amount_buckets::list_buckets),
+        )
         .route("/bandit/state", get(payments_gateway::http::handlers::bandit::get_state))
+        .route(
+            "/bandit/segment-definitions",
+            get(payments_gateway::http::handlers::bandit::list_definitions),
+        )
         .route("/ops/readiness", get(payments_gateway::http::handlers::ops::readiness))
         .route("/ops/liveness", get(payments_gateway::http::handlers::ops::liveness))
         .merge(admin_routes)
@@ -4,6 +4,8 @@ use crate::domain::payment::PaymentStatus;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
 
+pub const ROLLUP_ALL: &str = "ALL";
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct MetricKey {
     pub gateway: String,
@@ -11,6 +13,28 @@ pub struct MetricKey {
     pub bank: String,
 }
 
+impl MetricKey {
+    pub fn gateway_method(&self) -> MetricKey {
+        MetricKey {
+            gateway: self.gateway.clone(),
+            method: self.method.clone(),
+            bank: ROLLUP_ALL.to_string(),
+        }
+    }
+
+    pub fn is_rollup(&self) -> bool {
+        self.method.eq_ignore_ascii_case(ROLLUP_ALL) || self.bank.eq_ignore_ascii_case(ROLLUP_ALL)
+    }
+
+    pub fn gateway_only(&self) -> MetricKey {
+        MetricKey {
+            gateway: self.gateway.clone(),
+            method: ROLLUP_ALL.to_string(),
+            bank: ROLLUP_ALL.to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggregatedMetric {
     pub success_rate: f64,
@@ -31,54 +55,6 @@ pub struct SlidingMetrics {
     buckets: HashMap<MetricKey, BTreeMap<i64, MinuteBucket>>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::domain::payment::PaymentStatus;
-    use crate::metrics::event::PaymentEvent;
-    use chrono::TimeZone;
-    use uuid::Uuid;
-
-    #[test]
-    fn computes_window_metric() {
-        let mut s = SlidingMetrics::default();
-        let ts = chrono::Utc.timestamp_opt(1_700_000_000, 0).single().unwrap();
-        let key = MetricKey {
-            gateway: "g1".to_string(),
-            method: "UPI".to_string(),
-            bank: "HDFC".to_string(),
-        };
-
-        s.ingest(&PaymentEvent {
-            payment_id: Uuid::new_v4(),
-            gateway_used: "g1".to_string(),
-            payment_method: "UPI".to_string(),
-            issuing_bank: "HDFC".to_string(),
-            amount_bucket: "lt_500".to_string(),
-            status: PaymentStatus::Success,
-            latency_ms: 100,
-            error_code: None,
-            timestamp: ts,
-        });
-        s.ingest(&PaymentEvent {
-            payment_id: Uuid::new_v4(),
-            gateway_used: "g1".to_string(),
-            payment_method: "UPI".to_string(),
-            issuing_bank: "HDFC".to_string(),
-            amount_bucket: "lt_500".to_string(),
-            status: PaymentStatus::Failure,
-            latency_ms: 200,
-            error_code: Some("DECLINED".to_string()),
-            timestamp: ts,
-        });
-
-        let m = s.compute(&key, 5, ts).unwrap();
-        assert_eq!(m.total_requests, 2);
-        assert_eq!(m.failed_requests, 1);
-        assert!(m.success_rate > 0.49 && m.success_rate < 0.51);
-    }
-}
-
 impl SlidingMetrics {
     pub fn ingest(&mut self, event: &PaymentEvent) {
         let key = MetricKey {
@@ -86,6 +62,14 @@ impl SlidingMetrics {
             method: event.payment_method.clone(),
             bank: event.issuing_bank.clone(),
         };
+        let rollups = [key.gateway_method(), key.gateway_only()];
+        self.record(key, event);
+        for rollup in rollups {
+            self.record(rollup, event);
+        }
+    }
+
+    fn record(&mut self, key: MetricKey, event: &PaymentEvent) {
         let minute = minute_epoch(event.timestamp);
         let bucket_map = self.buckets.entry(key).or_default();
         let bucket = bucket_map
@@ -173,3 +157,82 @@ impl SlidingMetrics {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::payment::PaymentStatus;
+    use crate::metrics::event::PaymentEvent;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    #[test]
+    fn computes_window_metric() {
+        let mut s = SlidingMetrics::default();
+        let ts = chrono::Utc.timestamp_opt(1_700_000_000, 0).single().unwrap();
+        let key = MetricKey {
+            gateway: "g1".to_string(),
+            method: "UPI".to_string(),
+            bank: "HDFC".to_string(),
+        };
+
+        s.ingest(&PaymentEvent {
+            payment_id: Uuid::new_v4(),
+            gateway_used: "g1".to_string(),
+            payment_method: "UPI".to_string(),
+            issuing_bank: "HDFC".to_string(),
+            amount_bucket: "lt_500".to_string(),
+            status: PaymentStatus::Success,
+            latency_ms: 100,
+            error_code: None,
+            timestamp: ts,
+        });
+        s.ingest(&PaymentEvent {
+            payment_id: Uuid::new_v4(),
+            gateway_used: "g1".to_string(),
+            payment_method: "UPI".to_string(),
+            issuing_bank: "HDFC".to_string(),
+            amount_bucket: "lt_500".to_string(),
+            status: PaymentStatus::Failure,
+            latency_ms: 200,
+            error_code: Some("DECLINED".to_string()),
+            timestamp: ts,
+        });
+
+        let m = s.compute(&key, 5, ts).unwrap();
+        assert_eq!(m.total_requests, 2);
+        assert_eq!(m.failed_requests, 1);
+        assert!(m.success_rate > 0.49 && m.success_rate < 0.51);
+    }
+
+    #[test]
+    fn ingest_feeds_method_and_gateway_rollups() {
+        let mut s = SlidingMetrics::default();
+        let ts = chrono::Utc.timestamp_opt(1_700_000_000, 0).single().unwrap();
+        for (method, bank) in [("UPI", "HDFC"), ("UPI", "AXIS"), ("CARD", "HDFC")] {
+            s.ingest(&PaymentEvent {
+                payment_id: Uuid::new_v4(),
+                gateway_used: "g1".to_string(),
+                payment_method: method.to_string(),
+                issuing_bank: bank.to_string(),
+                amount_bucket: "lt_500".to_string(),
+                status: PaymentStatus::Success,
+                latency_ms: 100,
+                error_code: None,
+                timestamp: ts,
+            });
+        }
+
+        let leaf = MetricKey {
+            gateway: "g1".to_string(),
+            method: "UPI".to_string(),
+            bank: "HDFC".to_string(),
+        };
+        assert_eq!(s.compute(&leaf, 5, ts).unwrap().total_requests, 1);
+        assert_eq!(s.compute(&leaf.gateway_method(), 5, ts).unwrap().total_requests, 2);
+        assert_eq!(s.compute(&leaf.gateway_only(), 5, ts).unwrap().total_requests, 3);
+        assert!(!leaf.is_rollup());
+        assert!(leaf.gateway_method().is_rollup());
+        assert!(leaf.gateway_only().is_rollup());
+    }
+}
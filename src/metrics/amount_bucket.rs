@@ -1,24 +1,168 @@
-pub fn from_amount_minor(amount_minor: i64) -> String {
-    if amount_minor < 50_000 {
-        "lt_500".to_string()
-    } else if amount_minor < 200_000 {
-        "500_2000".to_string()
-    } else if amount_minor < 1_000_000 {
-        "2000_10000".to_string()
-    } else {
-        "gt_10000".to_string()
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+pub const DEFAULT_CURRENCY: &str = "INR";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmountBucket {
+    pub bucket_id: String,
+    pub min_amount_minor: i64,
+    pub max_amount_minor: Option<i64>,
+}
+
+impl AmountBucket {
+    pub fn contains(&self, amount_minor: i64) -> bool {
+        amount_minor >= self.min_amount_minor && self.max_amount_minor.is_none_or(|max| amount_minor < max)
+    }
+}
+
+pub fn default_buckets() -> Vec<AmountBucket> {
+    let bucket = |id: &str, min: i64, max: Option<i64>| AmountBucket {
+        bucket_id: id.to_string(),
+        min_amount_minor: min,
+        max_amount_minor: max,
+    };
+    vec![
+        bucket("lt_500", 0, Some(50_000)),
+        bucket("500_2000", 50_000, Some(200_000)),
+        bucket("2000_10000", 200_000, Some(1_000_000)),
+        bucket("gt_10000", 1_000_000, None),
+    ]
+}
+
+pub fn validate_buckets(buckets: &[AmountBucket]) -> Result<(), String> {
+    let Some(first) = buckets.first() else {
+        return Err("at least one bucket is required".to_string());
+    };
+    if first.min_amount_minor != 0 {
+        return Err("the first bucket must start at 0".to_string());
+    }
+    let mut ids = HashSet::new();
+    for (i, bucket) in buckets.iter().enumerate() {
+        if bucket.bucket_id.trim().is_empty() || bucket.bucket_id.contains(':') {
+            return Err("bucket_id must be non-empty and must not contain ':'".to_string());
+        }
+        if !ids.insert(bucket.bucket_id.as_str()) {
+            return Err(format!("bucket_id {} is listed twice", bucket.bucket_id));
+        }
+        if bucket.max_amount_minor.is_some_and(|max| max <= bucket.min_amount_minor) {
+            return Err(format!("bucket {} must have max_amount_minor > min_amount_minor", bucket.bucket_id));
+        }
+        match (bucket.max_amount_minor, buckets.get(i + 1)) {
+            (Some(max), Some(next)) if next.min_amount_minor != max => {
+                return Err(format!(
+                    "bucket {} must start at {} so buckets are contiguous",
+                    next.bucket_id, max
+                ));
+            }
+            (None, Some(_)) => return Err("only the last bucket may be open-ended".to_string()),
+            (Some(_), None) => return Err("the last bucket must be open-ended".to_string()),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct AmountBucketer {
+    by_currency: HashMap<String, Vec<AmountBucket>>,
+}
+
+impl Default for AmountBucketer {
+    fn default() -> Self {
+        Self::new(HashMap::new())
+    }
+}
+
+impl AmountBucketer {
+    pub fn new(by_currency: HashMap<String, Vec<AmountBucket>>) -> Self {
+        let mut by_currency: HashMap<String, Vec<AmountBucket>> = by_currency
+            .into_iter()
+            .filter(|(_, buckets)| !buckets.is_empty())
+            .map(|(currency, mut buckets)| {
+                buckets.sort_by_key(|b| b.min_amount_minor);
+                (currency.to_uppercase(), buckets)
+            })
+            .collect();
+        by_currency
+            .entry(DEFAULT_CURRENCY.to_string())
+            .or_insert_with(default_buckets);
+        Self { by_currency }
+    }
+
+    pub fn buckets(&self, currency: &str) -> &[AmountBucket] {
+        self.by_currency
+            .get(&currency.to_uppercase())
+            .or_else(|| self.by_currency.get(DEFAULT_CURRENCY))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn currencies(&self) -> Vec<&str> {
+        let mut out: Vec<&str> = self.by_currency.keys().map(String::as_str).collect();
+        out.sort();
+        out
+    }
+
+    pub fn bucket(&self, currency: &str, amount_minor: i64) -> String {
+        let buckets = self.buckets(currency);
+        buckets
+            .iter()
+            .find(|b| b.contains(amount_minor))
+            .or_else(|| if amount_minor < 0 { buckets.first() } else { buckets.last() })
+            .map(|b| b.bucket_id.clone())
+            .unwrap_or_default()
     }
 }
 
+pub fn plan_remap(
+    current_ids: &[String],
+    next: &[AmountBucket],
+    remap: &HashMap<String, String>,
+) -> Result<Vec<(String, String)>, String> {
+    let next_ids: HashSet<&str> = next.iter().map(|b| b.bucket_id.as_str()).collect();
+    let mut renames = Vec::new();
+    for (old, new) in remap {
+        if !current_ids.contains(old) {
+            return Err(format!("remap source {} is not a current bucket", old));
+        }
+        if next_ids.contains(old.as_str()) {
+            return Err(format!("remap source {} is still defined; only removed buckets can be remapped", old));
+        }
+        if !next_ids.contains(new.as_str()) {
+            return Err(format!("remap target {} is not one of the new buckets", new));
+        }
+        renames.push((old.clone(), new.clone()));
+    }
+    renames.sort();
+    Ok(renames)
+}
+
+pub fn validate_unique_ids(next: &[AmountBucket], other_currencies: &HashMap<String, String>) -> Result<(), String> {
+    match next.iter().find_map(|b| other_currencies.get(&b.bucket_id).map(|c| (&b.bucket_id, c))) {
+        Some((bucket_id, currency)) => Err(format!("bucket {} is already defined for {}", bucket_id, currency)),
+        None => Ok(()),
+    }
+}
+
+pub fn removed_ids(current_ids: &[String], next: &[AmountBucket], remap: &HashMap<String, String>) -> Vec<String> {
+    current_ids
+        .iter()
+        .filter(|id| !next.iter().any(|b| &b.bucket_id == *id) && !remap.contains_key(*id))
+        .cloned()
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::from_amount_minor;
+    use super::AmountBucketer;
 
     #[test]
     fn bucket_ranges() {
-        assert_eq!(from_amount_minor(10_000), "lt_500");
-        assert_eq!(from_amount_minor(50_000), "500_2000");
-        assert_eq!(from_amount_minor(250_000), "2000_10000");
-        assert_eq!(from_amount_minor(1_500_000), "gt_10000");
+        let bucketer = AmountBucketer::default();
+        assert_eq!(bucketer.bucket("INR", 10_000), "lt_500");
+        assert_eq!(bucketer.bucket("INR", 50_000), "500_2000");
+        assert_eq!(bucketer.bucket("INR", 250_000), "2000_10000");
+        assert_eq!(bucketer.bucket("INR", 1_500_000), "gt_10000");
     }
 }
@@ -1,20 +1,25 @@
 use crate::metrics::aggregator::AggregatedMetric;
-use crate::metrics::aggregator::MetricKey;
+use crate::metrics::aggregator::{MetricKey, ROLLUP_ALL};
+use crate::redis_conn::SharedRedis;
 use anyhow::Result;
 use redis::AsyncCommands;
 
 #[derive(Clone)]
 pub struct MetricsHotStore {
-    pub client: redis::Client,
+    pub redis: SharedRedis,
 }
 
 impl MetricsHotStore {
     pub fn new(redis_url: &str) -> Result<Self> {
         Ok(Self {
-            client: redis::Client::open(redis_url)?,
+            redis: SharedRedis::open(redis_url)?,
         })
     }
 
+    pub fn with_redis(redis: SharedRedis) -> Self {
+        Self { redis }
+    }
+
     pub fn metric_key(key: &MetricKey, window: i64) -> String {
         format!(
             "metrics:{}:{}:{}:{}m",
@@ -30,16 +35,22 @@ impl MetricsHotStore {
     }
 
     pub async fn write_metric(&self, key: &MetricKey, window: i64, metric: &AggregatedMetric) -> Result<()> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.conn().await?;
         let redis_key = Self::metric_key(key, window);
         let index_key = Self::index_key(&key.gateway, window);
         let member = format!("{}:{}", key.method.to_lowercase(), key.bank.to_lowercase());
         let payload = serde_json::to_string(metric)?;
         let ttl = (window * 60 + 120) as u64;
 
-        let _: () = conn.set_ex(&redis_key, payload, ttl).await?;
-        let _: usize = conn.sadd(index_key.clone(), member).await?;
-        let _: bool = conn.expire(index_key, ttl as i64).await?;
+        let mut pipe = redis::pipe();
+        pipe.set_ex(&redis_key, payload, ttl).ignore();
+        if !key.is_rollup() {
+            pipe.sadd(&index_key, member)
+                .ignore()
+                .expire(&index_key, ttl as i64)
+                .ignore();
+        }
+        let _: () = pipe.query_async(&mut conn).await?;
         Ok(())
     }
 
@@ -50,11 +61,11 @@ impl MetricsHotStore {
         filter_method: Option<&str>,
         filter_bank: Option<&str>,
     ) -> Result<Vec<(String, String, AggregatedMetric)>> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.conn().await?;
         let index_key = Self::index_key(gateway, window);
         let members: Vec<String> = conn.smembers(index_key).await.unwrap_or_default();
 
-        let mut out = Vec::new();
+        let mut wanted = Vec::new();
         for member in members {
             let parts: Vec<&str> = member.split(':').collect();
             if parts.len() != 2 {
@@ -62,22 +73,29 @@ impl MetricsHotStore {
             }
             let method = parts[0].to_string();
             let bank = parts[1].to_string();
+            if method.eq_ignore_ascii_case(ROLLUP_ALL) || bank.eq_ignore_ascii_case(ROLLUP_ALL) {
+                continue;
+            }
             if filter_method.is_some_and(|m| m.to_lowercase() != method) {
                 continue;
             }
             if filter_bank.is_some_and(|b| b.to_lowercase() != bank) {
                 continue;
             }
-            let redis_key = format!("metrics:{}:{}:{}:{}m", gateway.to_lowercase(), method, bank, window);
-            let payload: Option<String> = conn.get(redis_key).await.ok();
-            if let Some(p) = payload {
-                if let Ok(metric) = serde_json::from_str::<AggregatedMetric>(&p) {
-                    out.push((method, bank, metric));
-                }
-            }
+            wanted.push((method, bank));
         }
 
-        Ok(out)
+        let keys: Vec<String> = wanted
+            .iter()
+            .map(|(method, bank)| format!("metrics:{}:{}:{}:{}m", gateway.to_lowercase(), method, bank, window))
+            .collect();
+        let metrics = self.read_metrics(&keys).await?;
+
+        Ok(wanted
+            .into_iter()
+            .zip(metrics)
+            .filter_map(|((method, bank), metric)| metric.map(|m| (method, bank, m)))
+            .collect())
     }
 
     pub async fn read_single_metric(
@@ -87,15 +105,28 @@ impl MetricsHotStore {
         bank: &str,
         window: i64,
     ) -> Result<Option<AggregatedMetric>> {
-        let mut conn = self.client.get_multiplexed_async_connection().await?;
-        let redis_key = format!(
-            "metrics:{}:{}:{}:{}m",
-            gateway.to_lowercase(),
-            method.to_lowercase(),
-            bank.to_lowercase(),
-            window
-        );
-        let payload: Option<String> = conn.get(redis_key).await.ok();
-        Ok(payload.and_then(|p| serde_json::from_str::<AggregatedMetric>(&p).ok()))
+        let key = MetricKey {
+            gateway: gateway.to_string(),
+            method: method.to_string(),
+            bank: bank.to_string(),
+        };
+        let mut metrics = self.read_metrics(&[Self::metric_key(&key, window)]).await?;
+        Ok(metrics.pop().flatten())
     }
+
+    pub async fn read_metrics(&self, keys: &[String]) -> Result<Vec<Option<AggregatedMetric>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.redis.conn().await?;
+        let payloads: Vec<Option<String>> = redis::cmd("MGET").arg(keys).query_async(&mut conn).await?;
+        Ok(parse_metrics(&payloads))
+    }
+}
+
+pub fn parse_metrics(payloads: &[Option<String>]) -> Vec<Option<AggregatedMetric>> {
+    payloads
+        .iter()
+        .map(|p| p.as_deref().and_then(|p| serde_json::from_str::<AggregatedMetric>(p).ok()))
+        .collect()
 }
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use redis::aio::ConnectionManager;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+#[derive(Clone)]
+pub struct SharedRedis {
+    pub client: redis::Client,
+    manager: Arc<OnceCell<ConnectionManager>>,
+}
+
+impl SharedRedis {
+    pub fn new(client: redis::Client) -> Self {
+        Self {
+            client,
+            manager: Arc::new(OnceCell::new()),
+        }
+    }
+
+    pub fn open(redis_url: &str) -> Result<Self> {
+        Ok(Self::new(redis::Client::open(redis_url)?))
+    }
+
+    pub async fn conn(&self) -> Result<ConnectionManager> {
+        let manager = self
+            .manager
+            .get_or_try_init(|| ConnectionManager::new(self.client.clone()))
+            .await?;
+        Ok(manager.clone())
+    }
+}
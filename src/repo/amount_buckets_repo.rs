@@ -0,0 +1,276 @@
+use crate::metrics::amount_bucket::{plan_remap, removed_ids, validate_unique_ids, AmountBucket};
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct AmountBucketsRepo {
+    pub pool: PgPool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BucketReference {
+    pub bucket_id: String,
+    pub source: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone)]
+pub enum ReplaceOutcome {
+    Applied { renamed: Vec<(String, String)> },
+    Invalid(String),
+    Orphaned(Vec<BucketReference>),
+}
+
+impl AmountBucketsRepo {
+    pub async fn list_all(&self) -> Result<HashMap<String, Vec<AmountBucket>>> {
+        let rows = sqlx::query(
+            "SELECT currency, bucket_id, min_amount_minor, max_amount_minor FROM amount_buckets ORDER BY currency, min_amount_minor",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut out: HashMap<String, Vec<AmountBucket>> = HashMap::new();
+        for r in rows {
+            out.entry(r.get("currency")).or_default().push(AmountBucket {
+                bucket_id: r.get("bucket_id"),
+                min_amount_minor: r.get("min_amount_minor"),
+                max_amount_minor: r.get("max_amount_minor"),
+            });
+        }
+        Ok(out)
+    }
+
+    pub async fn replace(
+        &self,
+        currency: &str,
+        buckets: &[AmountBucket],
+        remap: &HashMap<String, String>,
+    ) -> Result<ReplaceOutcome> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("LOCK TABLE amount_buckets IN SHARE ROW EXCLUSIVE MODE")
+            .execute(tx.as_mut())
+            .await?;
+
+        let rows = sqlx::query("SELECT currency, bucket_id FROM amount_buckets")
+            .fetch_all(tx.as_mut())
+            .await?;
+        let mut current_ids = Vec::new();
+        let mut other_ids = Vec::new();
+        let mut other_currencies = HashMap::new();
+        for r in rows {
+            let bucket_id: String = r.get("bucket_id");
+            let owner: String = r.get("currency");
+            if owner == currency {
+                current_ids.push(bucket_id);
+            } else {
+                other_ids.push(bucket_id.clone());
+                other_currencies.insert(bucket_id, owner);
+            }
+        }
+
+        if let Err(err) = validate_unique_ids(buckets, &other_currencies) {
+            return Ok(ReplaceOutcome::Invalid(err));
+        }
+        let renames = match plan_remap(&current_ids, buckets, remap) {
+            Ok(renames) => renames,
+            Err(err) => return Ok(ReplaceOutcome::Invalid(err)),
+        };
+        if let Some((old, _)) = renames.iter().find(|(old, _)| other_ids.contains(old)) {
+            return Ok(ReplaceOutcome::Invalid(format!(
+                "bucket {} is still defined for another currency and cannot be remapped",
+                old
+            )));
+        }
+
+        let removed: Vec<String> = removed_ids(&current_ids, buckets, remap)
+            .into_iter()
+            .filter(|id| !other_ids.contains(id))
+            .collect();
+        if !removed.is_empty() {
+            let references = references(&mut tx, &removed).await?;
+            if !references.is_empty() {
+                return Ok(ReplaceOutcome::Orphaned(references));
+            }
+        }
+
+        for (old, new) in &renames {
+            rename_bucket(&mut tx, old, new).await?;
+        }
+
+        sqlx::query("DELETE FROM amount_buckets WHERE currency=$1")
+            .bind(currency)
+            .execute(tx.as_mut())
+            .await?;
+        for bucket in buckets {
+            sqlx::query(
+                "INSERT INTO amount_buckets (currency, bucket_id, min_amount_minor, max_amount_minor) VALUES ($1,$2,$3,$4)",
+            )
+            .bind(currency)
+            .bind(&bucket.bucket_id)
+            .bind(bucket.min_amount_minor)
+            .bind(bucket.max_amount_minor)
+            .execute(tx.as_mut())
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(ReplaceOutcome::Applied { renamed: renames })
+    }
+}
+
+fn bucket_position(alias: &str) -> String {
+    format!(
+        "(SELECT array_position(d.dimensions, 'AMOUNT_BUCKET') FROM bandit_segment_definitions d WHERE d.definition_id = {}.definition_id)",
+        alias
+    )
+}
+
+fn segment_bucket(alias: &str) -> String {
+    format!("split_part({}.segment, ':', {})", alias, bucket_position(alias))
+}
+
+fn segment_with_bucket(alias: &str, bucket_param: &str) -> String {
+    let position = bucket_position(alias);
+    format!(
+        "array_to_string((string_to_array({a}.segment, ':'))[:{p} - 1] || ARRAY[{b}::TEXT] || (string_to_array({a}.segment, ':'))[{p} + 1:], ':')",
+        a = alias,
+        p = position,
+        b = bucket_param
+    )
+}
+
+async fn references(tx: &mut Transaction<'_, Postgres>, bucket_ids: &[String]) -> Result<Vec<BucketReference>> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT 'gateway_amount_fit' AS source, amount_bucket AS bucket_id, COUNT(*) AS row_count
+        FROM gateway_amount_fit WHERE amount_bucket = ANY($1) GROUP BY amount_bucket
+        UNION ALL
+        SELECT 'routing_rules', amount_bucket, COUNT(*)
+        FROM routing_rules WHERE amount_bucket = ANY($1) GROUP BY amount_bucket
+        UNION ALL
+        SELECT 'experiment_filters', amount_bucket, COUNT(*)
+        FROM experiment_filters WHERE amount_bucket = ANY($1) GROUP BY amount_bucket
+        UNION ALL
+        SELECT 'scoring_profile_assignments', amount_bucket, COUNT(*)
+        FROM scoring_profile_assignments WHERE amount_bucket = ANY($1) GROUP BY amount_bucket
+        UNION ALL
+        SELECT 'bandit_policy', {policy_bucket}, COUNT(*)
+        FROM bandit_policy p WHERE {policy_bucket} = ANY($1) GROUP BY 2
+        UNION ALL
+        SELECT 'bandit_state', {state_bucket}, COUNT(*)
+        FROM bandit_state s WHERE {state_bucket} = ANY($1) GROUP BY 2
+        UNION ALL
+        SELECT 'bandit_linear_models', {model_bucket}, COUNT(*)
+        FROM bandit_linear_models m WHERE {model_bucket} = ANY($1) GROUP BY 2
+        "#,
+        policy_bucket = segment_bucket("p"),
+        state_bucket = segment_bucket("s"),
+        model_bucket = segment_bucket("m"),
+    ))
+    .bind(bucket_ids)
+    .fetch_all(tx.as_mut())
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| BucketReference {
+            bucket_id: r.get("bucket_id"),
+            source: r.get("source"),
+            rows: r.get("row_count"),
+        })
+        .collect())
+}
+
+async fn rename_bucket(tx: &mut Transaction<'_, Postgres>, old: &str, new: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO gateway_amount_fit (gateway_id, amount_bucket, score)
+        SELECT gateway_id, $2, score FROM gateway_amount_fit WHERE amount_bucket=$1
+        ON CONFLICT (gateway_id, amount_bucket) DO NOTHING
+        "#,
+    )
+    .bind(old)
+    .bind(new)
+    .execute(tx.as_mut())
+    .await?;
+    sqlx::query("DELETE FROM gateway_amount_fit WHERE amount_bucket=$1")
+        .bind(old)
+        .execute(tx.as_mut())
+        .await?;
+
+    for table in ["routing_rules", "experiment_filters"] {
+        sqlx::query(&format!("UPDATE {} SET amount_bucket=$2 WHERE amount_bucket=$1", table))
+            .bind(old)
+            .bind(new)
+            .execute(tx.as_mut())
+            .await?;
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE scoring_profile_assignments a SET amount_bucket=$2
+        WHERE a.amount_bucket=$1
+          AND NOT EXISTS (
+              SELECT 1 FROM scoring_profile_assignments b
+              WHERE b.amount_bucket=$2
+                AND COALESCE(b.merchant_id, '')=COALESCE(a.merchant_id, '')
+                AND COALESCE(b.payment_method, '')=COALESCE(a.payment_method, '')
+          )
+        "#,
+    )
+    .bind(old)
+    .bind(new)
+    .execute(tx.as_mut())
+    .await?;
+    sqlx::query("DELETE FROM scoring_profile_assignments WHERE amount_bucket=$1")
+        .bind(old)
+        .execute(tx.as_mut())
+        .await?;
+
+    sqlx::query(&format!(
+        r#"
+        INSERT INTO bandit_policy (definition_id, segment, enabled, updated_at)
+        SELECT p.definition_id, {renamed}, p.enabled, now()
+        FROM bandit_policy p WHERE {bucket}=$1
+        ON CONFLICT (definition_id, segment) DO UPDATE SET
+            enabled = bandit_policy.enabled OR EXCLUDED.enabled,
+            updated_at = now()
+        "#,
+        renamed = segment_with_bucket("p", "$2"),
+        bucket = segment_bucket("p"),
+    ))
+    .bind(old)
+    .bind(new)
+    .execute(tx.as_mut())
+    .await?;
+    sqlx::query(&format!(
+        r#"
+        INSERT INTO bandit_state (definition_id, segment, gateway_id, alpha, beta, updated_at)
+        SELECT definition_id, renamed, gateway_id, SUM(alpha) - COUNT(*) + 1, SUM(beta) - COUNT(*) + 1, now()
+        FROM (
+            SELECT s.definition_id, {renamed} AS renamed, s.gateway_id, s.alpha, s.beta
+            FROM bandit_state s WHERE {bucket}=$1
+        ) moved
+        GROUP BY definition_id, renamed, gateway_id
+        ON CONFLICT (definition_id, segment, gateway_id) DO UPDATE SET
+            alpha = bandit_state.alpha + EXCLUDED.alpha - 1,
+            beta = bandit_state.beta + EXCLUDED.beta - 1,
+            updated_at = now()
+        "#,
+        renamed = segment_with_bucket("s", "$2"),
+        bucket = segment_bucket("s"),
+    ))
+    .bind(old)
+    .bind(new)
+    .execute(tx.as_mut())
+    .await?;
+    for table in ["bandit_state", "bandit_policy", "bandit_linear_models"] {
+        sqlx::query(&format!("DELETE FROM {} t WHERE {}=$1", table, segment_bucket("t")))
+            .bind(old)
+            .execute(tx.as_mut())
+            .await?;
+    }
+    Ok(())
+}
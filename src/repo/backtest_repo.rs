@@ -0,0 +1,271 @@
+use crate::bandit::benchmark::LoggedDecision;
+use crate::bandit::evaluation::{EvaluationEvent, EventCandidate};
+use crate::bandit::linucb::features as bandit_features;
+use crate::bandit::segments::{BanditSegment, SegmentContext, SegmentDefinition, DEFAULT_DEFINITION_ID};
+use crate::domain::routing_decision::BanditSelection;
+use crate::metrics::aggregator::ROLLUP_ALL;
+use crate::metrics::amount_bucket::AmountBucketer;
+use crate::scoring::backtest::{AttemptOutcome, BacktestCase, MetricHistory, MetricSnapshot};
+use crate::scoring::blend::SUPPORTED_WINDOWS;
+use crate::scoring::types::RankedGateway;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct BacktestRepo {
+    pub pool: PgPool,
+}
+
+impl BacktestRepo {
+    pub async fn load_cases(&self, from: DateTime<Utc>, to: DateTime<Utc>, limit: i64, buckets: &AmountBucketer) -> Result<Vec<BacktestCase>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT payment_id, created_at, merchant_id, payment_method, issuing_bank, amount_minor, currency, card_network, card_type
+            FROM payments
+            WHERE created_at >= $1 AND created_at < $2
+            ORDER BY created_at ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut cases: Vec<BacktestCase> = rows
+            .into_iter()
+            .map(|row| BacktestCase {
+                payment_id: row.get("payment_id"),
+                created_at: row.get("created_at"),
+                merchant_id: row.get("merchant_id"),
+                method: row.get::<String, _>("payment_method").to_uppercase(),
+                issuing_bank: row
+                    .get::<Option<String>, _>("issuing_bank")
+                    .unwrap_or_else(|| "UNKNOWN".to_string())
+                    .to_uppercase(),
+                amount_minor: row.get("amount_minor"),
+                amount_bucket: buckets.bucket(&row.get::<String, _>("currency"), row.get("amount_minor")),
+                card_network: row.get("card_network"),
+                card_type: row.get("card_type"),
+                attempts: Vec::new(),
+            })
+            .collect();
+
+        let ids: Vec<Uuid> = cases.iter().map(|c| c.payment_id).collect();
+        let attempt_rows = sqlx::query(
+            r#"
+            SELECT payment_id, gateway_used, status, latency_ms
+            FROM payment_attempts
+            WHERE payment_id = ANY($1) AND status <> 'SKIPPED'
+            ORDER BY payment_id, attempt_number ASC
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut attempts: HashMap<Uuid, Vec<AttemptOutcome>> = HashMap::new();
+        for row in attempt_rows {
+            attempts
+                .entry(row.get("payment_id"))
+                .or_default()
+                .push(AttemptOutcome {
+                    gateway_id: row.get("gateway_used"),
+                    success: row.get::<String, _>("status") == "SUCCESS",
+                    latency_ms: row.get("latency_ms"),
+                });
+        }
+        for case in &mut cases {
+            case.attempts = attempts.remove(&case.payment_id).unwrap_or_default();
+        }
+
+        Ok(cases)
+    }
+
+    pub async fn load_logged_decisions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+        buckets: &AmountBucketer,
+    ) -> Result<Vec<LoggedDecision>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.merchant_id, p.payment_method, p.issuing_bank, p.card_network, p.amount_minor, p.currency,
+                   rd.created_at, rd.ranked_gateways_json, rd.bandit_selection_json,
+                   first_attempt.gateway_used, first_attempt.status
+            FROM routing_decisions rd
+            JOIN payments p ON p.payment_id = rd.payment_id
+            JOIN LATERAL (
+                SELECT gateway_used, status FROM payment_attempts a
+                WHERE a.payment_id = rd.payment_id AND a.status <> 'SKIPPED'
+                ORDER BY a.attempt_number ASC
+                LIMIT 1
+            ) first_attempt ON true
+            WHERE rd.created_at >= $1 AND rd.created_at < $2
+            ORDER BY rd.created_at ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let default_definition = SegmentDefinition::default_definition();
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let ranked: Vec<RankedGateway> = serde_json::from_value(row.get("ranked_gateways_json")).ok()?;
+                let selection: Option<BanditSelection> = row
+                    .get::<Option<serde_json::Value>, _>("bandit_selection_json")
+                    .and_then(|v| serde_json::from_value(v).ok());
+                let segment = match selection {
+                    Some(selection) => selection.policy_segment(),
+                    None => {
+                        let payment_method: String = row.get("payment_method");
+                        let amount_bucket = buckets.bucket(&row.get::<String, _>("currency"), row.get("amount_minor"));
+                        let issuer = row
+                            .get::<Option<String>, _>("issuing_bank")
+                            .unwrap_or_else(|| "UNKNOWN".to_string());
+                        let card_network: Option<String> = row.get("card_network");
+                        let merchant_id: String = row.get("merchant_id");
+                        default_definition.segment(&SegmentContext {
+                            payment_method: &payment_method,
+                            amount_bucket: &amount_bucket,
+                            issuer: &issuer,
+                            card_network: card_network.as_deref(),
+                            merchant_id: &merchant_id,
+                            at: row.get("created_at"),
+                        })
+                    }
+                };
+                Some(LoggedDecision {
+                    segment: segment.to_string(),
+                    candidates: ranked
+                        .iter()
+                        .map(|r| (r.gateway_id.clone(), bandit_features(&r.breakdown)))
+                        .collect(),
+                    chosen_gateway: row.get("gateway_used"),
+                    success: row.get::<String, _>("status") == "SUCCESS",
+                })
+            })
+            .collect())
+    }
+
+    pub async fn load_bandit_events(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        limit: i64,
+        segment: Option<&BanditSegment>,
+    ) -> Result<(Vec<EvaluationEvent>, usize)> {
+        let rows = sqlx::query(
+            r#"
+            SELECT rd.ranked_gateways_json, rd.bandit_selection_json,
+                   first_attempt.gateway_used, first_attempt.status
+            FROM routing_decisions rd
+            JOIN LATERAL (
+                SELECT gateway_used, status FROM payment_attempts a
+                WHERE a.payment_id = rd.payment_id AND a.status <> 'SKIPPED'
+                ORDER BY a.attempt_number ASC
+                LIMIT 1
+            ) first_attempt ON true
+            WHERE rd.bandit_selection_json IS NOT NULL
+              AND rd.created_at >= $1 AND rd.created_at < $2
+              AND ($4::TEXT IS NULL OR (
+                  COALESCE(rd.bandit_selection_json->>'definition_id', $6) = $4
+                  AND rd.bandit_selection_json->>'segment' = $5
+              ))
+            ORDER BY rd.created_at ASC
+            LIMIT $3
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(limit)
+        .bind(segment.map(|s| s.definition_id.clone()))
+        .bind(segment.map(|s| s.key.clone()))
+        .bind(DEFAULT_DEFINITION_ID)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut skipped = 0;
+        let mut events = Vec::new();
+        for row in &rows {
+            let selection: Option<BanditSelection> = serde_json::from_value(row.get("bandit_selection_json")).ok();
+            let ranked: Option<Vec<RankedGateway>> = serde_json::from_value(row.get("ranked_gateways_json")).ok();
+            let gateway_used: String = row.get("gateway_used");
+            let (Some(selection), Some(ranked)) = (selection, ranked) else {
+                skipped += 1;
+                continue;
+            };
+            if selection.chosen_gateway != gateway_used {
+                skipped += 1;
+                continue;
+            }
+            events.push(EvaluationEvent {
+                propensity: selection.propensity(),
+                segment: selection.policy_segment(),
+                state_segment: selection.state_segment(),
+                candidates: ranked
+                    .iter()
+                    .map(|r| EventCandidate {
+                        gateway_id: r.gateway_id.clone(),
+                        engine_score: r.score,
+                        features: bandit_features(&r.breakdown),
+                    })
+                    .collect(),
+                logged_gateway: gateway_used,
+                reward: if row.get::<String, _>("status") == "SUCCESS" { 1.0 } else { 0.0 },
+            });
+        }
+        Ok((events, skipped))
+    }
+
+    pub async fn load_metric_history(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<MetricHistory> {
+        let rows = sqlx::query(
+            r#"
+            SELECT snapshot_minute, window_size_minutes, gateway_name,
+                   CASE WHEN GROUPING(payment_method) = 1 THEN $4 ELSE payment_method END AS payment_method,
+                   CASE WHEN GROUPING(issuing_bank) = 1 THEN $4 ELSE issuing_bank END AS issuing_bank,
+                   SUM(total_requests)::BIGINT AS total_requests,
+                   COALESCE(SUM(success_rate * total_requests) / NULLIF(SUM(total_requests), 0), 0) AS success_rate,
+                   COALESCE(SUM(avg_latency_ms::BIGINT * total_requests) / NULLIF(SUM(total_requests), 0), 0)::INT AS avg_latency_ms,
+                   COALESCE(SUM(p95_latency_ms::BIGINT * total_requests) / NULLIF(SUM(total_requests), 0), 0)::INT AS p95_latency_ms
+            FROM gateway_metrics
+            WHERE window_size_minutes = ANY($3) AND snapshot_minute >= $1 AND snapshot_minute <= $2
+            GROUP BY snapshot_minute, window_size_minutes, gateway_name, ROLLUP (payment_method, issuing_bank)
+            "#,
+        )
+        .bind(from - chrono::Duration::minutes(10))
+        .bind(to)
+        .bind(SUPPORTED_WINDOWS.to_vec())
+        .bind(ROLLUP_ALL)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut history = MetricHistory::default();
+        for row in rows {
+            history.insert(
+                &row.get::<String, _>("gateway_name"),
+                &row.get::<String, _>("payment_method"),
+                &row.get::<String, _>("issuing_bank"),
+                MetricSnapshot {
+                    snapshot_minute: row.get("snapshot_minute"),
+                    window_minutes: row.get::<i32, _>("window_size_minutes") as i64,
+                    total_requests: row.get::<i64, _>("total_requests").max(0) as u64,
+                    success_rate: row.get("success_rate"),
+                    avg_latency_ms: row.get("avg_latency_ms"),
+                    p95_latency_ms: row.get("p95_latency_ms"),
+                },
+            );
+        }
+        Ok(history)
+    }
+}
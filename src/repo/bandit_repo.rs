@@ -1,6 +1,15 @@
+use crate::bandit::discount::{decayed, summarize, PosteriorSummary, PRIOR_ALPHA, PRIOR_BETA};
+use crate::bandit::linucb::{LinearModel, FEATURE_DIM};
+use crate::bandit::policy::{BanditAlgorithm, BanditPolicyConfig};
+use crate::bandit::reward::RewardConfig;
+use crate::bandit::safety::{warm_start_prior, SafetyConfig, DEFAULT_WARM_START_STRENGTH, WARM_START_LOOKBACK_DAYS};
+use crate::bandit::segments::{BanditSegment, SegmentDefinition, SegmentDimension};
 use crate::bandit::thompson;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct BanditRepo {
@@ -9,92 +18,506 @@ pub struct BanditRepo {
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct BanditStateRow {
+    pub definition_id: String,
     pub segment: String,
     pub gateway_id: String,
     pub alpha: f64,
     pub beta: f64,
+    pub raw_alpha: f64,
+    pub raw_beta: f64,
+    pub half_life_minutes: Option<f64>,
+    pub updated_at: DateTime<Utc>,
+    pub posterior: PosteriorSummary,
 }
 
+const MAX_HALF_LIVES: f64 = 1000.0;
+const HALF_LIFE_EXPR: &str = "(SELECT MAX(p.discount_half_life_minutes) FROM bandit_policy p WHERE p.definition_id = s.definition_id AND p.segment = s.segment)";
+
 impl BanditRepo {
-    pub async fn is_enabled(&self, segment: &str) -> Result<bool> {
-        let row = sqlx::query("SELECT enabled FROM bandit_policy WHERE segment=$1")
-            .bind(segment)
+    pub async fn is_enabled(&self, segment: &BanditSegment) -> Result<bool> {
+        let row = sqlx::query("SELECT enabled FROM bandit_policy WHERE definition_id=$1 AND segment=$2")
+            .bind(&segment.definition_id)
+            .bind(&segment.key)
             .fetch_optional(&self.pool)
             .await?;
         Ok(row.map(|r| r.get::<bool, _>("enabled")).unwrap_or(false))
     }
 
-    pub async fn set_enabled(&self, segment: &str, enabled: bool) -> Result<()> {
+    pub async fn enabled_segments(&self) -> Result<Vec<BanditSegment>> {
+        let rows = sqlx::query("SELECT definition_id, segment FROM bandit_policy WHERE enabled = true")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| BanditSegment::new(&r.get::<String, _>("definition_id"), &r.get::<String, _>("segment")))
+            .collect())
+    }
+
+    pub async fn set_enabled(&self, segment: &BanditSegment, enabled: bool) -> Result<()> {
         sqlx::query(
-            "INSERT INTO bandit_policy (segment, enabled, updated_at) VALUES ($1,$2,now()) ON CONFLICT (segment) DO UPDATE SET enabled=$2, updated_at=now()",
+            "INSERT INTO bandit_policy (definition_id, segment, enabled, updated_at) VALUES ($1,$2,$3,now()) ON CONFLICT (definition_id, segment) DO UPDATE SET enabled=$3, updated_at=now()",
         )
-        .bind(segment)
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
         .bind(enabled)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    pub async fn ensure_gateway_state(&self, segment: &str, gateway_id: &str) -> Result<()> {
+    pub async fn list_policies(&self) -> Result<Vec<BanditPolicyConfig>> {
+        let rows = sqlx::query(r#"
+            SELECT definition_id, segment, algorithm, exploration, reward_config,
+                   max_exploration_share, min_success_rate, warm_start_strength
+            FROM bandit_policy
+            "#)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| BanditPolicyConfig {
+                definition_id: r.get("definition_id"),
+                segment: r.get("segment"),
+                algorithm: BanditAlgorithm::parse(&r.get::<String, _>("algorithm")).unwrap_or_default(),
+                exploration: r.get("exploration"),
+                reward: r
+                    .get::<Option<serde_json::Value>, _>("reward_config")
+                    .and_then(|v| serde_json::from_value(v).ok())
+                    .unwrap_or_default(),
+                safety: SafetyConfig {
+                    max_exploration_share: r.get("max_exploration_share"),
+                    min_success_rate: r.get("min_success_rate"),
+                    warm_start_strength: r.get("warm_start_strength"),
+                },
+            })
+            .collect())
+    }
+
+    pub async fn set_algorithm(&self, segment: &BanditSegment, algorithm: BanditAlgorithm, exploration: f64) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bandit_policy (definition_id, segment, enabled, algorithm, exploration, updated_at)
+            VALUES ($1, $2, false, $3, $4, now())
+            ON CONFLICT (definition_id, segment) DO UPDATE SET algorithm=$3, exploration=$4, updated_at=now()
+            "#,
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(algorithm.as_str())
+        .bind(exploration)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn linear_models(&self, segment: &BanditSegment, gateways: &[String]) -> Result<HashMap<String, LinearModel>> {
+        let rows = sqlx::query(
+            "SELECT gateway_id, a_inv, b, observations FROM bandit_linear_models WHERE definition_id=$1 AND segment=$2 AND gateway_id = ANY($3)",
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(gateways)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .iter()
+            .map(|r| {
+                let model = LinearModel {
+                    a_inv: r.get("a_inv"),
+                    b: r.get("b"),
+                    observations: r.get("observations"),
+                };
+                (r.get("gateway_id"), model)
+            })
+            .filter(|(_, model): &(String, LinearModel)| model.is_valid(FEATURE_DIM))
+            .collect())
+    }
+
+    pub async fn update_linear(&self, segment: &BanditSegment, gateway_id: &str, x: &[f64], reward: f64) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let row = sqlx::query(
+            "SELECT a_inv, b, observations FROM bandit_linear_models WHERE definition_id=$1 AND segment=$2 AND gateway_id=$3 FOR UPDATE",
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(gateway_id)
+        .fetch_optional(tx.as_mut())
+        .await?;
+        let mut model = row
+            .map(|r| LinearModel {
+                a_inv: r.get("a_inv"),
+                b: r.get("b"),
+                observations: r.get("observations"),
+            })
+            .filter(|m| m.is_valid(FEATURE_DIM))
+            .unwrap_or_else(|| LinearModel::new(FEATURE_DIM));
+        model.update(x, reward);
+
         sqlx::query(
-            "INSERT INTO bandit_state (segment, gateway_id, alpha, beta, updated_at) VALUES ($1,$2,1.0,1.0,now()) ON CONFLICT (segment, gateway_id) DO NOTHING",
+            r#"
+            INSERT INTO bandit_linear_models (definition_id, segment, gateway_id, a_inv, b, observations, updated_at)
+            VALUES ($1,$2,$3,$4,$5,$6,now())
+            ON CONFLICT (definition_id, segment, gateway_id) DO UPDATE SET
+                a_inv=EXCLUDED.a_inv, b=EXCLUDED.b, observations=EXCLUDED.observations, updated_at=now()
+            "#,
         )
-        .bind(segment)
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
         .bind(gateway_id)
+        .bind(&model.a_inv)
+        .bind(&model.b)
+        .bind(model.observations)
+        .execute(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn set_reward(&self, segment: &BanditSegment, reward: &RewardConfig) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bandit_policy (definition_id, segment, enabled, reward_config, updated_at)
+            VALUES ($1, $2, false, $3, now())
+            ON CONFLICT (definition_id, segment) DO UPDATE SET reward_config=$3, updated_at=now()
+            "#,
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(serde_json::to_value(reward)?)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_safety(&self, segment: &BanditSegment, safety: &SafetyConfig) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bandit_policy (definition_id, segment, enabled, max_exploration_share, min_success_rate, warm_start_strength, updated_at)
+            VALUES ($1, $2, false, $3, $4, $5, now())
+            ON CONFLICT (definition_id, segment) DO UPDATE SET
+                max_exploration_share=$3,
+                min_success_rate=$4,
+                warm_start_strength=$5,
+                updated_at=now()
+            "#,
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(safety.max_exploration_share)
+        .bind(safety.min_success_rate)
+        .bind(safety.warm_start_strength)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_half_life(&self, segment: &BanditSegment, half_life_minutes: Option<f64>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO bandit_policy (definition_id, segment, enabled, discount_half_life_minutes, updated_at)
+            VALUES ($1, $2, false, $3, now())
+            ON CONFLICT (definition_id, segment) DO UPDATE SET discount_half_life_minutes=$3, updated_at=now()
+            "#,
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(half_life_minutes)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    pub async fn sample_scores(&self, segment: &str, gateways: &[String]) -> Result<Vec<(String, f64)>> {
-        let mut out = Vec::new();
+    pub async fn warm_start(&self, segment: &BanditSegment, gateway_id: &str) -> Result<(f64, f64)> {
+        let row = sqlx::query(
+            r#"
+            WITH seg AS (
+                SELECT
+                    upper(split_part($2, ':', array_position(d.dimensions, 'PAYMENT_METHOD'))) AS method,
+                    upper(split_part($2, ':', array_position(d.dimensions, 'ISSUER'))) AS issuer
+                FROM bandit_segment_definitions d WHERE d.definition_id = $1
+            )
+            SELECT
+                SUM(m.success_rate * m.total_requests) / NULLIF(SUM(m.total_requests), 0) AS success_rate,
+                COALESCE(SUM(m.total_requests), 0)::DOUBLE PRECISION AS requests,
+                (SELECT p.warm_start_strength FROM bandit_policy p WHERE p.definition_id = $1 AND p.segment = $2) AS strength
+            FROM gateway_metrics m
+            LEFT JOIN seg ON true
+            WHERE m.gateway_name = $3
+              AND (seg.method IS NULL OR m.payment_method = seg.method)
+              AND (seg.issuer IS NULL OR m.issuing_bank = seg.issuer)
+              AND m.payment_method <> 'ALL' AND m.issuing_bank <> 'ALL'
+              AND m.window_size_minutes = 1
+              AND m.snapshot_minute >= now() - make_interval(days => $4)
+            "#,
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(gateway_id)
+        .bind(WARM_START_LOOKBACK_DAYS as i32)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(warm_start_prior(
+            row.get("success_rate"),
+            row.get("requests"),
+            row.get::<Option<f64>, _>("strength").unwrap_or(DEFAULT_WARM_START_STRENGTH),
+        ))
+    }
+
+    pub async fn reset_segment(&self, segment: &BanditSegment) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            "UPDATE bandit_state SET alpha=1.0, beta=1.0, updated_at=now() WHERE definition_id=$1 AND segment=$2",
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM bandit_linear_models WHERE definition_id=$1 AND segment=$2")
+            .bind(&segment.definition_id)
+            .bind(&segment.key)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn reseed_segment(&self, segment: &BanditSegment, gateways: &[String]) -> Result<Vec<(String, f64, f64)>> {
+        let existing: Vec<String> = sqlx::query("SELECT gateway_id FROM bandit_state WHERE definition_id=$1 AND segment=$2")
+            .bind(&segment.definition_id)
+            .bind(&segment.key)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(|r| r.get("gateway_id"))
+            .collect();
+        let mut arms = existing;
         for gateway_id in gateways {
-            self.ensure_gateway_state(segment, gateway_id).await?;
-            let row = sqlx::query("SELECT alpha, beta FROM bandit_state WHERE segment=$1 AND gateway_id=$2")
-                .bind(segment)
-                .bind(gateway_id)
-                .fetch_one(&self.pool)
-                .await?;
-            let alpha: f64 = row.get("alpha");
-            let beta: f64 = row.get("beta");
-            out.push((gateway_id.clone(), thompson::sample(alpha, beta)));
+            if !arms.contains(gateway_id) {
+                arms.push(gateway_id.clone());
+            }
+        }
+
+        let mut seeded = Vec::new();
+        for gateway_id in &arms {
+            let (alpha, beta) = self.warm_start(segment, gateway_id).await?;
+            seeded.push((gateway_id.clone(), alpha, beta));
         }
 
+        let mut tx = self.pool.begin().await?;
+        for (gateway_id, alpha, beta) in &seeded {
+            sqlx::query(
+                r#"
+                INSERT INTO bandit_state (definition_id, segment, gateway_id, alpha, beta, updated_at)
+                VALUES ($1,$2,$3,$4,$5,now())
+                ON CONFLICT (definition_id, segment, gateway_id) DO UPDATE SET alpha=$4, beta=$5, updated_at=now()
+                "#,
+            )
+            .bind(&segment.definition_id)
+            .bind(&segment.key)
+            .bind(gateway_id)
+            .bind(alpha)
+            .bind(beta)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query("DELETE FROM bandit_linear_models WHERE definition_id=$1 AND segment=$2")
+            .bind(&segment.definition_id)
+            .bind(&segment.key)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(seeded)
+    }
+
+    pub async fn posteriors(&self, segment: &BanditSegment, gateways: &[String]) -> Result<Vec<(String, f64, f64)>> {
+        let rows = sqlx::query(&format!(
+            "SELECT s.definition_id, s.segment, s.gateway_id, s.alpha, s.beta, s.updated_at, {} AS half_life FROM bandit_state s WHERE s.definition_id=$1 AND s.segment=$2 AND s.gateway_id = ANY($3)",
+            HALF_LIFE_EXPR
+        ))
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(gateways)
+        .fetch_all(&self.pool)
+        .await?;
+        let now = Utc::now();
+        let states: HashMap<String, BanditStateRow> = rows
+            .iter()
+            .map(|row| map_state(row, now))
+            .map(|state| (state.gateway_id.clone(), state))
+            .collect();
+
+        Ok(gateways
+            .iter()
+            .map(|gateway_id| match states.get(gateway_id) {
+                Some(state) => (gateway_id.clone(), state.alpha, state.beta),
+                None => (gateway_id.clone(), PRIOR_ALPHA, PRIOR_BETA),
+            })
+            .collect())
+    }
+
+    pub async fn sample_scores(&self, segment: &BanditSegment, gateways: &[String]) -> Result<Vec<(String, f64)>> {
+        let mut out: Vec<(String, f64)> = self
+            .posteriors(segment, gateways)
+            .await?
+            .into_iter()
+            .map(|(gateway_id, alpha, beta)| (gateway_id, thompson::sample(alpha, beta)))
+            .collect();
         out.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         Ok(out)
     }
 
-    pub async fn update_outcome(&self, segment: &str, gateway_id: &str, success: bool) -> Result<()> {
-        self.ensure_gateway_state(segment, gateway_id).await?;
-        if success {
-            sqlx::query("UPDATE bandit_state SET alpha = alpha + 1, updated_at=now() WHERE segment=$1 AND gateway_id=$2")
-                .bind(segment)
-                .bind(gateway_id)
-                .execute(&self.pool)
-                .await?;
-        } else {
-            sqlx::query("UPDATE bandit_state SET beta = beta + 1, updated_at=now() WHERE segment=$1 AND gateway_id=$2")
-                .bind(segment)
-                .bind(gateway_id)
-                .execute(&self.pool)
-                .await?;
+    pub async fn update_outcome(&self, segment: &BanditSegment, gateway_id: &str, reward: f64) -> Result<()> {
+        let decay = format!(
+            "COALESCE(power(0.5, LEAST(GREATEST(EXTRACT(EPOCH FROM (now() - s.updated_at)), 0) / 60.0 / {}, {})), 1.0)",
+            HALF_LIFE_EXPR,
+            MAX_HALF_LIVES
+        );
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO bandit_state AS s (definition_id, segment, gateway_id, alpha, beta, updated_at)
+            VALUES ($1, $2, $3, 1.0 + $4, 1.0 + $5, now())
+            ON CONFLICT (definition_id, segment, gateway_id) DO UPDATE SET
+                alpha = 1.0 + GREATEST(s.alpha - 1.0, 0) * {decay} + $4,
+                beta = 1.0 + GREATEST(s.beta - 1.0, 0) * {decay} + $5,
+                updated_at = now()
+            RETURNING (xmax = 0) AS inserted
+            "#,
+            decay = decay,
+        ))
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(gateway_id)
+        .bind(reward.clamp(0.0, 1.0))
+        .bind(1.0 - reward.clamp(0.0, 1.0))
+        .fetch_one(&self.pool)
+        .await?;
+        if !row.get::<bool, _>("inserted") {
+            return Ok(());
         }
+
+        let (alpha, beta) = self.warm_start(segment, gateway_id).await?;
+        sqlx::query(
+            "UPDATE bandit_state SET alpha = alpha + $4 - 1.0, beta = beta + $5 - 1.0 WHERE definition_id=$1 AND segment=$2 AND gateway_id=$3",
+        )
+        .bind(&segment.definition_id)
+        .bind(&segment.key)
+        .bind(gateway_id)
+        .bind(alpha)
+        .bind(beta)
+        .execute(&self.pool)
+        .await?;
         Ok(())
     }
 
-    pub async fn list_state(&self) -> Result<Vec<BanditStateRow>> {
-        let rows = sqlx::query("SELECT segment, gateway_id, alpha, beta FROM bandit_state ORDER BY segment, gateway_id")
-            .fetch_all(&self.pool)
+    pub async fn list_definitions(&self) -> Result<Vec<SegmentDefinition>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT definition_id, dimensions, fallback_definition_id, min_observations, priority, timezone
+            FROM bandit_segment_definitions
+            ORDER BY priority ASC, definition_id ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter()
+            .map(|r| {
+                let dimensions = r
+                    .get::<Vec<String>, _>("dimensions")
+                    .iter()
+                    .map(|d| SegmentDimension::parse(d).ok_or_else(|| anyhow::anyhow!("unknown segment dimension {}", d)))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(SegmentDefinition {
+                    definition_id: r.get("definition_id"),
+                    dimensions,
+                    fallback_definition_id: r.get("fallback_definition_id"),
+                    min_observations: r.get("min_observations"),
+                    priority: r.get("priority"),
+                    timezone: r.get("timezone"),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn upsert_definition(&self, definition: &SegmentDefinition) -> Result<()> {
+        let dimensions: Vec<&str> = definition.dimensions.iter().map(|d| d.as_str()).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO bandit_segment_definitions (definition_id, dimensions, fallback_definition_id, min_observations, priority, timezone, updated_at)
+            VALUES ($1,$2,$3,$4,$5,$6,now())
+            ON CONFLICT (definition_id) DO UPDATE SET
+                dimensions=$2,
+                fallback_definition_id=$3,
+                min_observations=$4,
+                priority=$5,
+                timezone=$6,
+                updated_at=now()
+            "#,
+        )
+        .bind(&definition.definition_id)
+        .bind(&dimensions)
+        .bind(&definition.fallback_definition_id)
+        .bind(definition.min_observations)
+        .bind(definition.priority)
+        .bind(&definition.timezone)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn definition_policies(&self, definition_id: &str) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) AS policies FROM bandit_policy WHERE definition_id=$1")
+            .bind(definition_id)
+            .fetch_one(&self.pool)
             .await?;
+        Ok(row.get("policies"))
+    }
 
-        Ok(rows
-            .into_iter()
-            .map(|row| BanditStateRow {
-                segment: row.get("segment"),
-                gateway_id: row.get("gateway_id"),
-                alpha: row.get("alpha"),
-                beta: row.get("beta"),
-            })
-            .collect())
+    pub async fn delete_definition(&self, definition_id: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        for table in ["bandit_state", "bandit_linear_models"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE definition_id=$1", table))
+                .bind(definition_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        let result = sqlx::query("DELETE FROM bandit_segment_definitions WHERE definition_id=$1")
+            .bind(definition_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn list_state(&self) -> Result<Vec<BanditStateRow>> {
+        let rows = sqlx::query(&format!(
+            "SELECT s.definition_id, s.segment, s.gateway_id, s.alpha, s.beta, s.updated_at, {} AS half_life FROM bandit_state s ORDER BY s.definition_id, s.segment, s.gateway_id",
+            HALF_LIFE_EXPR
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let now = Utc::now();
+        Ok(rows.iter().map(|row| map_state(row, now)).collect())
+    }
+}
+
+fn map_state(row: &PgRow, now: DateTime<Utc>) -> BanditStateRow {
+    let raw_alpha: f64 = row.get("alpha");
+    let raw_beta: f64 = row.get("beta");
+    let updated_at: DateTime<Utc> = row.get("updated_at");
+    let half_life_minutes: Option<f64> = row.get("half_life");
+    let elapsed_minutes = (now - updated_at).num_milliseconds() as f64 / 60_000.0;
+    let (alpha, beta) = decayed(raw_alpha, raw_beta, elapsed_minutes, half_life_minutes);
+    BanditStateRow {
+        definition_id: row.get("definition_id"),
+        segment: row.get("segment"),
+        gateway_id: row.get("gateway_id"),
+        alpha,
+        beta,
+        raw_alpha,
+        raw_beta,
+        half_life_minutes,
+        updated_at,
+        posterior: summarize(alpha, beta),
     }
 }
@@ -0,0 +1,65 @@
+use crate::bins::index::{BinRecord, CardType};
+use anyhow::Result;
+use sqlx::{PgPool, Row};
+
+const IMPORT_CHUNK_SIZE: usize = 5_000;
+
+#[derive(Clone)]
+pub struct BinsRepo {
+    pub pool: PgPool,
+}
+
+impl BinsRepo {
+    pub async fn list_all(&self) -> Result<Vec<BinRecord>> {
+        let rows = sqlx::query(
+            "SELECT bin_start, bin_end, card_network, card_type, issuer_country, issuer_id FROM card_bins",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|r| {
+                let card_type = CardType::parse(&r.get::<String, _>("card_type"))?;
+                Some(BinRecord {
+                    bin_start: r.get("bin_start"),
+                    bin_end: r.get("bin_end"),
+                    card_network: r.get("card_network"),
+                    card_type,
+                    issuer_country: r.get("issuer_country"),
+                    issuer_id: r.get("issuer_id"),
+                })
+            })
+            .collect())
+    }
+
+    pub async fn import(&self, records: &[BinRecord]) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut upserted = 0;
+        for chunk in records.chunks(IMPORT_CHUNK_SIZE) {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO card_bins (bin_start, bin_end, card_network, card_type, issuer_country, issuer_id)
+                SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[], $4::text[], $5::text[], $6::text[])
+                ON CONFLICT (bin_start, bin_end) DO UPDATE SET
+                    card_network=EXCLUDED.card_network,
+                    card_type=EXCLUDED.card_type,
+                    issuer_country=EXCLUDED.issuer_country,
+                    issuer_id=EXCLUDED.issuer_id,
+                    updated_at=now()
+                "#,
+            )
+            .bind(chunk.iter().map(|r| r.bin_start.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.bin_end.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.card_network.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.card_type.as_str().to_string()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.issuer_country.clone()).collect::<Vec<_>>())
+            .bind(chunk.iter().map(|r| r.issuer_id.clone()).collect::<Vec<_>>())
+            .execute(tx.as_mut())
+            .await?;
+            upserted += result.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(upserted)
+    }
+}
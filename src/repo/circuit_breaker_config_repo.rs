@@ -1,5 +1,6 @@
 use anyhow::Result;
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct CircuitBreakerConfigRepo {
@@ -19,6 +20,22 @@ pub struct CircuitThresholds {
     pub half_open_consecutive_failure_reopen: i32,
 }
 
+impl Default for CircuitThresholds {
+    fn default() -> Self {
+        Self {
+            failure_rate_threshold_2m: 0.40,
+            consecutive_failure_threshold: 10,
+            timeout_rate_threshold_5m: 0.50,
+            cooldown_seconds: 30,
+            half_open_probe_ratio: 0.10,
+            half_open_min_probe_count: 5,
+            half_open_success_rate_close: 0.80,
+            half_open_consecutive_success_close: 5,
+            half_open_consecutive_failure_reopen: 3,
+        }
+    }
+}
+
 impl CircuitBreakerConfigRepo {
     pub async fn get_thresholds(&self, gateway_id: &str, payment_method: &str) -> Result<CircuitThresholds> {
         let row = sqlx::query(
@@ -36,29 +53,42 @@ impl CircuitBreakerConfigRepo {
         .await?;
 
         if let Some(row) = row {
-            Ok(CircuitThresholds {
-                failure_rate_threshold_2m: row.get("failure_rate_threshold_2m"),
-                consecutive_failure_threshold: row.get("consecutive_failure_threshold"),
-                timeout_rate_threshold_5m: row.get("timeout_rate_threshold_5m"),
-                cooldown_seconds: row.get("cooldown_seconds"),
-                half_open_probe_ratio: row.get("half_open_probe_ratio"),
-                half_open_min_probe_count: row.get("half_open_min_probe_count"),
-                half_open_success_rate_close: row.get("half_open_success_rate_close"),
-                half_open_consecutive_success_close: row.get("half_open_consecutive_success_close"),
-                half_open_consecutive_failure_reopen: row.get("half_open_consecutive_failure_reopen"),
-            })
+            Ok(map_thresholds(&row))
         } else {
-            Ok(CircuitThresholds {
-                failure_rate_threshold_2m: 0.40,
-                consecutive_failure_threshold: 10,
-                timeout_rate_threshold_5m: 0.50,
-                cooldown_seconds: 30,
-                half_open_probe_ratio: 0.10,
-                half_open_min_probe_count: 5,
-                half_open_success_rate_close: 0.80,
-                half_open_consecutive_success_close: 5,
-                half_open_consecutive_failure_reopen: 3,
-            })
+            Ok(CircuitThresholds::default())
         }
     }
+
+    pub async fn list_all(&self) -> Result<HashMap<(String, String), CircuitThresholds>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT gateway_id, payment_method, failure_rate_threshold_2m, consecutive_failure_threshold,
+                   timeout_rate_threshold_5m, cooldown_seconds, half_open_probe_ratio, half_open_min_probe_count,
+                   half_open_success_rate_close, half_open_consecutive_success_close,
+                   half_open_consecutive_failure_reopen
+            FROM circuit_breaker_config
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| ((r.get("gateway_id"), r.get("payment_method")), map_thresholds(r)))
+            .collect())
+    }
+}
+
+fn map_thresholds(row: &sqlx::postgres::PgRow) -> CircuitThresholds {
+    CircuitThresholds {
+        failure_rate_threshold_2m: row.get("failure_rate_threshold_2m"),
+        consecutive_failure_threshold: row.get("consecutive_failure_threshold"),
+        timeout_rate_threshold_5m: row.get("timeout_rate_threshold_5m"),
+        cooldown_seconds: row.get("cooldown_seconds"),
+        half_open_probe_ratio: row.get("half_open_probe_ratio"),
+        half_open_min_probe_count: row.get("half_open_min_probe_count"),
+        half_open_success_rate_close: row.get("half_open_success_rate_close"),
+        half_open_consecutive_success_close: row.get("half_open_consecutive_success_close"),
+        half_open_consecutive_failure_reopen: row.get("half_open_consecutive_failure_reopen"),
+    }
 }
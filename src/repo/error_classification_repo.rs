@@ -1,12 +1,13 @@
 use anyhow::Result;
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct ErrorClassificationRepo {
     pub pool: PgPool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct ErrorClass {
     pub retryable: bool,
     pub timeout_like: bool,
@@ -30,11 +31,29 @@ impl ErrorClassificationRepo {
                 non_retryable_user_error: row.get("non_retryable_user_error"),
             })
         } else {
-            Ok(ErrorClass {
-                retryable: false,
-                timeout_like: false,
-                non_retryable_user_error: false,
-            })
+            Ok(ErrorClass::default())
         }
     }
+
+    pub async fn list_all(&self) -> Result<HashMap<(String, String), ErrorClass>> {
+        let rows = sqlx::query(
+            "SELECT gateway_id, error_code, retryable, timeout_like, non_retryable_user_error FROM gateway_error_classification",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| {
+                (
+                    (r.get("gateway_id"), r.get("error_code")),
+                    ErrorClass {
+                        retryable: r.get("retryable"),
+                        timeout_like: r.get("timeout_like"),
+                        non_retryable_user_error: r.get("non_retryable_user_error"),
+                    },
+                )
+            })
+            .collect())
+    }
 }
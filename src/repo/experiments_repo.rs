@@ -1,6 +1,12 @@
-use crate::domain::experiment::{Experiment, ExperimentFilter, ExperimentResultRow};
+use crate::domain::experiment::{
+    AnalysisMethod, Experiment, ExperimentFilter, ExperimentResultRow, ExperimentStatus, ExperimentVariant, LifecycleEvent, RampStep,
+};
+use crate::experiments::assigner::legacy_variants;
+use crate::experiments::lifecycle::{initial_exposure, initial_status, LifecycleAction, Transition};
 use anyhow::Result;
+use sqlx::postgres::PgRow;
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -17,9 +23,18 @@ pub struct CreateExperimentInput {
     pub start_date: chrono::DateTime<chrono::Utc>,
     pub end_date: Option<chrono::DateTime<chrono::Utc>>,
     pub created_by: String,
+    pub variants: Vec<ExperimentVariant>,
+    pub ramp_schedule: Vec<RampStep>,
+    pub analysis_method: AnalysisMethod,
     pub filter: CreateExperimentFilterInput,
 }
 
+const EXPERIMENT_COLUMNS: &str = r#"
+    e.experiment_id, e.name, e.status, e.traffic_control_pct, e.traffic_treatment_pct,
+    e.treatment_gateway, e.start_date, e.end_date, e.created_by, e.exposure_pct,
+    e.ramp_schedule_json, e.ramp_step, e.ramped_at
+"#;
+
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct CreateExperimentFilterInput {
     pub payment_method: Option<String>,
@@ -27,35 +42,82 @@ pub struct CreateExperimentFilterInput {
     pub max_amount_minor: Option<i64>,
     pub merchant_id: Option<String>,
     pub amount_bucket: Option<String>,
+    #[serde(default)]
+    pub card_network: Option<String>,
+    #[serde(default)]
+    pub card_type: Option<String>,
 }
 
 impl ExperimentsRepo {
     pub async fn create(&self, input: CreateExperimentInput) -> Result<Experiment> {
         let experiment_id = Uuid::new_v4();
+        let now = chrono::Utc::now();
+        let status = initial_status(input.start_date, now);
+        let (exposure_pct, ramp_step) = initial_exposure(&input.ramp_schedule);
+        let ramped_at = (status == ExperimentStatus::Running).then_some(now);
+        let mut tx = self.pool.begin().await?;
         sqlx::query(
             r#"
             INSERT INTO experiments (
                 experiment_id, name, status, traffic_control_pct, traffic_treatment_pct,
-                treatment_gateway, start_date, end_date, created_by
-            ) VALUES ($1,$2,'RUNNING',$3,$4,$5,$6,$7,$8)
+                treatment_gateway, start_date, end_date, created_by, exposure_pct,
+                ramp_schedule_json, ramp_step, ramped_at, analysis_method
+            ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14)
             "#,
         )
         .bind(experiment_id)
         .bind(&input.name)
+        .bind(status.as_str())
         .bind(input.traffic_control_pct)
         .bind(input.traffic_treatment_pct)
         .bind(&input.treatment_gateway)
         .bind(input.start_date)
         .bind(input.end_date)
         .bind(&input.created_by)
-        .execute(&self.pool)
+        .bind(exposure_pct)
+        .bind(serde_json::to_value(&input.ramp_schedule)?)
+        .bind(ramp_step)
+        .bind(ramped_at)
+        .bind(input.analysis_method.as_str())
+        .execute(&mut *tx)
+        .await?;
+        insert_event(
+            &mut tx,
+            experiment_id,
+            None,
+            &Transition::new(LifecycleAction::Created, status),
+            exposure_pct,
+            &input.created_by,
+        )
         .await?;
 
+        for variant in &input.variants {
+            sqlx::query(
+                r#"
+                INSERT INTO experiment_variants (
+                    experiment_id, variant_name, traffic_pct, override_gateway, scoring_profile_id,
+                    bandit_enabled, rule_set, retry_policy_json
+                ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8)
+                "#,
+            )
+            .bind(experiment_id)
+            .bind(&variant.variant_name)
+            .bind(variant.traffic_pct)
+            .bind(&variant.override_gateway)
+            .bind(&variant.scoring_profile_id)
+            .bind(variant.bandit_enabled)
+            .bind(&variant.rule_set)
+            .bind(variant.retry_policy.as_ref().map(serde_json::to_value).transpose()?)
+            .execute(&mut *tx)
+            .await?;
+        }
+
         sqlx::query(
             r#"
             INSERT INTO experiment_filters (
-                experiment_id, payment_method, min_amount_minor, max_amount_minor, merchant_id, amount_bucket
-            ) VALUES ($1,$2,$3,$4,$5,$6)
+                experiment_id, payment_method, min_amount_minor, max_amount_minor, merchant_id, amount_bucket,
+                card_network, card_type
+            ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8)
             "#,
         )
         .bind(experiment_id)
@@ -64,96 +126,206 @@ impl ExperimentsRepo {
         .bind(input.filter.max_amount_minor)
         .bind(input.filter.merchant_id)
         .bind(input.filter.amount_bucket)
-        .execute(&self.pool)
+        .bind(input.filter.card_network.map(|n| n.to_uppercase()))
+        .bind(input.filter.card_type.map(|t| t.to_uppercase()))
+        .execute(&mut *tx)
         .await?;
+        tx.commit().await?;
 
         Ok(Experiment {
             experiment_id,
             name: input.name,
-            status: "RUNNING".to_string(),
+            status: status.as_str().to_string(),
             traffic_control_pct: input.traffic_control_pct,
             traffic_treatment_pct: input.traffic_treatment_pct,
             treatment_gateway: input.treatment_gateway,
             start_date: input.start_date,
             end_date: input.end_date,
             created_by: input.created_by,
+            variants: input.variants,
+            exposure_pct,
+            ramp_schedule: input.ramp_schedule,
+            ramp_step,
+            ramped_at,
+            analysis_method: input.analysis_method.as_str().to_string(),
         })
     }
 
-    pub async fn list(&self) -> Result<Vec<Experiment>> {
+    async fn variants(&self, experiment_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<ExperimentVariant>>> {
         let rows = sqlx::query(
-            "SELECT experiment_id, name, status, traffic_control_pct, traffic_treatment_pct, treatment_gateway, start_date, end_date, created_by FROM experiments ORDER BY created_at DESC",
+            r#"
+            SELECT experiment_id, variant_name, traffic_pct, override_gateway, scoring_profile_id,
+                   bandit_enabled, rule_set, retry_policy_json
+            FROM experiment_variants
+            WHERE experiment_id = ANY($1)
+            ORDER BY experiment_id, variant_name <> 'control', variant_name
+            "#,
         )
+        .bind(experiment_ids)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(|row| Experiment {
-                experiment_id: row.get("experiment_id"),
-                name: row.get("name"),
-                status: row.get("status"),
-                traffic_control_pct: row.get("traffic_control_pct"),
-                traffic_treatment_pct: row.get("traffic_treatment_pct"),
-                treatment_gateway: row.get("treatment_gateway"),
-                start_date: row.get("start_date"),
-                end_date: row.get("end_date"),
-                created_by: row.get("created_by"),
-            })
-            .collect())
+        let mut out: HashMap<Uuid, Vec<ExperimentVariant>> = HashMap::new();
+        for row in rows {
+            let retry_policy: Option<serde_json::Value> = row.get("retry_policy_json");
+            out.entry(row.get("experiment_id")).or_default().push(ExperimentVariant {
+                variant_name: row.get("variant_name"),
+                traffic_pct: row.get("traffic_pct"),
+                override_gateway: row.get("override_gateway"),
+                scoring_profile_id: row.get("scoring_profile_id"),
+                bandit_enabled: row.get("bandit_enabled"),
+                rule_set: row.get("rule_set"),
+                retry_policy: retry_policy.map(serde_json::from_value).transpose()?,
+            });
+        }
+        Ok(out)
+    }
+
+    async fn with_variants(&self, mut experiments: Vec<Experiment>) -> Result<Vec<Experiment>> {
+        let ids: Vec<Uuid> = experiments.iter().map(|e| e.experiment_id).collect();
+        let mut variants = self.variants(&ids).await?;
+        for exp in &mut experiments {
+            exp.variants = variants.remove(&exp.experiment_id).unwrap_or_else(|| {
+                legacy_variants(exp.traffic_control_pct, exp.traffic_treatment_pct, &exp.treatment_gateway)
+            });
+        }
+        Ok(experiments)
+    }
+
+    pub async fn list(&self) -> Result<Vec<Experiment>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM experiments e ORDER BY e.created_at DESC",
+            EXPERIMENT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let experiments = rows.iter().map(map_experiment).collect::<Result<Vec<_>>>()?;
+        self.with_variants(experiments).await
+    }
+
+    pub async fn get(&self, experiment_id: Uuid) -> Result<Option<Experiment>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM experiments e WHERE e.experiment_id=$1",
+            EXPERIMENT_COLUMNS
+        ))
+        .bind(experiment_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(self.with_variants(vec![map_experiment(&row)?]).await?.pop()),
+            None => Ok(None),
+        }
     }
 
     pub async fn get_active_with_filters(&self) -> Result<Vec<(Experiment, ExperimentFilter)>> {
-        let rows = sqlx::query(
+        let rows = sqlx::query(&format!(
             r#"
-            SELECT e.experiment_id, e.name, e.status, e.traffic_control_pct, e.traffic_treatment_pct,
-                   e.treatment_gateway, e.start_date, e.end_date, e.created_by,
-                   f.payment_method, f.min_amount_minor, f.max_amount_minor, f.merchant_id, f.amount_bucket
+            SELECT {},
+                   f.payment_method, f.min_amount_minor, f.max_amount_minor, f.merchant_id, f.amount_bucket,
+                   f.card_network, f.card_type
             FROM experiments e
             JOIN experiment_filters f ON e.experiment_id = f.experiment_id
-            WHERE e.status='RUNNING' AND e.start_date <= now() AND (e.end_date IS NULL OR e.end_date >= now())
+            WHERE e.status='RUNNING' AND e.start_date <= now() AND (e.end_date IS NULL OR e.end_date > now())
             ORDER BY e.created_at DESC
             "#,
+            EXPERIMENT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut experiments = Vec::with_capacity(rows.len());
+        let mut filters = Vec::with_capacity(rows.len());
+        for row in &rows {
+            experiments.push(map_experiment(row)?);
+            filters.push(ExperimentFilter {
+                experiment_id: row.get("experiment_id"),
+                payment_method: row.get("payment_method"),
+                min_amount_minor: row.get("min_amount_minor"),
+                max_amount_minor: row.get("max_amount_minor"),
+                merchant_id: row.get("merchant_id"),
+                amount_bucket: row.get("amount_bucket"),
+                card_network: row.get("card_network"),
+                card_type: row.get("card_type"),
+            });
+        }
+        let experiments = self.with_variants(experiments).await?;
+        Ok(experiments.into_iter().zip(filters).collect())
+    }
+
+    pub async fn transition(
+        &self,
+        experiment: &Experiment,
+        transition: &Transition,
+        actor: &str,
+    ) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            r#"
+            UPDATE experiments SET
+                status=$3,
+                exposure_pct=COALESCE($4, exposure_pct),
+                ramp_step=COALESCE($5, ramp_step),
+                ramped_at=CASE WHEN $6 THEN now() ELSE ramped_at END
+            WHERE experiment_id=$1 AND status=$2
+            "#,
+        )
+        .bind(experiment.experiment_id)
+        .bind(&experiment.status)
+        .bind(transition.to_status.as_str())
+        .bind(transition.exposure_pct)
+        .bind(transition.ramp_step)
+        .bind(transition.restart_ramp_clock)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        insert_event(
+            &mut tx,
+            experiment.experiment_id,
+            ExperimentStatus::parse(&experiment.status),
+            transition,
+            transition.exposure_pct.unwrap_or(experiment.exposure_pct),
+            actor,
         )
+        .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn history(&self, experiment_id: Uuid) -> Result<Vec<LifecycleEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT event_id, experiment_id, action, from_status, to_status, exposure_pct, reason, actor, created_at
+            FROM experiment_lifecycle_events
+            WHERE experiment_id=$1
+            ORDER BY created_at ASC, event_id ASC
+            "#,
+        )
+        .bind(experiment_id)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(rows
             .into_iter()
-            .map(|row| {
-                (
-                    Experiment {
-                        experiment_id: row.get("experiment_id"),
-                        name: row.get("name"),
-                        status: row.get("status"),
-                        traffic_control_pct: row.get("traffic_control_pct"),
-                        traffic_treatment_pct: row.get("traffic_treatment_pct"),
-                        treatment_gateway: row.get("treatment_gateway"),
-                        start_date: row.get("start_date"),
-                        end_date: row.get("end_date"),
-                        created_by: row.get("created_by"),
-                    },
-                    ExperimentFilter {
-                        experiment_id: row.get("experiment_id"),
-                        payment_method: row.get("payment_method"),
-                        min_amount_minor: row.get("min_amount_minor"),
-                        max_amount_minor: row.get("max_amount_minor"),
-                        merchant_id: row.get("merchant_id"),
-                        amount_bucket: row.get("amount_bucket"),
-                    },
-                )
+            .map(|row| LifecycleEvent {
+                event_id: row.get("event_id"),
+                experiment_id: row.get("experiment_id"),
+                action: row.get("action"),
+                from_status: row.get("from_status"),
+                to_status: row.get("to_status"),
+                exposure_pct: row.get("exposure_pct"),
+                reason: row.get("reason"),
+                actor: row.get("actor"),
+                created_at: row.get("created_at"),
             })
             .collect())
     }
 
-    pub async fn stop(&self, experiment_id: Uuid) -> Result<()> {
-        sqlx::query("UPDATE experiments SET status='PAUSED' WHERE experiment_id=$1")
-            .bind(experiment_id)
-            .execute(&self.pool)
-            .await?;
-        Ok(())
-    }
-
     pub async fn upsert_assignment(&self, experiment_id: Uuid, customer_id: &str, variant: &str, bucket: i32) -> Result<()> {
         sqlx::query(
             r#"
@@ -245,3 +417,51 @@ impl ExperimentsRepo {
             .collect())
     }
 }
+
+async fn insert_event(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    experiment_id: Uuid,
+    from_status: Option<ExperimentStatus>,
+    transition: &Transition,
+    exposure_pct: i32,
+    actor: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO experiment_lifecycle_events (
+            experiment_id, action, from_status, to_status, exposure_pct, reason, actor
+        ) VALUES ($1,$2,$3,$4,$5,$6,$7)
+        "#,
+    )
+    .bind(experiment_id)
+    .bind(transition.action.as_str())
+    .bind(from_status.map(|s| s.as_str()))
+    .bind(transition.to_status.as_str())
+    .bind(exposure_pct)
+    .bind(&transition.reason)
+    .bind(actor)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+fn map_experiment(row: &PgRow) -> Result<Experiment> {
+    let ramp_schedule: serde_json::Value = row.get("ramp_schedule_json");
+    Ok(Experiment {
+        experiment_id: row.get("experiment_id"),
+        name: row.get("name"),
+        status: row.get("status"),
+        traffic_control_pct: row.get("traffic_control_pct"),
+        traffic_treatment_pct: row.get("traffic_treatment_pct"),
+        treatment_gateway: row.get("treatment_gateway"),
+        start_date: row.get("start_date"),
+        end_date: row.get("end_date"),
+        created_by: row.get("created_by"),
+        variants: Vec::new(),
+        exposure_pct: row.get("exposure_pct"),
+        ramp_schedule: serde_json::from_value(ramp_schedule)?,
+        ramp_step: row.get("ramp_step"),
+        ramped_at: row.get("ramped_at"),
+        analysis_method: row.get("analysis_method"),
+    })
+}
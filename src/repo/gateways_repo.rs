@@ -9,7 +9,7 @@ pub struct GatewaysRepo {
 impl GatewaysRepo {
     pub async fn list_all(&self) -> anyhow::Result<Vec<GatewayConfig>> {
         let rows = sqlx::query(
-            "SELECT gateway_id, gateway_name, adapter_type, is_enabled, priority, supported_methods, timeout_ms, mock_behavior FROM gateways_config ORDER BY priority ASC, gateway_name ASC",
+            "SELECT gateway_id, gateway_name, adapter_type, is_enabled, priority, supported_methods, timeout_ms, mock_behavior, max_in_flight, max_tps FROM gateways_config ORDER BY priority ASC, gateway_name ASC",
         )
         .fetch_all(&self.pool)
         .await?;
@@ -25,13 +25,15 @@ impl GatewaysRepo {
                 supported_methods: r.get("supported_methods"),
                 timeout_ms: r.get("timeout_ms"),
                 mock_behavior: r.get("mock_behavior"),
+                max_in_flight: r.get("max_in_flight"),
+                max_tps: r.get("max_tps"),
             })
             .collect())
     }
 
     pub async fn list_enabled_by_method(&self, method: &str) -> anyhow::Result<Vec<GatewayConfig>> {
         let rows = sqlx::query(
-            "SELECT gateway_id, gateway_name, adapter_type, is_enabled, priority, supported_methods, timeout_ms, mock_behavior FROM gateways_config WHERE is_enabled = true AND $1 = ANY(supported_methods) ORDER BY priority ASC, gateway_name ASC",
+            "SELECT gateway_id, gateway_name, adapter_type, is_enabled, priority, supported_methods, timeout_ms, mock_behavior, max_in_flight, max_tps FROM gateways_config WHERE is_enabled = true AND $1 = ANY(supported_methods) ORDER BY priority ASC, gateway_name ASC",
         )
         .bind(method)
         .fetch_all(&self.pool)
@@ -48,6 +50,8 @@ impl GatewaysRepo {
                 supported_methods: r.get("supported_methods"),
                 timeout_ms: r.get("timeout_ms"),
                 mock_behavior: r.get("mock_behavior"),
+                max_in_flight: r.get("max_in_flight"),
+                max_tps: r.get("max_tps"),
             })
             .collect())
     }
@@ -58,14 +62,27 @@ impl GatewaysRepo {
         is_enabled: bool,
         priority: i32,
         supported_methods: Vec<String>,
+        max_in_flight: Option<i32>,
+        max_tps: Option<i32>,
     ) -> anyhow::Result<()> {
         sqlx::query(
-            "UPDATE gateways_config SET is_enabled = $2, priority = $3, supported_methods = $4, updated_at = now() WHERE gateway_id = $1",
+            r#"
+            UPDATE gateways_config SET
+                is_enabled = $2,
+                priority = $3,
+                supported_methods = $4,
+                max_in_flight = CASE WHEN $5::INT IS NULL THEN max_in_flight ELSE NULLIF($5, 0) END,
+                max_tps = CASE WHEN $6::INT IS NULL THEN max_tps ELSE NULLIF($6, 0) END,
+                updated_at = now()
+            WHERE gateway_id = $1
+            "#,
         )
         .bind(gateway_id)
         .bind(is_enabled)
         .bind(priority)
         .bind(supported_methods)
+        .bind(max_in_flight)
+        .bind(max_tps)
         .execute(&self.pool)
         .await?;
 
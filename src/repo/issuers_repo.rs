@@ -0,0 +1,38 @@
+use crate::scoring::issuers::{AliasType, IssuerAlias};
+use anyhow::Result;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct IssuersRepo {
+    pub pool: PgPool,
+}
+
+impl IssuersRepo {
+    pub async fn list_aliases(&self) -> Result<Vec<IssuerAlias>> {
+        let rows = sqlx::query("SELECT alias_type, alias, issuer_id FROM issuer_aliases")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .filter_map(|r| {
+                let alias_type = AliasType::parse(&r.get::<String, _>("alias_type"))?;
+                Some(IssuerAlias {
+                    alias_type,
+                    alias: r.get("alias"),
+                    issuer_id: r.get("issuer_id"),
+                })
+            })
+            .collect())
+    }
+
+    pub async fn all_affinity(&self) -> Result<HashMap<(String, String), f64>> {
+        let rows = sqlx::query("SELECT gateway_id, issuer_id, score FROM gateway_issuer_affinity")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| ((r.get("gateway_id"), r.get("issuer_id")), r.get("score")))
+            .collect())
+    }
+}
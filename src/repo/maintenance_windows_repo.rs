@@ -0,0 +1,136 @@
+use crate::scoring::schedule::{MaintenanceMode, MaintenanceWindow, MaintenanceWindowInput, DEFAULT_PENALTY_MULTIPLIER};
+use anyhow::Result;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct MaintenanceWindowsRepo {
+    pub pool: PgPool,
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT window_id, gateway_id, starts_at, ends_at, payment_methods, issuers, mode,
+           penalty_multiplier, reason
+    FROM gateway_maintenance_windows
+"#;
+
+impl MaintenanceWindowsRepo {
+    pub async fn list(&self) -> Result<Vec<MaintenanceWindow>> {
+        let rows = sqlx::query(&format!("{} ORDER BY starts_at ASC", SELECT_COLUMNS))
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(map_window).collect()
+    }
+
+    pub async fn list_current(&self) -> Result<Vec<MaintenanceWindow>> {
+        let rows = sqlx::query(&format!("{} WHERE ends_at > now() ORDER BY starts_at ASC", SELECT_COLUMNS))
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(map_window).collect()
+    }
+
+    pub async fn get(&self, window_id: Uuid) -> Result<Option<MaintenanceWindow>> {
+        let row = sqlx::query(&format!("{} WHERE window_id=$1", SELECT_COLUMNS))
+            .bind(window_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(map_window).transpose()
+    }
+
+    pub async fn create(&self, input: MaintenanceWindowInput) -> Result<MaintenanceWindow> {
+        let window_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO gateway_maintenance_windows (
+                window_id, gateway_id, starts_at, ends_at, payment_methods, issuers, mode,
+                penalty_multiplier, reason
+            ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)
+            "#,
+        )
+        .bind(window_id)
+        .bind(&input.gateway_id)
+        .bind(input.starts_at)
+        .bind(input.ends_at)
+        .bind(upper(&input.payment_methods))
+        .bind(upper(&input.issuers))
+        .bind(input.mode.as_str())
+        .bind(penalty_multiplier(&input))
+        .bind(&input.reason)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(window_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("maintenance window {} not found after insert", window_id))
+    }
+
+    pub async fn update(&self, window_id: Uuid, input: MaintenanceWindowInput) -> Result<Option<MaintenanceWindow>> {
+        let result = sqlx::query(
+            r#"
+            UPDATE gateway_maintenance_windows SET
+                gateway_id=$2,
+                starts_at=$3,
+                ends_at=$4,
+                payment_methods=$5,
+                issuers=$6,
+                mode=$7,
+                penalty_multiplier=$8,
+                reason=$9,
+                updated_at=now()
+            WHERE window_id=$1
+            "#,
+        )
+        .bind(window_id)
+        .bind(&input.gateway_id)
+        .bind(input.starts_at)
+        .bind(input.ends_at)
+        .bind(upper(&input.payment_methods))
+        .bind(upper(&input.issuers))
+        .bind(input.mode.as_str())
+        .bind(penalty_multiplier(&input))
+        .bind(&input.reason)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get(window_id).await
+    }
+
+    pub async fn delete(&self, window_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM gateway_maintenance_windows WHERE window_id=$1")
+            .bind(window_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn upper(values: &[String]) -> Vec<String> {
+    values.iter().map(|v| v.trim().to_uppercase()).collect()
+}
+
+fn penalty_multiplier(input: &MaintenanceWindowInput) -> f64 {
+    match input.mode {
+        MaintenanceMode::Exclude => 0.0,
+        MaintenanceMode::Penalize => input.penalty_multiplier.unwrap_or(DEFAULT_PENALTY_MULTIPLIER),
+    }
+}
+
+fn map_window(row: &PgRow) -> Result<MaintenanceWindow> {
+    let mode: String = row.get("mode");
+    Ok(MaintenanceWindow {
+        window_id: row.get("window_id"),
+        gateway_id: row.get("gateway_id"),
+        starts_at: row.get("starts_at"),
+        ends_at: row.get("ends_at"),
+        payment_methods: row.get("payment_methods"),
+        issuers: row.get("issuers"),
+        mode: MaintenanceMode::parse(&mode)
+            .ok_or_else(|| anyhow::anyhow!("unknown maintenance mode {}", mode))?,
+        penalty_multiplier: row.get("penalty_multiplier"),
+        reason: row.get("reason"),
+    })
+}
@@ -9,6 +9,8 @@ pub struct PaymentRecordInput {
     pub request_hash: String,
     pub req: CreatePaymentRequest,
     pub issuing_bank: Option<String>,
+    pub card_network: Option<String>,
+    pub card_type: Option<String>,
     pub gateway_used: String,
     pub routing_strategy: String,
     pub routing_reason: String,
@@ -17,6 +19,7 @@ pub struct PaymentRecordInput {
     pub gateway_response_code: Option<String>,
     pub error_message: Option<String>,
     pub latency_ms: i32,
+    pub expected_fee_minor: Option<i64>,
 }
 
 #[derive(Clone)]
@@ -75,18 +78,20 @@ impl PaymentsRepo {
             INSERT INTO payments (
                 payment_id, merchant_id, idempotency_key, request_hash, amount_minor, currency,
                 payment_method, issuing_bank, gateway_used, routing_strategy, routing_reason,
-                status, gateway_transaction_ref, gateway_response_code, error_message, latency_ms
+                status, gateway_transaction_ref, gateway_response_code, error_message, latency_ms,
+                card_network, expected_fee_minor, card_type
             ) VALUES (
                 $1, $2, $3, $4, $5, $6,
                 $7, $8, $9, $10, $11,
-                $12, $13, $14, $15, $16
+                $12, $13, $14, $15, $16,
+                $17, $18, $19
             )
             "#,
         )
         .bind(data.payment_id)
-        .bind(data.merchant_id)
-        .bind(data.idempotency_key)
-        .bind(data.request_hash)
+        .bind(&data.merchant_id)
+        .bind(&data.idempotency_key)
+        .bind(&data.request_hash)
         .bind(data.req.amount_minor)
         .bind(data.req.currency.clone())
         .bind(format!("{:?}", data.req.payment_method))
@@ -99,6 +104,9 @@ impl PaymentsRepo {
         .bind(data.gateway_response_code.clone())
         .bind(data.error_message.clone())
         .bind(data.latency_ms)
+        .bind(data.card_network.clone())
+        .bind(data.expected_fee_minor)
+        .bind(data.card_type.clone())
         .execute(tx.as_mut())
         .await?;
 
@@ -15,6 +15,18 @@ pub struct RetryPolicy {
     pub enabled: bool,
 }
 
+impl RetryPolicy {
+    pub fn default_for(merchant_id: &str) -> Self {
+        Self {
+            merchant_id: merchant_id.to_string(),
+            max_attempts: 3,
+            latency_budget_ms: 10000,
+            retry_on_timeout: false,
+            enabled: true,
+        }
+    }
+}
+
 impl RetryPolicyRepo {
     pub async fn get_for_merchant(&self, merchant_id: &str) -> Result<RetryPolicy> {
         let row = sqlx::query(
@@ -33,16 +45,29 @@ impl RetryPolicyRepo {
                 enabled: row.get("enabled"),
             })
         } else {
-            Ok(RetryPolicy {
-                merchant_id: merchant_id.to_string(),
-                max_attempts: 3,
-                latency_budget_ms: 10000,
-                retry_on_timeout: false,
-                enabled: true,
-            })
+            Ok(RetryPolicy::default_for(merchant_id))
         }
     }
 
+    pub async fn list_all(&self) -> Result<Vec<RetryPolicy>> {
+        let rows = sqlx::query(
+            "SELECT merchant_id, max_attempts, latency_budget_ms, retry_on_timeout, enabled FROM retry_policy",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| RetryPolicy {
+                merchant_id: row.get("merchant_id"),
+                max_attempts: row.get("max_attempts"),
+                latency_budget_ms: row.get("latency_budget_ms"),
+                retry_on_timeout: row.get("retry_on_timeout"),
+                enabled: row.get("enabled"),
+            })
+            .collect())
+    }
+
     pub async fn upsert(&self, policy: RetryPolicy) -> Result<()> {
         sqlx::query(
             r#"
@@ -8,19 +8,29 @@ pub struct RoutingDecisionsRepo {
     pub pool: PgPool,
 }
 
+#[derive(Debug, Clone)]
+pub struct NewRoutingDecision {
+    pub payment_id: Uuid,
+    pub selected_gateway: String,
+    pub selected_score: f64,
+    pub runner_up_gateway: Option<String>,
+    pub runner_up_score: Option<f64>,
+    pub strategy: String,
+    pub reason_summary: String,
+    pub score_breakdown_json: serde_json::Value,
+    pub ranked_gateways_json: serde_json::Value,
+    pub matched_rule_id: Option<Uuid>,
+    pub matched_rule_ids: serde_json::Value,
+    pub dropped_gateways_json: serde_json::Value,
+    pub penalized_gateways_json: serde_json::Value,
+    pub scoring_profile_id: Option<String>,
+    pub scoring_profile_version: Option<i32>,
+    pub bandit_propensity: Option<f64>,
+    pub bandit_selection_json: Option<serde_json::Value>,
+}
+
 impl RoutingDecisionsRepo {
-    pub async fn insert(
-        &self,
-        payment_id: Uuid,
-        selected_gateway: &str,
-        selected_score: f64,
-        runner_up_gateway: Option<&str>,
-        runner_up_score: Option<f64>,
-        strategy: &str,
-        reason_summary: &str,
-        score_breakdown_json: serde_json::Value,
-        ranked_gateways_json: serde_json::Value,
-    ) -> Result<()> {
+    pub async fn insert(&self, in_row: NewRoutingDecision) -> Result<()> {
         sqlx::query(
             r#"
             INSERT INTO routing_decisions (
@@ -32,20 +42,36 @@ impl RoutingDecisionsRepo {
                 strategy,
                 reason_summary,
                 score_breakdown_json,
-                ranked_gateways_json
-            ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)
+                ranked_gateways_json,
+                matched_rule_id,
+                matched_rule_ids,
+                scoring_profile_id,
+                scoring_profile_version,
+                dropped_gateways_json,
+                bandit_propensity,
+                bandit_selection_json,
+                penalized_gateways_json
+            ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16,$17)
             ON CONFLICT (payment_id) DO NOTHING
             "#,
         )
-        .bind(payment_id)
-        .bind(selected_gateway)
-        .bind(selected_score)
-        .bind(runner_up_gateway)
-        .bind(runner_up_score)
-        .bind(strategy)
-        .bind(reason_summary)
-        .bind(score_breakdown_json)
-        .bind(ranked_gateways_json)
+        .bind(in_row.payment_id)
+        .bind(in_row.selected_gateway)
+        .bind(in_row.selected_score)
+        .bind(in_row.runner_up_gateway)
+        .bind(in_row.runner_up_score)
+        .bind(in_row.strategy)
+        .bind(in_row.reason_summary)
+        .bind(in_row.score_breakdown_json)
+        .bind(in_row.ranked_gateways_json)
+        .bind(in_row.matched_rule_id)
+        .bind(in_row.matched_rule_ids)
+        .bind(in_row.scoring_profile_id)
+        .bind(in_row.scoring_profile_version)
+        .bind(in_row.dropped_gateways_json)
+        .bind(in_row.bandit_propensity)
+        .bind(in_row.bandit_selection_json)
+        .bind(in_row.penalized_gateways_json)
         .execute(&self.pool)
         .await?;
         Ok(())
@@ -55,7 +81,9 @@ impl RoutingDecisionsRepo {
         let row = sqlx::query(
             r#"
             SELECT payment_id, selected_gateway, selected_score, runner_up_gateway, runner_up_score,
-                   strategy, reason_summary, score_breakdown_json, ranked_gateways_json, created_at
+                   strategy, reason_summary, score_breakdown_json, ranked_gateways_json,
+                   matched_rule_id, matched_rule_ids, dropped_gateways_json, penalized_gateways_json, scoring_profile_id, scoring_profile_version,
+                   bandit_propensity, bandit_selection_json, created_at
             FROM routing_decisions WHERE payment_id=$1
             "#,
         )
@@ -73,6 +101,14 @@ impl RoutingDecisionsRepo {
             reason_summary: r.get("reason_summary"),
             score_breakdown_json: r.get("score_breakdown_json"),
             ranked_gateways_json: r.get("ranked_gateways_json"),
+            matched_rule_id: r.get("matched_rule_id"),
+            matched_rule_ids: r.get("matched_rule_ids"),
+            dropped_gateways_json: r.get("dropped_gateways_json"),
+            penalized_gateways_json: r.get("penalized_gateways_json"),
+            scoring_profile_id: r.get("scoring_profile_id"),
+            scoring_profile_version: r.get("scoring_profile_version"),
+            bandit_propensity: r.get("bandit_propensity"),
+            bandit_selection_json: r.get("bandit_selection_json"),
             created_at: r.get("created_at"),
         }))
     }
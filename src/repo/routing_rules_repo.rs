@@ -0,0 +1,180 @@
+use crate::domain::routing_rule::{default_rule_set, RoutingRule, RuleAction, RuleConditions};
+use anyhow::Result;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct RoutingRulesRepo {
+    pub pool: PgPool,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct RoutingRuleInput {
+    pub name: String,
+    pub priority: i32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub conditions: RuleConditions,
+    pub action: RuleAction,
+    pub gateway_id: String,
+    pub boost: Option<f64>,
+    #[serde(default = "default_rule_set")]
+    pub rule_set: String,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+const SELECT_COLUMNS: &str = r#"
+    SELECT rule_id, name, priority, enabled, payment_method, merchant_id, issuing_bank,
+           min_amount_minor, max_amount_minor, amount_bucket, card_network, card_type,
+           action, gateway_id, boost, rule_set, created_at, updated_at
+    FROM routing_rules
+"#;
+
+impl RoutingRulesRepo {
+    pub async fn list(&self) -> Result<Vec<RoutingRule>> {
+        let rows = sqlx::query(&format!("{} ORDER BY priority ASC, created_at ASC", SELECT_COLUMNS))
+            .fetch_all(&self.pool)
+            .await?;
+        rows.iter().map(map_rule).collect()
+    }
+
+    pub async fn list_enabled(&self) -> Result<Vec<RoutingRule>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE enabled=TRUE ORDER BY priority ASC, created_at ASC",
+            SELECT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(map_rule).collect()
+    }
+
+    pub async fn get(&self, rule_id: Uuid) -> Result<Option<RoutingRule>> {
+        let row = sqlx::query(&format!("{} WHERE rule_id=$1", SELECT_COLUMNS))
+            .bind(rule_id)
+            .fetch_optional(&self.pool)
+            .await?;
+        row.as_ref().map(map_rule).transpose()
+    }
+
+    pub async fn create(&self, input: RoutingRuleInput) -> Result<RoutingRule> {
+        let rule_id = Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO routing_rules (
+                rule_id, name, priority, enabled, payment_method, merchant_id, issuing_bank,
+                min_amount_minor, max_amount_minor, amount_bucket, action, gateway_id, boost,
+                card_network, card_type, rule_set
+            ) VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9,$10,$11,$12,$13,$14,$15,$16)
+            "#,
+        )
+        .bind(rule_id)
+        .bind(&input.name)
+        .bind(input.priority)
+        .bind(input.enabled)
+        .bind(input.conditions.payment_method.as_ref().map(|m| m.to_uppercase()))
+        .bind(&input.conditions.merchant_id)
+        .bind(input.conditions.issuing_bank.as_ref().map(|b| b.to_uppercase()))
+        .bind(input.conditions.min_amount_minor)
+        .bind(input.conditions.max_amount_minor)
+        .bind(&input.conditions.amount_bucket)
+        .bind(input.action.as_str())
+        .bind(&input.gateway_id)
+        .bind(input.boost)
+        .bind(input.conditions.card_network.as_ref().map(|n| n.to_uppercase()))
+        .bind(input.conditions.card_type.as_ref().map(|t| t.to_uppercase()))
+        .bind(&input.rule_set)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(rule_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("routing rule {} not found after insert", rule_id))
+    }
+
+    pub async fn update(&self, rule_id: Uuid, input: RoutingRuleInput) -> Result<Option<RoutingRule>> {
+        let result = sqlx::query(
+            r#"
+            UPDATE routing_rules SET
+                name=$2,
+                priority=$3,
+                enabled=$4,
+                payment_method=$5,
+                merchant_id=$6,
+                issuing_bank=$7,
+                min_amount_minor=$8,
+                max_amount_minor=$9,
+                amount_bucket=$10,
+                action=$11,
+                gateway_id=$12,
+                boost=$13,
+                card_network=$14,
+                card_type=$15,
+                rule_set=$16,
+                updated_at=now()
+            WHERE rule_id=$1
+            "#,
+        )
+        .bind(rule_id)
+        .bind(&input.name)
+        .bind(input.priority)
+        .bind(input.enabled)
+        .bind(input.conditions.payment_method.as_ref().map(|m| m.to_uppercase()))
+        .bind(&input.conditions.merchant_id)
+        .bind(input.conditions.issuing_bank.as_ref().map(|b| b.to_uppercase()))
+        .bind(input.conditions.min_amount_minor)
+        .bind(input.conditions.max_amount_minor)
+        .bind(&input.conditions.amount_bucket)
+        .bind(input.action.as_str())
+        .bind(&input.gateway_id)
+        .bind(input.boost)
+        .bind(input.conditions.card_network.as_ref().map(|n| n.to_uppercase()))
+        .bind(input.conditions.card_type.as_ref().map(|t| t.to_uppercase()))
+        .bind(&input.rule_set)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get(rule_id).await
+    }
+
+    pub async fn delete(&self, rule_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM routing_rules WHERE rule_id=$1")
+            .bind(rule_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn map_rule(row: &PgRow) -> Result<RoutingRule> {
+    let action: String = row.get("action");
+    Ok(RoutingRule {
+        rule_id: row.get("rule_id"),
+        name: row.get("name"),
+        priority: row.get("priority"),
+        enabled: row.get("enabled"),
+        conditions: RuleConditions {
+            payment_method: row.get("payment_method"),
+            merchant_id: row.get("merchant_id"),
+            issuing_bank: row.get("issuing_bank"),
+            min_amount_minor: row.get("min_amount_minor"),
+            max_amount_minor: row.get("max_amount_minor"),
+            amount_bucket: row.get("amount_bucket"),
+            card_network: row.get("card_network"),
+            card_type: row.get("card_type"),
+        },
+        action: RuleAction::parse(&action).ok_or_else(|| anyhow::anyhow!("unknown rule action {}", action))?,
+        gateway_id: row.get("gateway_id"),
+        boost: row.get("boost"),
+        rule_set: row.get("rule_set"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
@@ -1,5 +1,7 @@
+use crate::scoring::cost::FeeSlab;
 use anyhow::Result;
-use chrono::{Datelike, Timelike};
+use crate::scoring::schedule::parse_timezone;
+use chrono_tz::Tz;
 use sqlx::{PgPool, Row};
 use std::collections::HashMap;
 
@@ -16,12 +18,22 @@ pub struct ScoringWeights {
     pub bank_affinity_weight: f64,
     pub amount_fit_weight: f64,
     pub time_weight: f64,
+    pub cost_weight: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TimePenalty {
+    pub gateway_id: String,
+    pub hour_of_day: i32,
+    pub day_of_month: Option<i32>,
+    pub multiplier: f64,
+    pub timezone: Tz,
 }
 
 impl ScoringConfigRepo {
     pub async fn load_weights(&self) -> Result<ScoringWeights> {
         let row = sqlx::query(
-            "SELECT success_rate_weight, latency_weight, method_affinity_weight, bank_affinity_weight, amount_fit_weight, time_weight FROM scoring_config WHERE config_id='default'",
+            "SELECT success_rate_weight, latency_weight, method_affinity_weight, bank_affinity_weight, amount_fit_weight, time_weight, cost_weight FROM scoring_config WHERE config_id='default'",
         )
         .fetch_one(&self.pool)
         .await?;
@@ -33,6 +45,7 @@ impl ScoringConfigRepo {
             bank_affinity_weight: row.get("bank_affinity_weight"),
             amount_fit_weight: row.get("amount_fit_weight"),
             time_weight: row.get("time_weight"),
+            cost_weight: row.get("cost_weight"),
         })
     }
 
@@ -61,20 +74,56 @@ impl ScoringConfigRepo {
     }
 
     pub async fn time_multiplier(&self, gateway_id: &str, now: chrono::DateTime<chrono::Utc>) -> Result<f64> {
-        let hour = now.hour() as i32;
-        let day = now.day() as i32;
         let row = sqlx::query(
-            "SELECT multiplier FROM gateway_time_penalty WHERE gateway_id=$1 AND hour_of_day=$2 AND (day_of_month=$3 OR day_of_month IS NULL) ORDER BY day_of_month DESC NULLS LAST LIMIT 1",
+            r#"
+            SELECT multiplier FROM gateway_time_penalty
+            WHERE gateway_id=$1
+              AND hour_of_day = EXTRACT(HOUR FROM $2::timestamptz AT TIME ZONE timezone)::int
+              AND (day_of_month IS NULL OR day_of_month = EXTRACT(DAY FROM $2::timestamptz AT TIME ZONE timezone)::int)
+            ORDER BY day_of_month DESC NULLS LAST LIMIT 1
+            "#,
         )
         .bind(gateway_id)
-        .bind(hour)
-        .bind(day)
+        .bind(now)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(row.map(|r| r.get("multiplier")).unwrap_or(1.0))
     }
 
+    pub async fn fee_slabs(&self, gateway_id: &str, method: &str) -> Result<Vec<FeeSlab>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT gateway_id, payment_method, card_network, card_type, min_amount_minor, max_amount_minor,
+                   pct_fee, fixed_fee_minor, gst_pct
+            FROM gateway_fee_schedule
+            WHERE gateway_id=$1 AND payment_method=$2
+            ORDER BY min_amount_minor ASC
+            "#,
+        )
+        .bind(gateway_id)
+        .bind(method)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(map_fee_slab).collect())
+    }
+
+    pub async fn all_fee_slabs(&self) -> Result<Vec<FeeSlab>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT gateway_id, payment_method, card_network, card_type, min_amount_minor, max_amount_minor,
+                   pct_fee, fixed_fee_minor, gst_pct
+            FROM gateway_fee_schedule
+            ORDER BY gateway_id, payment_method, min_amount_minor ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(map_fee_slab).collect())
+    }
+
     pub async fn resolve_bank_from_bin(&self, card_number: &str) -> Result<Option<String>> {
         if card_number.len() < 6 {
             return Ok(None);
@@ -99,4 +148,64 @@ impl ScoringConfigRepo {
         }
         Ok(out)
     }
+
+    pub async fn all_method_affinity(&self) -> Result<HashMap<(String, String), f64>> {
+        let rows = sqlx::query("SELECT gateway_id, payment_method, score FROM gateway_method_affinity")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| ((r.get("gateway_id"), r.get("payment_method")), r.get("score")))
+            .collect())
+    }
+
+    pub async fn all_amount_fit(&self) -> Result<HashMap<(String, String), f64>> {
+        let rows = sqlx::query("SELECT gateway_id, amount_bucket, score FROM gateway_amount_fit")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| ((r.get("gateway_id"), r.get("amount_bucket")), r.get("score")))
+            .collect())
+    }
+
+    pub async fn all_time_penalties(&self) -> Result<Vec<TimePenalty>> {
+        let rows = sqlx::query("SELECT gateway_id, hour_of_day, day_of_month, multiplier, timezone FROM gateway_time_penalty")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| TimePenalty {
+                gateway_id: r.get("gateway_id"),
+                hour_of_day: r.get("hour_of_day"),
+                day_of_month: r.get("day_of_month"),
+                multiplier: r.get("multiplier"),
+                timezone: parse_timezone(&r.get::<String, _>("timezone")).unwrap_or(Tz::UTC),
+            })
+            .collect())
+    }
+
+    pub async fn bin_bank_map(&self) -> Result<HashMap<String, String>> {
+        let rows = sqlx::query("SELECT bin_prefix, bank_code FROM bin_bank_map")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|r| (r.get("bin_prefix"), r.get("bank_code")))
+            .collect())
+    }
+}
+
+fn map_fee_slab(row: &sqlx::postgres::PgRow) -> FeeSlab {
+    FeeSlab {
+        gateway_id: row.get("gateway_id"),
+        payment_method: row.get("payment_method"),
+        card_network: row.get("card_network"),
+        card_type: row.get("card_type"),
+        min_amount_minor: row.get("min_amount_minor"),
+        max_amount_minor: row.get("max_amount_minor"),
+        pct_fee: row.get("pct_fee"),
+        fixed_fee_minor: row.get("fixed_fee_minor"),
+        gst_pct: row.get("gst_pct"),
+    }
 }
@@ -0,0 +1,272 @@
+use crate::scoring::blend::SignalBlend;
+use crate::scoring::profiles::{
+    select_assignment, ProfileAssignment, ResolvedProfile, ScoringProfileVersion, DEFAULT_PROFILE_ID,
+};
+use crate::scoring::types::ScoreWeights;
+use anyhow::Result;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ScoringProfilesRepo {
+    pub pool: PgPool,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ScoringProfileView {
+    pub profile_id: String,
+    pub description: String,
+    pub current_version: i32,
+    pub weights: ScoreWeights,
+    pub signal_blend: SignalBlend,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct NewProfileVersion {
+    pub weights: ScoreWeights,
+    #[serde(default)]
+    pub signal_blend: Option<SignalBlend>,
+    pub author: String,
+    pub change_note: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct AssignmentInput {
+    pub merchant_id: Option<String>,
+    pub payment_method: Option<String>,
+    pub amount_bucket: Option<String>,
+    pub profile_id: String,
+}
+
+impl ScoringProfilesRepo {
+    pub async fn list(&self) -> Result<Vec<ScoringProfileView>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.profile_id, p.description, p.current_version, p.updated_at, v.weights, v.signal_blend
+            FROM scoring_profiles p
+            JOIN scoring_profile_versions v ON v.profile_id = p.profile_id AND v.version = p.current_version
+            ORDER BY p.profile_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|r| {
+                Ok(ScoringProfileView {
+                    profile_id: r.get("profile_id"),
+                    description: r.get("description"),
+                    current_version: r.get("current_version"),
+                    weights: serde_json::from_value(r.get("weights"))?,
+                    signal_blend: parse_blend(r.get("signal_blend"))?,
+                    updated_at: r.get("updated_at"),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn versions(&self, profile_id: &str) -> Result<Vec<ScoringProfileVersion>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT profile_id, version, weights, signal_blend, author, change_note, created_at
+            FROM scoring_profile_versions
+            WHERE profile_id=$1
+            ORDER BY version DESC
+            "#,
+        )
+        .bind(profile_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter()
+            .map(|r| {
+                Ok(ScoringProfileVersion {
+                    profile_id: r.get("profile_id"),
+                    version: r.get("version"),
+                    weights: serde_json::from_value(r.get("weights"))?,
+                    signal_blend: parse_blend(r.get("signal_blend"))?,
+                    author: r.get("author"),
+                    change_note: r.get("change_note"),
+                    created_at: r.get("created_at"),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn create_version(&self, profile_id: &str, input: NewProfileVersion) -> Result<ScoringProfileVersion> {
+        let mut tx = self.pool.begin().await?;
+        let current: Option<i32> = sqlx::query("SELECT current_version FROM scoring_profiles WHERE profile_id=$1 FOR UPDATE")
+            .bind(profile_id)
+            .fetch_optional(tx.as_mut())
+            .await?
+            .map(|r| r.get("current_version"));
+        let version = current.unwrap_or(0) + 1;
+        let signal_blend = input.signal_blend.clone().unwrap_or_default();
+
+        if current.is_none() {
+            sqlx::query("INSERT INTO scoring_profiles (profile_id, description, current_version) VALUES ($1,$2,$3)")
+                .bind(profile_id)
+                .bind(input.description.clone().unwrap_or_default())
+                .bind(version)
+                .execute(tx.as_mut())
+                .await?;
+        }
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO scoring_profile_versions (profile_id, version, weights, signal_blend, author, change_note)
+            VALUES ($1,$2,$3,$4,$5,$6)
+            RETURNING created_at
+            "#,
+        )
+        .bind(profile_id)
+        .bind(version)
+        .bind(serde_json::to_value(&input.weights)?)
+        .bind(serde_json::to_value(&signal_blend)?)
+        .bind(&input.author)
+        .bind(&input.change_note)
+        .fetch_one(tx.as_mut())
+        .await?;
+
+        if current.is_some() {
+            sqlx::query(
+                "UPDATE scoring_profiles SET current_version=$2, description=COALESCE($3, description), updated_at=now() WHERE profile_id=$1",
+            )
+            .bind(profile_id)
+            .bind(version)
+            .bind(&input.description)
+            .execute(tx.as_mut())
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(ScoringProfileVersion {
+            profile_id: profile_id.to_string(),
+            version,
+            weights: input.weights,
+            signal_blend,
+            author: input.author,
+            change_note: input.change_note,
+            created_at: row.get("created_at"),
+        })
+    }
+
+    pub async fn list_assignments(&self) -> Result<Vec<ProfileAssignment>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT assignment_id, merchant_id, payment_method, amount_bucket, profile_id
+            FROM scoring_profile_assignments
+            ORDER BY created_at ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| ProfileAssignment {
+                assignment_id: r.get("assignment_id"),
+                merchant_id: r.get("merchant_id"),
+                payment_method: r.get("payment_method"),
+                amount_bucket: r.get("amount_bucket"),
+                profile_id: r.get("profile_id"),
+            })
+            .collect())
+    }
+
+    pub async fn upsert_assignment(&self, input: AssignmentInput) -> Result<ProfileAssignment> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO scoring_profile_assignments (assignment_id, merchant_id, payment_method, amount_bucket, profile_id)
+            VALUES ($1,$2,$3,$4,$5)
+            ON CONFLICT (COALESCE(merchant_id, ''), COALESCE(payment_method, ''), COALESCE(amount_bucket, ''))
+            DO UPDATE SET profile_id=EXCLUDED.profile_id
+            RETURNING assignment_id
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(&input.merchant_id)
+        .bind(input.payment_method.as_ref().map(|m| m.to_uppercase()))
+        .bind(&input.amount_bucket)
+        .bind(&input.profile_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(ProfileAssignment {
+            assignment_id: row.get("assignment_id"),
+            merchant_id: input.merchant_id,
+            payment_method: input.payment_method.map(|m| m.to_uppercase()),
+            amount_bucket: input.amount_bucket,
+            profile_id: input.profile_id,
+        })
+    }
+
+    pub async fn delete_assignment(&self, assignment_id: Uuid) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM scoring_profile_assignments WHERE assignment_id=$1")
+            .bind(assignment_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn current(&self, profile_id: &str) -> Result<Option<ResolvedProfile>> {
+        let row = sqlx::query(
+            r#"
+            SELECT p.profile_id, p.current_version, v.weights, v.signal_blend
+            FROM scoring_profiles p
+            JOIN scoring_profile_versions v ON v.profile_id = p.profile_id AND v.version = p.current_version
+            WHERE p.profile_id=$1
+            "#,
+        )
+        .bind(profile_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(|r| {
+            Ok(ResolvedProfile {
+                profile_id: r.get("profile_id"),
+                version: r.get("current_version"),
+                weights: serde_json::from_value(r.get("weights"))?,
+                signal_blend: parse_blend(r.get("signal_blend"))?,
+            })
+        })
+        .transpose()
+    }
+
+    pub async fn current_all(&self) -> Result<Vec<ResolvedProfile>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.profile_id, p.current_version, v.weights, v.signal_blend
+            FROM scoring_profiles p
+            JOIN scoring_profile_versions v ON v.profile_id = p.profile_id AND v.version = p.current_version
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|r| {
+                Ok(ResolvedProfile {
+                    profile_id: r.get("profile_id"),
+                    version: r.get("current_version"),
+                    weights: serde_json::from_value(r.get("weights"))?,
+                    signal_blend: parse_blend(r.get("signal_blend"))?,
+                })
+            })
+            .collect()
+    }
+
+    pub async fn resolve(&self, merchant_id: &str, payment_method: &str, amount_bucket: &str) -> Result<Option<ResolvedProfile>> {
+        let assignments = self.list_assignments().await?;
+        let profile_id = select_assignment(&assignments, merchant_id, payment_method, amount_bucket)
+            .map(|a| a.profile_id.clone())
+            .unwrap_or_else(|| DEFAULT_PROFILE_ID.to_string());
+        self.current(&profile_id).await
+    }
+}
+
+fn parse_blend(value: Option<serde_json::Value>) -> Result<SignalBlend> {
+    Ok(value.map(serde_json::from_value).transpose()?.unwrap_or_default())
+}
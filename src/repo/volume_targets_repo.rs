@@ -0,0 +1,100 @@
+use crate::volume::pacing::{VolumeTarget, VolumeUsage};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct VolumeTargetsRepo {
+    pub pool: PgPool,
+}
+
+impl VolumeTargetsRepo {
+    pub async fn list(&self) -> Result<Vec<VolumeTarget>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT gateway_id, monthly_commit_minor, daily_cap_minor, monthly_cap_minor, pace_boost, enabled
+            FROM gateway_volume_targets
+            ORDER BY gateway_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| VolumeTarget {
+                gateway_id: row.get("gateway_id"),
+                monthly_commit_minor: row.get("monthly_commit_minor"),
+                daily_cap_minor: row.get("daily_cap_minor"),
+                monthly_cap_minor: row.get("monthly_cap_minor"),
+                pace_boost: row.get("pace_boost"),
+                enabled: row.get("enabled"),
+            })
+            .collect())
+    }
+
+    pub async fn list_enabled(&self) -> Result<Vec<VolumeTarget>> {
+        Ok(self.list().await?.into_iter().filter(|t| t.enabled).collect())
+    }
+
+    pub async fn upsert(&self, target: VolumeTarget) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO gateway_volume_targets (
+                gateway_id, monthly_commit_minor, daily_cap_minor, monthly_cap_minor, pace_boost, enabled, updated_at
+            ) VALUES ($1,$2,$3,$4,$5,$6,now())
+            ON CONFLICT (gateway_id) DO UPDATE SET
+                monthly_commit_minor=EXCLUDED.monthly_commit_minor,
+                daily_cap_minor=EXCLUDED.daily_cap_minor,
+                monthly_cap_minor=EXCLUDED.monthly_cap_minor,
+                pace_boost=EXCLUDED.pace_boost,
+                enabled=EXCLUDED.enabled,
+                updated_at=now()
+            "#,
+        )
+        .bind(target.gateway_id)
+        .bind(target.monthly_commit_minor)
+        .bind(target.daily_cap_minor)
+        .bind(target.monthly_cap_minor)
+        .bind(target.pace_boost)
+        .bind(target.enabled)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn settled_volume(
+        &self,
+        day_start: DateTime<Utc>,
+        month_start: DateTime<Utc>,
+    ) -> Result<HashMap<String, VolumeUsage>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT gateway_used,
+                   COALESCE(SUM(amount_minor) FILTER (WHERE created_at >= $1), 0)::BIGINT AS daily_minor,
+                   COALESCE(SUM(amount_minor), 0)::BIGINT AS monthly_minor
+            FROM payments
+            WHERE status='SUCCESS' AND created_at >= $2
+            GROUP BY gateway_used
+            "#,
+        )
+        .bind(day_start)
+        .bind(month_start)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.get("gateway_used"),
+                    VolumeUsage {
+                        daily_minor: row.get("daily_minor"),
+                        monthly_minor: row.get("monthly_minor"),
+                    },
+                )
+            })
+            .collect())
+    }
+}
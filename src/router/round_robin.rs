@@ -5,6 +5,12 @@ pub struct RoundRobinRouter {
     counter: AtomicUsize,
 }
 
+impl Default for RoundRobinRouter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl RoundRobinRouter {
     pub fn new() -> Self {
         Self {
@@ -40,6 +46,8 @@ mod tests {
                 supported_methods: vec!["UPI".to_string()],
                 timeout_ms: 1000,
                 mock_behavior: None,
+                max_in_flight: None,
+                max_tps: None,
             },
             GatewayConfig {
                 gateway_id: "g2".to_string(),
@@ -50,6 +58,8 @@ mod tests {
                 supported_methods: vec!["UPI".to_string()],
                 timeout_ms: 1000,
                 mock_behavior: None,
+                max_in_flight: None,
+                max_tps: None,
             },
         ];
 
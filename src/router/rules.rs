@@ -0,0 +1,220 @@
+use crate::bins::index::validate_card_filters;
+use crate::domain::routing_rule::{RoutingRule, RuleAction, RuleConditions};
+use crate::scoring::types::RankedGateway;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+#[derive(Debug, Clone)]
+pub struct RuleInput {
+    pub payment_method: String,
+    pub merchant_id: String,
+    pub issuing_bank: String,
+    pub amount_minor: i64,
+    pub amount_bucket: String,
+    pub card_network: Option<String>,
+    pub card_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleOutcome {
+    pub forced_gateway: Option<String>,
+    pub forced_rule_id: Option<Uuid>,
+    pub excluded: HashSet<String>,
+    pub boosts: HashMap<String, f64>,
+    pub matched_rule_ids: Vec<Uuid>,
+    pub rule_gateways: Vec<(Uuid, String)>,
+}
+
+impl RuleOutcome {
+    pub fn primary_rule_id(&self, available: &[String]) -> Option<Uuid> {
+        self.forced_rule_id.or_else(|| {
+            self.rule_gateways
+                .iter()
+                .find(|(_, gateway_id)| available.contains(gateway_id))
+                .map(|(rule_id, _)| *rule_id)
+        })
+    }
+
+    pub fn allows(&self, gateway_id: &str) -> bool {
+        if self.excluded.contains(gateway_id) {
+            return false;
+        }
+        match &self.forced_gateway {
+            Some(forced) => forced == gateway_id,
+            None => true,
+        }
+    }
+}
+
+pub fn conditions_match(conditions: &RuleConditions, input: &RuleInput) -> bool {
+    if let Some(method) = &conditions.payment_method {
+        if !method.eq_ignore_ascii_case(&input.payment_method) {
+            return false;
+        }
+    }
+
+    if let Some(merchant) = &conditions.merchant_id {
+        if merchant != &input.merchant_id {
+            return false;
+        }
+    }
+
+    if let Some(bank) = &conditions.issuing_bank {
+        if !bank.eq_ignore_ascii_case(&input.issuing_bank) {
+            return false;
+        }
+    }
+
+    if let Some(min) = conditions.min_amount_minor {
+        if input.amount_minor < min {
+            return false;
+        }
+    }
+
+    if let Some(max) = conditions.max_amount_minor {
+        if input.amount_minor > max {
+            return false;
+        }
+    }
+
+    if let Some(bucket) = &conditions.amount_bucket {
+        if bucket != &input.amount_bucket {
+            return false;
+        }
+    }
+
+    if let Some(network) = &conditions.card_network {
+        if !input.card_network.as_deref().is_some_and(|n| n.eq_ignore_ascii_case(network)) {
+            return false;
+        }
+    }
+
+    if let Some(card_type) = &conditions.card_type {
+        if !input.card_type.as_deref().is_some_and(|t| t.eq_ignore_ascii_case(card_type)) {
+            return false;
+        }
+    }
+
+    true
+}
+
+pub fn evaluate(rules: &[RoutingRule], input: &RuleInput) -> RuleOutcome {
+    let mut ordered: Vec<&RoutingRule> = rules.iter().filter(|r| r.enabled).collect();
+    ordered.sort_by(|a, b| a.priority.cmp(&b.priority).then(a.created_at.cmp(&b.created_at)));
+
+    let mut outcome = RuleOutcome::default();
+    for rule in ordered {
+        if !conditions_match(&rule.conditions, input) {
+            continue;
+        }
+        outcome.matched_rule_ids.push(rule.rule_id);
+        match rule.action {
+            RuleAction::Force => {
+                outcome.forced_gateway = Some(rule.gateway_id.clone());
+                outcome.forced_rule_id = Some(rule.rule_id);
+                break;
+            }
+            RuleAction::Exclude => {
+                outcome.excluded.insert(rule.gateway_id.clone());
+            }
+            RuleAction::Boost => {
+                *outcome.boosts.entry(rule.gateway_id.clone()).or_insert(0.0) += rule.boost.unwrap_or(0.0);
+            }
+        }
+        outcome.rule_gateways.push((rule.rule_id, rule.gateway_id.clone()));
+    }
+    outcome
+}
+
+pub fn layer_production_excludes(mut outcome: RuleOutcome, production: &[RoutingRule], input: &RuleInput) -> RuleOutcome {
+    let excludes: Vec<RoutingRule> = production
+        .iter()
+        .filter(|r| r.action == RuleAction::Exclude)
+        .cloned()
+        .collect();
+    let production = evaluate(&excludes, input);
+    outcome.excluded.extend(production.excluded);
+    for effect in production.rule_gateways {
+        if !outcome.rule_gateways.contains(&effect) {
+            outcome.rule_gateways.push(effect);
+        }
+    }
+    for rule_id in production.matched_rule_ids {
+        if !outcome.matched_rule_ids.contains(&rule_id) {
+            outcome.matched_rule_ids.push(rule_id);
+        }
+    }
+    outcome
+}
+
+pub fn resolve_force(
+    rules: &[RoutingRule],
+    evaluate: impl Fn(&[RoutingRule]) -> RuleOutcome,
+    is_usable: impl Fn(&str) -> bool,
+) -> (RuleOutcome, Vec<String>) {
+    let mut remaining: Vec<RoutingRule> = rules.to_vec();
+    let mut outcome = evaluate(&remaining);
+    let mut skipped_rule_ids = Vec::new();
+    let mut unavailable = Vec::new();
+    while let (Some(forced), Some(rule_id)) = (outcome.forced_gateway.clone(), outcome.forced_rule_id) {
+        if !outcome.excluded.contains(&forced) && is_usable(&forced) {
+            break;
+        }
+        remaining.retain(|r| r.rule_id != rule_id);
+        skipped_rule_ids.push(rule_id);
+        if !unavailable.contains(&forced) {
+            unavailable.push(forced);
+        }
+        outcome = evaluate(&remaining);
+    }
+    for rule_id in skipped_rule_ids.into_iter().rev() {
+        if !outcome.matched_rule_ids.contains(&rule_id) {
+            outcome.matched_rule_ids.insert(0, rule_id);
+        }
+    }
+    (outcome, unavailable)
+}
+
+pub fn apply_boosts(mut ranked: Vec<RankedGateway>, boosts: &HashMap<String, f64>) -> Vec<RankedGateway> {
+    if boosts.is_empty() {
+        return ranked;
+    }
+    for r in &mut ranked {
+        if let Some(boost) = boosts.get(&r.gateway_id) {
+            r.score = (r.score + boost).clamp(0.0, 1.0);
+        }
+    }
+    ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+pub fn validate_rule(
+    action: RuleAction,
+    gateway_id: &str,
+    boost: Option<f64>,
+    conditions: &RuleConditions,
+    known_gateways: &[String],
+) -> Result<(), String> {
+    if gateway_id.trim().is_empty() {
+        return Err("gateway_id is required".to_string());
+    }
+    if !known_gateways.iter().any(|g| g == gateway_id) {
+        return Err(format!("unknown gateway {}", gateway_id));
+    }
+    match (action, boost) {
+        (RuleAction::Boost, None) => return Err("boost is required for BOOST rules".to_string()),
+        (RuleAction::Boost, Some(b)) if !(-1.0..=1.0).contains(&b) => {
+            return Err("boost must be between -1.0 and 1.0".to_string())
+        }
+        (RuleAction::Force | RuleAction::Exclude, Some(_)) => {
+            return Err("boost is only allowed for BOOST rules".to_string())
+        }
+        _ => {}
+    }
+    if let (Some(min), Some(max)) = (conditions.min_amount_minor, conditions.max_amount_minor) {
+        if min > max {
+            return Err("min_amount_minor must be <= max_amount_minor".to_string());
+        }
+    }
+    validate_card_filters(conditions.card_network.as_deref(), conditions.card_type.as_deref())
+}
@@ -0,0 +1,347 @@
+use crate::gateways::GatewayConfig;
+use crate::metrics::aggregator::ROLLUP_ALL;
+use crate::router::round_robin::RoundRobinRouter;
+use crate::scoring::blend::{blend_windows, BlendedSignal, SignalBlend};
+use crate::scoring::cost::{cost_score, expected_fee_minor};
+use crate::scoring::engine::rank_gateways;
+use crate::scoring::hierarchy::{smooth, LevelStats};
+use crate::scoring::schedule::{apply_penalties, MaintenanceMode};
+use crate::scoring::types::{GatewayCandidate, ScoreInputs, ScoreWeights};
+use crate::service::routing_config::RoutingConfigSnapshot;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+const MAX_SNAPSHOT_STALENESS_MINUTES: i64 = 10;
+const OUTCOME_WINDOW_MINUTES: i64 = 5;
+
+#[derive(Debug, Clone)]
+pub struct MetricSnapshot {
+    pub snapshot_minute: DateTime<Utc>,
+    pub window_minutes: i64,
+    pub total_requests: u64,
+    pub success_rate: f64,
+    pub avg_latency_ms: i32,
+    pub p95_latency_ms: i32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MetricHistory {
+    by_key: HashMap<(String, String, String, i64), Vec<MetricSnapshot>>,
+}
+
+impl MetricHistory {
+    pub fn insert(&mut self, gateway: &str, method: &str, bank: &str, snapshot: MetricSnapshot) {
+        let entries = self
+            .by_key
+            .entry((
+                gateway.to_lowercase(),
+                method.to_lowercase(),
+                bank.to_lowercase(),
+                snapshot.window_minutes,
+            ))
+            .or_default();
+        let idx = entries.partition_point(|s| s.snapshot_minute <= snapshot.snapshot_minute);
+        entries.insert(idx, snapshot);
+    }
+
+    pub fn at(&self, gateway: &str, method: &str, bank: &str, window_minutes: i64, ts: DateTime<Utc>) -> Option<&MetricSnapshot> {
+        let entries = self.by_key.get(&(
+            gateway.to_lowercase(),
+            method.to_lowercase(),
+            bank.to_lowercase(),
+            window_minutes,
+        ))?;
+        let idx = entries.partition_point(|s| s.snapshot_minute <= ts);
+        let snapshot = entries.get(idx.checked_sub(1)?)?;
+        if ts - snapshot.snapshot_minute > chrono::Duration::minutes(MAX_SNAPSHOT_STALENESS_MINUTES) {
+            return None;
+        }
+        Some(snapshot)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AttemptOutcome {
+    pub gateway_id: String,
+    pub success: bool,
+    pub latency_ms: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct BacktestCase {
+    pub payment_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub merchant_id: String,
+    pub method: String,
+    pub issuing_bank: String,
+    pub amount_minor: i64,
+    pub amount_bucket: String,
+    pub card_network: Option<String>,
+    pub card_type: Option<String>,
+    pub attempts: Vec<AttemptOutcome>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BacktestStrategy {
+    #[default]
+    Scoring,
+    Priority,
+    RoundRobin,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestScenario {
+    pub name: String,
+    #[serde(default)]
+    pub strategy: BacktestStrategy,
+    #[serde(default)]
+    pub weights: Option<ScoreWeights>,
+    #[serde(default)]
+    pub method_affinity: HashMap<String, HashMap<String, f64>>,
+    #[serde(default)]
+    pub amount_fit: HashMap<String, HashMap<String, f64>>,
+}
+
+impl BacktestScenario {
+    pub fn baseline() -> Self {
+        Self {
+            name: "baseline".to_string(),
+            strategy: BacktestStrategy::Scoring,
+            weights: None,
+            method_affinity: HashMap::new(),
+            amount_fit: HashMap::new(),
+        }
+    }
+
+    fn method_affinity(&self, config: &RoutingConfigSnapshot, gateway_id: &str, method: &str) -> f64 {
+        self.method_affinity
+            .get(gateway_id)
+            .and_then(|by_method| by_method.iter().find(|(m, _)| m.eq_ignore_ascii_case(method)))
+            .map(|(_, score)| *score)
+            .unwrap_or_else(|| config.method_affinity(gateway_id, method))
+    }
+
+    fn amount_fit(&self, config: &RoutingConfigSnapshot, gateway_id: &str, amount_bucket: &str) -> f64 {
+        self.amount_fit
+            .get(gateway_id)
+            .and_then(|by_bucket| by_bucket.get(amount_bucket))
+            .copied()
+            .unwrap_or_else(|| config.amount_fit(gateway_id, amount_bucket))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub strategy: BacktestStrategy,
+    pub cases: usize,
+    pub evaluated: usize,
+    pub no_evidence: usize,
+    pub top_choice_changed: usize,
+    pub top_choice_change_rate: f64,
+    pub differs_from_actual: usize,
+    pub baseline_success_rate: f64,
+    pub scenario_success_rate: f64,
+    pub success_rate_delta: f64,
+    pub baseline_avg_latency_ms: f64,
+    pub scenario_avg_latency_ms: f64,
+    pub latency_delta_ms: f64,
+    pub baseline_avg_fee_minor: f64,
+    pub scenario_avg_fee_minor: f64,
+    pub fee_delta_minor: f64,
+    pub selection_share: BTreeMap<String, f64>,
+}
+
+pub struct Backtester<'a> {
+    pub config: &'a RoutingConfigSnapshot,
+    pub metrics: &'a MetricHistory,
+}
+
+impl Backtester<'_> {
+    pub fn expected_fee(&self, gateway_id: &str, case: &BacktestCase) -> Option<i64> {
+        expected_fee_minor(
+            self.config.fee_slabs(gateway_id, &case.method),
+            &case.method,
+            case.card_network.as_deref(),
+            case.card_type.as_deref(),
+            case.amount_minor,
+        )
+    }
+
+    pub fn signal(&self, gateway_id: &str, case: &BacktestCase, blend: &SignalBlend) -> BlendedSignal {
+        let stats = |method: &str, bank: &str, window_minutes: i64| {
+            self.metrics
+                .at(gateway_id, method, bank, window_minutes, case.created_at)
+                .map(|m| LevelStats {
+                    success_rate: m.success_rate,
+                    p95_latency_ms: m.p95_latency_ms,
+                    total_requests: m.total_requests,
+                })
+        };
+        let per_window: Vec<_> = blend
+            .window_minutes()
+            .into_iter()
+            .map(|window| {
+                (
+                    window,
+                    smooth(
+                        stats(&case.method, &case.issuing_bank, window),
+                        stats(&case.method, ROLLUP_ALL, window),
+                        stats(ROLLUP_ALL, ROLLUP_ALL, window),
+                    ),
+                )
+            })
+            .collect();
+        blend_windows(blend, &per_window)
+    }
+
+    pub fn top_choice(&self, case: &BacktestCase, scenario: &BacktestScenario, router: &RoundRobinRouter) -> Option<String> {
+        let config = self.config;
+        let eligible: Vec<GatewayConfig> = config
+            .gateways
+            .iter()
+            .filter(|g| g.is_enabled && g.supported_methods.iter().any(|m| m.eq_ignore_ascii_case(&case.method)))
+            .filter(|g| {
+                config
+                    .maintenance_window(&g.gateway_id, &case.method, &case.issuing_bank, case.created_at)
+                    .is_none_or(|w| w.mode != MaintenanceMode::Exclude)
+            })
+            .cloned()
+            .collect();
+
+        match scenario.strategy {
+            BacktestStrategy::Priority => eligible
+                .iter()
+                .min_by_key(|g| g.priority)
+                .map(|g| g.gateway_id.clone()),
+            BacktestStrategy::RoundRobin => router.select(&eligible).map(|(g, _)| g.gateway_id),
+            BacktestStrategy::Scoring => {
+                let profile = config.resolve_profile(&case.merchant_id, &case.method, &case.amount_bucket);
+                let weights = scenario
+                    .weights
+                    .clone()
+                    .unwrap_or_else(|| config.weights_for(profile.as_ref()));
+                let blend = config.blend_for(profile.as_ref());
+                let mut penalties = HashMap::new();
+                let candidates: Vec<GatewayCandidate> = eligible
+                    .into_iter()
+                    .map(|gateway| {
+                        let signal = self.signal(&gateway.gateway_id, case, &blend);
+                        if let Some(window) = config
+                            .maintenance_window(&gateway.gateway_id, &case.method, &case.issuing_bank, case.created_at)
+                            .filter(|w| w.mode == MaintenanceMode::Penalize)
+                        {
+                            penalties.insert(gateway.gateway_id.clone(), window.penalty_multiplier);
+                        }
+                        let inputs = ScoreInputs {
+                            success_rate: signal.success_rate,
+                            p95_latency_ms: signal.p95_latency_ms,
+                            method_affinity: scenario.method_affinity(config, &gateway.gateway_id, &case.method),
+                            bank_affinity: config.issuer_affinity(&gateway.gateway_id, &case.issuing_bank),
+                            amount_fit: scenario.amount_fit(config, &gateway.gateway_id, &case.amount_bucket),
+                            time_multiplier: config.time_multiplier(&gateway.gateway_id, case.created_at),
+                            cost_score: cost_score(self.expected_fee(&gateway.gateway_id, case), case.amount_minor),
+                            metric_level: signal.level,
+                            signal_windows: signal.windows,
+                        };
+                        GatewayCandidate { gateway, inputs }
+                    })
+                    .collect();
+                apply_penalties(rank_gateways(&candidates, &weights), &penalties)
+                    .into_iter()
+                    .next()
+                    .map(|r| r.gateway_id)
+            }
+        }
+    }
+
+    pub fn estimate_outcome(&self, case: &BacktestCase, gateway_id: &str) -> Option<(f64, f64)> {
+        if let Some(attempt) = case.attempts.iter().find(|a| a.gateway_id == gateway_id) {
+            return Some((if attempt.success { 1.0 } else { 0.0 }, attempt.latency_ms as f64));
+        }
+        self.metrics
+            .at(gateway_id, &case.method, &case.issuing_bank, OUTCOME_WINDOW_MINUTES, case.created_at)
+            .map(|m| (m.success_rate, m.avg_latency_ms as f64))
+    }
+
+    pub fn run(&self, cases: &[BacktestCase], scenario: &BacktestScenario) -> ScenarioReport {
+        let baseline = BacktestScenario::baseline();
+        let baseline_router = RoundRobinRouter::new();
+        let scenario_router = RoundRobinRouter::new();
+
+        let mut evaluated = 0_usize;
+        let mut no_evidence = 0_usize;
+        let mut changed = 0_usize;
+        let mut differs_from_actual = 0_usize;
+        let mut base_success = 0.0;
+        let mut scen_success = 0.0;
+        let mut base_latency = 0.0;
+        let mut scen_latency = 0.0;
+        let mut base_fee = 0.0;
+        let mut scen_fee = 0.0;
+        let mut selections: BTreeMap<String, usize> = BTreeMap::new();
+
+        for case in cases {
+            let (Some(base_top), Some(scen_top)) = (
+                self.top_choice(case, &baseline, &baseline_router),
+                self.top_choice(case, scenario, &scenario_router),
+            ) else {
+                no_evidence += 1;
+                continue;
+            };
+
+            if base_top != scen_top {
+                changed += 1;
+            }
+            if case.attempts.first().is_some_and(|a| a.gateway_id != scen_top) {
+                differs_from_actual += 1;
+            }
+            *selections.entry(scen_top.clone()).or_insert(0) += 1;
+
+            let (Some((bs, bl)), Some((ss, sl))) = (
+                self.estimate_outcome(case, &base_top),
+                self.estimate_outcome(case, &scen_top),
+            ) else {
+                no_evidence += 1;
+                continue;
+            };
+            evaluated += 1;
+            base_success += bs;
+            scen_success += ss;
+            base_latency += bl;
+            scen_latency += sl;
+            base_fee += self.expected_fee(&base_top, case).unwrap_or(0) as f64;
+            scen_fee += self.expected_fee(&scen_top, case).unwrap_or(0) as f64;
+        }
+
+        let decided: usize = selections.values().sum();
+        let mean = |sum: f64| if evaluated > 0 { sum / evaluated as f64 } else { 0.0 };
+
+        ScenarioReport {
+            name: scenario.name.clone(),
+            strategy: scenario.strategy,
+            cases: cases.len(),
+            evaluated,
+            no_evidence,
+            top_choice_changed: changed,
+            top_choice_change_rate: if decided > 0 { changed as f64 / decided as f64 } else { 0.0 },
+            differs_from_actual,
+            baseline_success_rate: mean(base_success),
+            scenario_success_rate: mean(scen_success),
+            success_rate_delta: mean(scen_success) - mean(base_success),
+            baseline_avg_latency_ms: mean(base_latency),
+            scenario_avg_latency_ms: mean(scen_latency),
+            latency_delta_ms: mean(scen_latency) - mean(base_latency),
+            baseline_avg_fee_minor: mean(base_fee),
+            scenario_avg_fee_minor: mean(scen_fee),
+            fee_delta_minor: mean(scen_fee) - mean(base_fee),
+            selection_share: selections
+                .into_iter()
+                .map(|(g, n)| (g, n as f64 / decided.max(1) as f64))
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::scoring::hierarchy::{MetricLevel, SmoothedMetric};
+use serde::{Deserialize, Serialize};
+
+pub const SUPPORTED_WINDOWS: [i64; 4] = [1, 5, 15, 60];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowWeight {
+    pub window_minutes: i64,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalBlend {
+    pub windows: Vec<WindowWeight>,
+}
+
+impl Default for SignalBlend {
+    fn default() -> Self {
+        Self {
+            windows: vec![WindowWeight { window_minutes: 5, weight: 1.0 }],
+        }
+    }
+}
+
+impl SignalBlend {
+    pub fn ewma(alpha: f64) -> Self {
+        let alpha = alpha.clamp(0.01, 1.0);
+        let raw: Vec<f64> = (0..SUPPORTED_WINDOWS.len())
+            .map(|i| alpha * (1.0 - alpha).powi(i as i32))
+            .collect();
+        let total: f64 = raw.iter().sum();
+        Self {
+            windows: SUPPORTED_WINDOWS
+                .iter()
+                .zip(raw)
+                .map(|(window_minutes, w)| WindowWeight {
+                    window_minutes: *window_minutes,
+                    weight: w / total,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn window_minutes(&self) -> Vec<i64> {
+        self.windows.iter().map(|w| w.window_minutes).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowContribution {
+    pub window_minutes: i64,
+    pub weight: f64,
+    pub success_rate: f64,
+    pub p95_latency_ms: i32,
+    pub level: MetricLevel,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlendedSignal {
+    pub success_rate: f64,
+    pub p95_latency_ms: i32,
+    pub level: MetricLevel,
+    pub windows: Vec<WindowContribution>,
+}
+
+pub fn validate_blend(blend: &SignalBlend) -> Result<(), String> {
+    if blend.windows.is_empty() {
+        return Err("signal_blend.windows must not be empty".to_string());
+    }
+    let mut seen = Vec::new();
+    for w in &blend.windows {
+        if !SUPPORTED_WINDOWS.contains(&w.window_minutes) {
+            return Err(format!(
+                "window_minutes {} is not one of {:?}",
+                w.window_minutes, SUPPORTED_WINDOWS
+            ));
+        }
+        if seen.contains(&w.window_minutes) {
+            return Err(format!("window_minutes {} listed twice", w.window_minutes));
+        }
+        seen.push(w.window_minutes);
+        if !w.weight.is_finite() || w.weight < 0.0 {
+            return Err(format!("weight for {}m window must be a non-negative number", w.window_minutes));
+        }
+    }
+    if blend.windows.iter().map(|w| w.weight).sum::<f64>() <= 0.0 {
+        return Err("signal_blend weights must not all be zero".to_string());
+    }
+    Ok(())
+}
+
+pub fn blend_windows(blend: &SignalBlend, per_window: &[(i64, SmoothedMetric)]) -> BlendedSignal {
+    let observed: Vec<(WindowWeight, SmoothedMetric)> = blend
+        .windows
+        .iter()
+        .filter_map(|w| {
+            per_window
+                .iter()
+                .find(|(minutes, _)| *minutes == w.window_minutes)
+                .map(|(_, metric)| (*w, *metric))
+        })
+        .collect();
+    let total_weight: f64 = observed
+        .iter()
+        .filter(|(_, m)| m.level != MetricLevel::GlobalPrior)
+        .map(|(w, _)| w.weight)
+        .sum();
+
+    let windows: Vec<WindowContribution> = observed
+        .iter()
+        .map(|(w, m)| WindowContribution {
+            window_minutes: w.window_minutes,
+            weight: if total_weight > 0.0 && m.level != MetricLevel::GlobalPrior {
+                w.weight / total_weight
+            } else {
+                0.0
+            },
+            success_rate: m.success_rate,
+            p95_latency_ms: m.p95_latency_ms,
+            level: m.level,
+        })
+        .collect();
+
+    if total_weight <= 0.0 {
+        let prior = SmoothedMetric::default();
+        return BlendedSignal {
+            success_rate: prior.success_rate,
+            p95_latency_ms: prior.p95_latency_ms,
+            level: MetricLevel::GlobalPrior,
+            windows,
+        };
+    }
+
+    let success_rate = windows.iter().map(|w| w.weight * w.success_rate).sum();
+    let p95: f64 = windows.iter().map(|w| w.weight * w.p95_latency_ms as f64).sum();
+    let level = windows
+        .iter()
+        .filter(|w| w.weight > 0.0)
+        .map(|w| w.level)
+        .min()
+        .unwrap_or(MetricLevel::GlobalPrior);
+
+    BlendedSignal {
+        success_rate,
+        p95_latency_ms: p95.round() as i32,
+        level,
+        windows,
+    }
+}
@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+pub const ANY_NETWORK: &str = "ANY";
+pub const ANY_CARD_TYPE: &str = "ANY";
+const REFERENCE_FEE_RATE: f64 = 0.03;
+const UNKNOWN_FEE_SCORE: f64 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeSlab {
+    pub gateway_id: String,
+    pub payment_method: String,
+    pub card_network: String,
+    #[serde(default = "any_card_type")]
+    pub card_type: String,
+    pub min_amount_minor: i64,
+    pub max_amount_minor: Option<i64>,
+    pub pct_fee: f64,
+    pub fixed_fee_minor: i64,
+    pub gst_pct: f64,
+}
+
+fn any_card_type() -> String {
+    ANY_CARD_TYPE.to_string()
+}
+
+impl FeeSlab {
+    pub fn covers(&self, amount_minor: i64) -> bool {
+        amount_minor >= self.min_amount_minor && self.max_amount_minor.is_none_or(|max| amount_minor <= max)
+    }
+
+    pub fn fee_minor(&self, amount_minor: i64) -> i64 {
+        let base = amount_minor as f64 * self.pct_fee / 100.0 + self.fixed_fee_minor as f64;
+        (base * (1.0 + self.gst_pct / 100.0)).round() as i64
+    }
+}
+
+pub fn card_network(card_number: &str) -> Option<&'static str> {
+    let digits: String = card_number.chars().filter(|c| c.is_ascii_digit()).collect();
+    let prefix = |n: usize| digits.get(..n).and_then(|p| p.parse::<u32>().ok());
+
+    if digits.starts_with("34") || digits.starts_with("37") {
+        return Some("AMEX");
+    }
+    if matches!(prefix(2), Some(60 | 65 | 81 | 82)) || prefix(3) == Some(508) {
+        return Some("RUPAY");
+    }
+    if matches!(prefix(2), Some(51..=55)) || matches!(prefix(4), Some(2221..=2720)) {
+        return Some("MASTERCARD");
+    }
+    if digits.starts_with('4') {
+        return Some("VISA");
+    }
+    if matches!(prefix(2), Some(36 | 38 | 39)) || matches!(prefix(3), Some(300..=305)) {
+        return Some("DINERS");
+    }
+    None
+}
+
+pub fn select_slab<'a>(
+    slabs: &'a [FeeSlab],
+    payment_method: &str,
+    card_network: Option<&str>,
+    card_type: Option<&str>,
+    amount_minor: i64,
+) -> Option<&'a FeeSlab> {
+    let eligible = slabs
+        .iter()
+        .filter(|s| s.payment_method.eq_ignore_ascii_case(payment_method) && s.covers(amount_minor));
+
+    let specificity = |slab_value: &str, any: &str, actual: Option<&str>| -> Option<u8> {
+        if slab_value == any {
+            Some(0)
+        } else if actual.is_some_and(|a| slab_value.eq_ignore_ascii_case(a)) {
+            Some(1)
+        } else {
+            None
+        }
+    };
+
+    let mut best: Option<(u8, &FeeSlab)> = None;
+    for slab in eligible {
+        let (Some(network), Some(kind)) = (
+            specificity(&slab.card_network, ANY_NETWORK, card_network),
+            specificity(&slab.card_type, ANY_CARD_TYPE, card_type),
+        ) else {
+            continue;
+        };
+        let rank = network * 2 + kind;
+        if best.is_none_or(|(r, _)| rank > r) {
+            best = Some((rank, slab));
+        }
+    }
+    best.map(|(_, slab)| slab)
+}
+
+pub fn expected_fee_minor(
+    slabs: &[FeeSlab],
+    payment_method: &str,
+    card_network: Option<&str>,
+    card_type: Option<&str>,
+    amount_minor: i64,
+) -> Option<i64> {
+    select_slab(slabs, payment_method, card_network, card_type, amount_minor).map(|s| s.fee_minor(amount_minor))
+}
+
+pub fn cost_score(expected_fee_minor: Option<i64>, amount_minor: i64) -> f64 {
+    match expected_fee_minor {
+        Some(fee) if amount_minor > 0 => {
+            let rate = fee as f64 / amount_minor as f64;
+            (1.0 - rate / REFERENCE_FEE_RATE).clamp(0.0, 1.0)
+        }
+        _ => UNKNOWN_FEE_SCORE,
+    }
+}
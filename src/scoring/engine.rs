@@ -5,13 +5,7 @@ pub fn latency_score(p95_latency_ms: i32) -> f64 {
 }
 
 pub fn clamp01(v: f64) -> f64 {
-    if v < 0.0 {
-        0.0
-    } else if v > 1.0 {
-        1.0
-    } else {
-        v
-    }
+    v.clamp(0.0, 1.0)
 }
 
 pub fn score_gateway(candidate: &GatewayCandidate, weights: &ScoreWeights) -> RankedGateway {
@@ -21,13 +15,15 @@ pub fn score_gateway(candidate: &GatewayCandidate, weights: &ScoreWeights) -> Ra
     let bank_affinity = clamp01(candidate.inputs.bank_affinity);
     let amount_fit = clamp01(candidate.inputs.amount_fit);
     let time_weight = clamp01(candidate.inputs.time_multiplier);
+    let cost_score = clamp01(candidate.inputs.cost_score);
 
     let raw = (weights.success_rate_weight * success_rate_score)
         + (weights.latency_weight * latency_component)
         + (weights.method_affinity_weight * method_affinity)
         + (weights.bank_affinity_weight * bank_affinity)
         + (weights.amount_fit_weight * amount_fit)
-        + (weights.time_weight * time_weight);
+        + (weights.time_weight * time_weight)
+        + (weights.cost_weight * cost_score);
 
     let final_score = clamp01(raw);
 
@@ -41,7 +37,10 @@ pub fn score_gateway(candidate: &GatewayCandidate, weights: &ScoreWeights) -> Ra
             bank_affinity,
             amount_fit,
             time_weight,
+            cost_score,
             final_score,
+            metric_level: Some(candidate.inputs.metric_level),
+            signal_windows: candidate.inputs.signal_windows.clone(),
         },
     }
 }
@@ -60,6 +59,7 @@ pub fn rank_gateways(candidates: &[GatewayCandidate], weights: &ScoreWeights) ->
 mod tests {
     use super::*;
     use crate::gateways::GatewayConfig;
+    use crate::scoring::hierarchy::MetricLevel;
     use crate::scoring::types::{GatewayCandidate, ScoreInputs, ScoreWeights};
 
     #[test]
@@ -71,6 +71,7 @@ mod tests {
             bank_affinity_weight: 0.12,
             amount_fit_weight: 0.08,
             time_weight: 0.05,
+            cost_weight: 0.0,
         };
 
         let g1 = GatewayCandidate {
@@ -83,6 +84,8 @@ mod tests {
                 supported_methods: vec!["UPI".to_string()],
                 timeout_ms: 1000,
                 mock_behavior: None,
+                max_in_flight: None,
+                max_tps: None,
             },
             inputs: ScoreInputs {
                 success_rate: 0.95,
@@ -91,6 +94,9 @@ mod tests {
                 bank_affinity: 1.0,
                 amount_fit: 0.8,
                 time_multiplier: 1.0,
+                cost_score: 0.5,
+                metric_level: MetricLevel::GatewayMethodBank,
+                signal_windows: Vec::new(),
             },
         };
 
@@ -104,6 +110,8 @@ mod tests {
                 supported_methods: vec!["UPI".to_string()],
                 timeout_ms: 1000,
                 mock_behavior: None,
+                max_in_flight: None,
+                max_tps: None,
             },
             inputs: ScoreInputs {
                 success_rate: 0.8,
@@ -112,6 +120,9 @@ mod tests {
                 bank_affinity: 0.5,
                 amount_fit: 0.7,
                 time_multiplier: 1.0,
+                cost_score: 0.5,
+                metric_level: MetricLevel::GatewayMethodBank,
+                signal_windows: Vec::new(),
             },
         };
 
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+pub const GLOBAL_PRIOR_SUCCESS_RATE: f64 = 0.5;
+pub const GLOBAL_PRIOR_P95_LATENCY_MS: i32 = 1500;
+pub const PRIOR_STRENGTH: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricLevel {
+    GatewayMethodBank,
+    GatewayMethod,
+    Gateway,
+    #[default]
+    GlobalPrior,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LevelStats {
+    pub success_rate: f64,
+    pub p95_latency_ms: i32,
+    pub total_requests: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothedMetric {
+    pub success_rate: f64,
+    pub p95_latency_ms: i32,
+    pub level: MetricLevel,
+}
+
+impl Default for SmoothedMetric {
+    fn default() -> Self {
+        Self {
+            success_rate: GLOBAL_PRIOR_SUCCESS_RATE,
+            p95_latency_ms: GLOBAL_PRIOR_P95_LATENCY_MS,
+            level: MetricLevel::GlobalPrior,
+        }
+    }
+}
+
+pub fn shrink(stats: &LevelStats, parent: &SmoothedMetric, strength: f64, level: MetricLevel) -> SmoothedMetric {
+    let n = stats.total_requests as f64;
+    let alpha = strength * parent.success_rate + stats.success_rate * n;
+    let beta = strength * (1.0 - parent.success_rate) + (1.0 - stats.success_rate) * n;
+    let weight = n / (n + strength);
+    let p95 = weight * stats.p95_latency_ms as f64 + (1.0 - weight) * parent.p95_latency_ms as f64;
+
+    SmoothedMetric {
+        success_rate: alpha / (alpha + beta),
+        p95_latency_ms: p95.round() as i32,
+        level,
+    }
+}
+
+pub fn smooth(bank: Option<LevelStats>, method: Option<LevelStats>, gateway: Option<LevelStats>) -> SmoothedMetric {
+    let levels = [
+        (gateway, MetricLevel::Gateway),
+        (method, MetricLevel::GatewayMethod),
+        (bank, MetricLevel::GatewayMethodBank),
+    ];
+
+    let mut estimate = SmoothedMetric::default();
+    for (stats, level) in levels {
+        let Some(stats) = stats.filter(|s| s.total_requests > 0) else {
+            continue;
+        };
+        estimate = shrink(&stats, &estimate, PRIOR_STRENGTH, level);
+    }
+    estimate
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const UNKNOWN_ISSUER: &str = "UNKNOWN";
+pub const DEFAULT_ISSUER_AFFINITY: f64 = 0.5;
+pub const UNKNOWN_ISSUER_AFFINITY: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AliasType {
+    UpiHandle,
+    NetbankingCode,
+    BinBank,
+}
+
+impl AliasType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AliasType::UpiHandle => "UPI_HANDLE",
+            AliasType::NetbankingCode => "NETBANKING_CODE",
+            AliasType::BinBank => "BIN_BANK",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "UPI_HANDLE" => Some(AliasType::UpiHandle),
+            "NETBANKING_CODE" => Some(AliasType::NetbankingCode),
+            "BIN_BANK" => Some(AliasType::BinBank),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuerAlias {
+    pub alias_type: AliasType,
+    pub alias: String,
+    pub issuer_id: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct IssuerRegistry {
+    aliases: HashMap<(AliasType, String), String>,
+}
+
+impl IssuerRegistry {
+    pub fn new(aliases: Vec<IssuerAlias>) -> Self {
+        Self {
+            aliases: aliases
+                .into_iter()
+                .map(|a| ((a.alias_type, a.alias.to_uppercase()), a.issuer_id.to_uppercase()))
+                .collect(),
+        }
+    }
+
+    pub fn lookup(&self, alias_type: AliasType, raw: &str) -> Option<&str> {
+        self.aliases
+            .get(&(alias_type, raw.to_uppercase()))
+            .map(String::as_str)
+    }
+
+    pub fn lookup_any(&self, raw: &str) -> Option<&str> {
+        [AliasType::BinBank, AliasType::NetbankingCode, AliasType::UpiHandle]
+            .into_iter()
+            .find_map(|t| self.lookup(t, raw))
+    }
+
+    pub fn canonical(&self, alias_type: Option<AliasType>, raw: Option<&str>) -> String {
+        let Some(raw) = raw.map(str::trim).filter(|r| !r.is_empty()) else {
+            return UNKNOWN_ISSUER.to_string();
+        };
+        let found = match alias_type {
+            Some(t) => self.lookup(t, raw),
+            None => self.lookup_any(raw),
+        };
+        found.map(str::to_string).unwrap_or_else(|| raw.to_uppercase())
+    }
+}
+
+pub fn issuer_affinity(table: &HashMap<(String, String), f64>, gateway_id: &str, issuer: &str) -> f64 {
+    if let Some(score) = table.get(&(gateway_id.to_string(), issuer.to_string())) {
+        return *score;
+    }
+    if issuer == UNKNOWN_ISSUER {
+        UNKNOWN_ISSUER_AFFINITY
+    } else {
+        DEFAULT_ISSUER_AFFINITY
+    }
+}
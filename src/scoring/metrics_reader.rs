@@ -1,10 +1,31 @@
+use crate::metrics::aggregator::{AggregatedMetric, MetricKey};
 use crate::metrics::store_redis::MetricsHotStore;
+use crate::scoring::blend::{blend_windows, SignalBlend, WindowContribution};
+use crate::scoring::hierarchy::{smooth, LevelStats, MetricLevel, GLOBAL_PRIOR_P95_LATENCY_MS, GLOBAL_PRIOR_SUCCESS_RATE};
 use anyhow::Result;
+use std::collections::HashMap;
+
+const LEVELS_PER_WINDOW: usize = 3;
 
 #[derive(Debug, Clone, serde::Deserialize)]
 pub struct GatewayMetricInput {
     pub success_rate: f64,
     pub p95_latency_ms: i32,
+    #[serde(default)]
+    pub level: MetricLevel,
+    #[serde(default)]
+    pub windows: Vec<WindowContribution>,
+}
+
+impl Default for GatewayMetricInput {
+    fn default() -> Self {
+        Self {
+            success_rate: GLOBAL_PRIOR_SUCCESS_RATE,
+            p95_latency_ms: GLOBAL_PRIOR_P95_LATENCY_MS,
+            level: MetricLevel::GlobalPrior,
+            windows: Vec::new(),
+        }
+    }
 }
 
 pub async fn read_metric_for_gateway(
@@ -12,16 +33,76 @@ pub async fn read_metric_for_gateway(
     gateway: &str,
     method: &str,
     bank: &str,
+    blend: &SignalBlend,
 ) -> Result<GatewayMetricInput> {
-    if let Some(metric) = store.read_single_metric(gateway, method, bank, 5).await? {
-        return Ok(GatewayMetricInput {
-            success_rate: metric.success_rate,
-            p95_latency_ms: metric.p95_latency_ms,
-        });
-    }
+    let mut metrics = read_metrics_for_gateways(store, &[gateway.to_string()], method, bank, blend).await?;
+    Ok(metrics.remove(gateway).unwrap_or_default())
+}
+
+pub async fn read_metrics_for_gateways(
+    store: &MetricsHotStore,
+    gateways: &[String],
+    method: &str,
+    bank: &str,
+    blend: &SignalBlend,
+) -> Result<HashMap<String, GatewayMetricInput>> {
+    let keys = metric_keys(gateways, method, bank, blend);
+    let metrics = store.read_metrics(&keys).await?;
+    Ok(blend_gateway_metrics(gateways, blend, &metrics))
+}
+
+pub fn metric_keys(gateways: &[String], method: &str, bank: &str, blend: &SignalBlend) -> Vec<String> {
+    let windows = blend.window_minutes();
+    gateways
+        .iter()
+        .flat_map(|gateway| {
+            let leaf = MetricKey {
+                gateway: gateway.clone(),
+                method: method.to_string(),
+                bank: bank.to_string(),
+            };
+            let levels = [leaf.clone(), leaf.gateway_method(), leaf.gateway_only()];
+            windows
+                .iter()
+                .flat_map(move |window| levels.clone().map(|key| MetricsHotStore::metric_key(&key, *window)))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+pub fn blend_gateway_metrics(
+    gateways: &[String],
+    blend: &SignalBlend,
+    metrics: &[Option<AggregatedMetric>],
+) -> HashMap<String, GatewayMetricInput> {
+    let windows = blend.window_minutes();
+    let stats = |m: &Option<AggregatedMetric>| {
+        m.as_ref().map(|m| LevelStats {
+            success_rate: m.success_rate,
+            p95_latency_ms: m.p95_latency_ms,
+            total_requests: m.total_requests,
+        })
+    };
 
-    Ok(GatewayMetricInput {
-        success_rate: 0.5,
-        p95_latency_ms: 1500,
-    })
+    gateways
+        .iter()
+        .zip(metrics.chunks(LEVELS_PER_WINDOW * windows.len().max(1)))
+        .map(|(gateway, per_gateway)| {
+            let per_window: Vec<_> = windows
+                .iter()
+                .zip(per_gateway.chunks(LEVELS_PER_WINDOW))
+                .map(|(window, levels)| (*window, smooth(stats(&levels[0]), stats(&levels[1]), stats(&levels[2]))))
+                .collect();
+            let blended = blend_windows(blend, &per_window);
+            (
+                gateway.clone(),
+                GatewayMetricInput {
+                    success_rate: blended.success_rate,
+                    p95_latency_ms: blended.p95_latency_ms,
+                    level: blended.level,
+                    windows: blended.windows,
+                },
+            )
+        })
+        .collect()
 }
@@ -1,3 +1,10 @@
+pub mod backtest;
+pub mod blend;
+pub mod cost;
 pub mod engine;
+pub mod hierarchy;
+pub mod issuers;
 pub mod metrics_reader;
+pub mod profiles;
+pub mod schedule;
 pub mod types;
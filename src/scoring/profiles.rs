@@ -0,0 +1,75 @@
+use crate::scoring::blend::SignalBlend;
+use crate::scoring::types::ScoreWeights;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_PROFILE_ID: &str = "default";
+const WEIGHT_SUM_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoringProfileVersion {
+    pub profile_id: String,
+    pub version: i32,
+    pub weights: ScoreWeights,
+    pub signal_blend: SignalBlend,
+    pub author: String,
+    pub change_note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileAssignment {
+    pub assignment_id: Uuid,
+    pub merchant_id: Option<String>,
+    pub payment_method: Option<String>,
+    pub amount_bucket: Option<String>,
+    pub profile_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedProfile {
+    pub profile_id: String,
+    pub version: i32,
+    pub weights: ScoreWeights,
+    pub signal_blend: SignalBlend,
+}
+
+pub fn validate_weights(w: &ScoreWeights) -> Result<(), String> {
+    let fields = [
+        ("success_rate_weight", w.success_rate_weight),
+        ("latency_weight", w.latency_weight),
+        ("method_affinity_weight", w.method_affinity_weight),
+        ("bank_affinity_weight", w.bank_affinity_weight),
+        ("amount_fit_weight", w.amount_fit_weight),
+        ("time_weight", w.time_weight),
+        ("cost_weight", w.cost_weight),
+    ];
+    for (name, value) in fields {
+        if !value.is_finite() || value < 0.0 {
+            return Err(format!("{} must be a non-negative number", name));
+        }
+    }
+    let sum: f64 = fields.iter().map(|(_, v)| v).sum();
+    if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+        return Err(format!("weights must sum to 1.0, got {:.6}", sum));
+    }
+    Ok(())
+}
+
+fn specificity(a: &ProfileAssignment) -> u8 {
+    (a.merchant_id.is_some() as u8) * 4 + (a.payment_method.is_some() as u8) * 2 + (a.amount_bucket.is_some() as u8)
+}
+
+pub fn select_assignment<'a>(
+    assignments: &'a [ProfileAssignment],
+    merchant_id: &str,
+    payment_method: &str,
+    amount_bucket: &str,
+) -> Option<&'a ProfileAssignment> {
+    assignments
+        .iter()
+        .filter(|a| a.merchant_id.as_deref().is_none_or(|m| m == merchant_id))
+        .filter(|a| a.payment_method.as_deref().is_none_or(|m| m.eq_ignore_ascii_case(payment_method)))
+        .filter(|a| a.amount_bucket.as_deref().is_none_or(|b| b == amount_bucket))
+        .max_by_key(|a| specificity(a))
+}
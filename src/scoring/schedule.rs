@@ -0,0 +1,131 @@
+use crate::scoring::types::RankedGateway;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const DEFAULT_PENALTY_MULTIPLIER: f64 = 0.5;
+
+pub fn parse_timezone(value: &str) -> Option<Tz> {
+    value.trim().parse().ok()
+}
+
+pub fn local_hour_and_day(at: DateTime<Utc>, timezone: Tz) -> (i32, i32) {
+    let local = at.with_timezone(&timezone);
+    (local.hour() as i32, local.day() as i32)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MaintenanceMode {
+    Exclude,
+    Penalize,
+}
+
+impl MaintenanceMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MaintenanceMode::Exclude => "EXCLUDE",
+            MaintenanceMode::Penalize => "PENALIZE",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "EXCLUDE" => Some(MaintenanceMode::Exclude),
+            "PENALIZE" => Some(MaintenanceMode::Penalize),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    pub window_id: Uuid,
+    pub gateway_id: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub payment_methods: Vec<String>,
+    pub issuers: Vec<String>,
+    pub mode: MaintenanceMode,
+    pub penalty_multiplier: f64,
+    pub reason: Option<String>,
+}
+
+impl MaintenanceWindow {
+    pub fn applies(&self, gateway_id: &str, method: &str, issuer: &str, at: DateTime<Utc>) -> bool {
+        self.gateway_id == gateway_id
+            && at >= self.starts_at
+            && at < self.ends_at
+            && (self.payment_methods.is_empty() || self.payment_methods.iter().any(|m| m.eq_ignore_ascii_case(method)))
+            && (self.issuers.is_empty() || self.issuers.iter().any(|i| i.eq_ignore_ascii_case(issuer)))
+    }
+}
+
+pub fn apply_penalties(mut ranked: Vec<RankedGateway>, penalties: &HashMap<String, f64>) -> Vec<RankedGateway> {
+    if penalties.is_empty() {
+        return ranked;
+    }
+    for r in &mut ranked {
+        if let Some(multiplier) = penalties.get(&r.gateway_id) {
+            r.score = (r.score * multiplier).clamp(0.0, 1.0);
+        }
+    }
+    ranked.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    ranked
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceWindowInput {
+    pub gateway_id: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    #[serde(default)]
+    pub payment_methods: Vec<String>,
+    #[serde(default)]
+    pub issuers: Vec<String>,
+    pub mode: MaintenanceMode,
+    pub penalty_multiplier: Option<f64>,
+    pub reason: Option<String>,
+}
+
+pub fn validate_window(input: &MaintenanceWindowInput) -> Result<(), String> {
+    if input.gateway_id.trim().is_empty() {
+        return Err("gateway_id is required".to_string());
+    }
+    if input.ends_at <= input.starts_at {
+        return Err("ends_at must be after starts_at".to_string());
+    }
+    match (input.mode, input.penalty_multiplier) {
+        (MaintenanceMode::Exclude, Some(_)) => Err("penalty_multiplier only applies to PENALIZE windows".to_string()),
+        (MaintenanceMode::Penalize, Some(m)) if !(0.0..=1.0).contains(&m) => {
+            Err("penalty_multiplier must be between 0 and 1".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn active_window<'a>(
+    windows: &'a [MaintenanceWindow],
+    gateway_id: &str,
+    method: &str,
+    issuer: &str,
+    at: DateTime<Utc>,
+) -> Option<&'a MaintenanceWindow> {
+    let mut best: Option<&MaintenanceWindow> = None;
+    for window in windows.iter().filter(|w| w.applies(gateway_id, method, issuer, at)) {
+        let better = match best {
+            None => true,
+            Some(b) => match (b.mode, window.mode) {
+                (MaintenanceMode::Penalize, MaintenanceMode::Exclude) => true,
+                (MaintenanceMode::Penalize, MaintenanceMode::Penalize) => window.penalty_multiplier < b.penalty_multiplier,
+                _ => false,
+            },
+        };
+        if better {
+            best = Some(window);
+        }
+    }
+    best
+}
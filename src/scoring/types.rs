@@ -1,4 +1,6 @@
 use crate::gateways::GatewayConfig;
+use crate::scoring::blend::WindowContribution;
+use crate::scoring::hierarchy::MetricLevel;
 
 #[derive(Debug, Clone)]
 pub struct ScoreInputs {
@@ -8,9 +10,12 @@ pub struct ScoreInputs {
     pub bank_affinity: f64,
     pub amount_fit: f64,
     pub time_multiplier: f64,
+    pub cost_score: f64,
+    pub metric_level: MetricLevel,
+    pub signal_windows: Vec<WindowContribution>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ScoreWeights {
     pub success_rate_weight: f64,
     pub latency_weight: f64,
@@ -18,6 +23,8 @@ pub struct ScoreWeights {
     pub bank_affinity_weight: f64,
     pub amount_fit_weight: f64,
     pub time_weight: f64,
+    #[serde(default)]
+    pub cost_weight: f64,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -28,7 +35,13 @@ pub struct ScoreBreakdown {
     pub bank_affinity: f64,
     pub amount_fit: f64,
     pub time_weight: f64,
+    #[serde(default)]
+    pub cost_score: f64,
     pub final_score: f64,
+    #[serde(default)]
+    pub metric_level: Option<MetricLevel>,
+    #[serde(default)]
+    pub signal_windows: Vec<WindowContribution>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -1,35 +1,51 @@
-use crate::circuit::evaluator::pre_call_decision;
+use crate::bandit::discount::{PRIOR_ALPHA, PRIOR_BETA};
+use crate::bandit::evaluation::greedy_probabilities;
+use crate::bandit::linucb::{features as bandit_features, LinearModel, FEATURE_DIM};
+use crate::bandit::policy::BanditAlgorithm;
+use crate::bandit::reward::{reward as bandit_reward, AttemptReward};
+use crate::bandit::safety::{mix_propensities, passes_floor};
+use crate::bandit::segments::{BanditSegment, SegmentContext};
+use crate::bandit::thompson;
+use crate::bins::index::CardAttributes;
+use crate::circuit::evaluator::{pre_call_decision, rejects_all_calls};
 use crate::circuit::state::CircuitDecision;
-use crate::circuit::store_redis::CircuitStoreRedis;
+use crate::circuit::store_redis::{CircuitStoreRedis, CircuitView};
 use crate::circuit::transitions::apply_transition;
 use crate::domain::context::build_context;
-use crate::experiments::assigner::assign_variant;
+use crate::domain::experiment::ExperimentVariant;
+use crate::domain::routing_decision::{BanditSelection, DroppedGateway, PenalizedGateway};
+use crate::experiments::assigner::{assign_variant, is_exposed};
 use crate::experiments::filter::{matches as experiment_matches, MatchInput as ExperimentMatchInput};
 use crate::domain::payment::PaymentInstrument;
 use crate::domain::payment::{CreatePaymentRequest, CreatePaymentResponse, ErrorEnvelope, ErrorPayload, PaymentStatus};
+use crate::domain::routing_rule::DEFAULT_RULE_SET;
+use crate::gateways::bulkhead::{lease_ttl_ms, BulkheadDecision, GatewayBulkhead};
 use crate::gateways::mock::MockGateway;
 use crate::gateways::razorpay::RazorpayGateway;
 use crate::gateways::{GatewayConfig, GatewayRequest, GatewayResult, NormalizedGatewayResponse, PaymentGateway};
-use crate::metrics::amount_bucket::from_amount_minor;
 use crate::metrics::event::PaymentEvent;
-use crate::metrics::store_redis::MetricsHotStore;
-use crate::repo::circuit_breaker_config_repo::CircuitBreakerConfigRepo;
-use crate::repo::error_classification_repo::ErrorClassificationRepo;
+use crate::metrics::store_redis::{parse_metrics, MetricsHotStore};
 use crate::repo::bandit_repo::BanditRepo;
 use crate::repo::experiments_repo::ExperimentsRepo;
-use crate::repo::gateways_repo::GatewaysRepo;
 use crate::repo::outbox_repo::OutboxRepo;
 use crate::repo::payment_attempts_repo::{NewPaymentAttempt, PaymentAttemptsRepo};
 use crate::repo::payment_verification_repo::PaymentVerificationRepo;
 use crate::repo::payments_repo::{PaymentRecordInput, PaymentsRepo};
-use crate::repo::retry_policy_repo::RetryPolicyRepo;
-use crate::repo::routing_decisions_repo::RoutingDecisionsRepo;
-use crate::repo::scoring_config_repo::ScoringConfigRepo;
+use crate::repo::retry_policy_repo::RetryPolicy;
+use crate::repo::routing_decisions_repo::{NewRoutingDecision, RoutingDecisionsRepo};
+use crate::router::rules::{apply_boosts, evaluate as evaluate_rules, layer_production_excludes, resolve_force, RuleInput};
+use crate::scoring::cost::{cost_score, expected_fee_minor};
 use crate::scoring::engine::rank_gateways;
-use crate::scoring::metrics_reader::read_metric_for_gateway;
-use crate::scoring::types::{GatewayCandidate, RankedGateway, ScoreInputs, ScoreWeights};
+use crate::scoring::issuers::AliasType;
+use crate::scoring::blend::SignalBlend;
+use crate::scoring::metrics_reader::{blend_gateway_metrics, metric_keys, GatewayMetricInput};
+use crate::scoring::schedule::{apply_penalties, MaintenanceMode};
+use crate::scoring::types::{GatewayCandidate, RankedGateway, ScoreInputs};
 use crate::service::retry_orchestrator::{attempt_limit, classify_attempt_result, should_stop_for_budget, RetryDirective};
+use crate::service::routing_config::{RoutingConfigSnapshot, RoutingConfigStore};
 use crate::service::webhook_dispatcher::WebhookDispatcher;
+use crate::volume::pacing::{has_cap, is_capped, pace_boost, VolumeUsage};
+use crate::volume::store_redis::VolumeStoreRedis;
 use axum::http::HeaderMap;
 use chrono::Timelike;
 use sqlx::PgPool;
@@ -44,17 +60,15 @@ pub struct PaymentService {
     pub pool: PgPool,
     pub payments_repo: PaymentsRepo,
     pub outbox_repo: OutboxRepo,
-    pub gateways_repo: GatewaysRepo,
     pub experiments_repo: ExperimentsRepo,
     pub bandit_repo: BanditRepo,
-    pub scoring_config_repo: ScoringConfigRepo,
+    pub routing_config: RoutingConfigStore,
     pub routing_decisions_repo: RoutingDecisionsRepo,
-    pub circuit_breaker_config_repo: CircuitBreakerConfigRepo,
     pub metrics_hot_store: MetricsHotStore,
     pub circuit_store: CircuitStoreRedis,
+    pub bulkhead: GatewayBulkhead,
+    pub volume_store: VolumeStoreRedis,
     pub payment_attempts_repo: PaymentAttemptsRepo,
-    pub retry_policy_repo: RetryPolicyRepo,
-    pub error_classification_repo: ErrorClassificationRepo,
     pub payment_verification_repo: PaymentVerificationRepo,
     pub webhook_dispatcher: WebhookDispatcher,
     pub razorpay: Arc<RazorpayGateway>,
@@ -117,12 +131,9 @@ impl PaymentService {
             .map(str::to_string);
         let context = build_context(&req, client_ip, user_agent);
 
+        let config = self.routing_config.current();
         let method = format!("{:?}", req.payment_method).to_uppercase();
-        let available = self
-            .gateways_repo
-            .list_enabled_by_method(&method)
-            .await
-            .map_err(internal)?;
+        let available = config.enabled_gateways(&method);
 
         if available.is_empty() {
             return Err((
@@ -131,48 +142,185 @@ impl PaymentService {
             ));
         }
 
-        let issuing_bank = self.resolve_issuing_bank(&req, &context).await.map_err(internal)?;
-        let amount_bucket = from_amount_minor(req.amount_minor);
-        let weights = self.scoring_config_repo.load_weights().await.map_err(internal)?;
+        let issuing_bank = resolve_issuing_bank(&config, &req, &context);
+        let card = match &req.instrument {
+            PaymentInstrument::Card(card) => config.card_attributes(&card.number),
+            _ => CardAttributes::default(),
+        };
+        let amount_bucket = config.amount_bucket(&req.currency, req.amount_minor);
         let experiment_ctx = self
-            .resolve_experiment(&req, &method, &amount_bucket)
+            .resolve_experiment(&req, &method, &amount_bucket, &card)
             .await
             .map_err(internal)?;
+        let variant = experiment_ctx.as_ref().map(|c| &c.variant);
 
-        let mut candidates = Vec::new();
-        for gateway in available {
-            let metric = read_metric_for_gateway(
-                &self.metrics_hot_store,
-                &gateway.gateway_id,
-                &method,
-                &issuing_bank,
-            )
+        let rule_set = variant.and_then(|v| v.rule_set.as_deref()).unwrap_or(DEFAULT_RULE_SET);
+        let rule_input = RuleInput {
+            payment_method: method.clone(),
+            merchant_id: req.merchant_id.clone(),
+            issuing_bank: issuing_bank.clone(),
+            amount_minor: req.amount_minor,
+            amount_bucket: amount_bucket.clone(),
+            card_network: card.card_network.clone(),
+            card_type: card.card_type.clone(),
+        };
+        let enabled_ids: Vec<String> = available.iter().map(|g| g.gateway_id.clone()).collect();
+
+        let scoring_profile = variant
+            .and_then(|v| v.scoring_profile_id.as_ref())
+            .and_then(|id| config.profiles.get(id).cloned())
+            .or_else(|| config.resolve_profile(&req.merchant_id, &method, &amount_bucket));
+        let weights = config.weights_for(scoring_profile.as_ref());
+        let signal_blend = config.blend_for(scoring_profile.as_ref());
+
+        let now = chrono::Utc::now();
+        let volume_ids: Vec<String> = config
+            .volume_targets
+            .iter()
+            .filter(|t| enabled_ids.contains(&t.gateway_id))
+            .map(|t| t.gateway_id.clone())
+            .collect();
+        let RoutingReads {
+            metrics,
+            circuit_views,
+            usages,
+        } = self
+            .read_routing_state(&method, &issuing_bank, &signal_blend, &enabled_ids, &volume_ids, now)
             .await
             .map_err(internal)?;
+        let capped_gateways: Vec<String> = config
+            .volume_targets
+            .iter()
+            .filter(|t| {
+                usages
+                    .get(&t.gateway_id)
+                    .is_some_and(|usage| is_capped(t, usage, req.amount_minor))
+            })
+            .map(|t| t.gateway_id.clone())
+            .collect();
+
+        let production_rules = config.routing_rules_for(DEFAULT_RULE_SET);
+        let (rule_outcome, forced_unavailable) = resolve_force(
+            &config.routing_rules_for(rule_set),
+            |rules| {
+                let outcome = evaluate_rules(rules, &rule_input);
+                if rule_set == DEFAULT_RULE_SET {
+                    outcome
+                } else {
+                    layer_production_excludes(outcome, &production_rules, &rule_input)
+                }
+            },
+            |gateway_id| {
+                enabled_ids.iter().any(|g| g == gateway_id)
+                    && !capped_gateways.iter().any(|g| g == gateway_id)
+                    && config
+                        .maintenance_window(gateway_id, &method, &issuing_bank, now)
+                        .is_none_or(|w| w.mode != MaintenanceMode::Exclude)
+                    && !circuit_views
+                        .get(gateway_id)
+                        .is_some_and(|v| rejects_all_calls(v.override_state.as_deref(), &v.snapshot, now))
+            },
+        );
+        let primary_rule_id = rule_outcome.primary_rule_id(&enabled_ids);
+        let mut available: Vec<GatewayConfig> = available
+            .into_iter()
+            .filter(|g| rule_outcome.allows(&g.gateway_id))
+            .collect();
+        if available.is_empty() {
+            return Err((
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                err("NO_GATEWAY_AVAILABLE", "routing rules excluded every eligible gateway"),
+            ));
+        }
 
-            let method_affinity = self
-                .scoring_config_repo
-                .method_affinity(&gateway.gateway_id, &method)
-                .await
-                .map_err(internal)?;
-            let amount_fit = self
-                .scoring_config_repo
-                .amount_fit(&gateway.gateway_id, &amount_bucket)
-                .await
-                .map_err(internal)?;
-            let time_multiplier = self
-                .scoring_config_repo
-                .time_multiplier(&gateway.gateway_id, chrono::Utc::now())
-                .await
-                .map_err(internal)?;
-
-            let bank_affinity = if gateway.gateway_name.to_uppercase() == issuing_bank {
-                1.0
-            } else if issuing_bank == "UNKNOWN" {
-                0.6
-            } else {
-                0.5
+        let mut boosts = rule_outcome.boosts.clone();
+        for target in &config.volume_targets {
+            let Some(usage) = usages.get(&target.gateway_id) else {
+                continue;
             };
+            if capped_gateways.contains(&target.gateway_id) {
+                continue;
+            }
+            let boost = pace_boost(target, usage, now);
+            if boost > 0.0 {
+                *boosts.entry(target.gateway_id.clone()).or_insert(0.0) += boost;
+            }
+        }
+
+        let mut dropped_gateways: Vec<DroppedGateway> = forced_unavailable
+            .iter()
+            .map(|gateway_id| DroppedGateway {
+                gateway_id: gateway_id.clone(),
+                reason: "rule_forced_unavailable".to_string(),
+                maintenance_window_id: None,
+            })
+            .collect();
+        let capped_available: Vec<String> = capped_gateways
+            .iter()
+            .filter(|g| available.iter().any(|a| &a.gateway_id == *g))
+            .cloned()
+            .collect();
+        available.retain(|g| !capped_gateways.contains(&g.gateway_id));
+        if available.is_empty() {
+            return Err((
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                err("NO_GATEWAY_AVAILABLE", "every eligible gateway has reached its volume cap"),
+            ));
+        }
+        dropped_gateways.extend(capped_available.iter().map(|gateway_id| DroppedGateway {
+            gateway_id: gateway_id.clone(),
+            reason: "volume_cap".to_string(),
+            maintenance_window_id: None,
+        }));
+        available.retain(|g| match config.maintenance_window(&g.gateway_id, &method, &issuing_bank, now) {
+            Some(window) if window.mode == MaintenanceMode::Exclude => {
+                dropped_gateways.push(DroppedGateway {
+                    gateway_id: g.gateway_id.clone(),
+                    reason: "maintenance".to_string(),
+                    maintenance_window_id: Some(window.window_id),
+                });
+                false
+            }
+            _ => true,
+        });
+        if available.is_empty() {
+            return Err((
+                axum::http::StatusCode::SERVICE_UNAVAILABLE,
+                err("NO_GATEWAY_AVAILABLE", "every eligible gateway is in a maintenance window"),
+            ));
+        }
+
+        let mut candidates = Vec::new();
+        let mut expected_fees = std::collections::HashMap::new();
+        let mut penalized_gateways: Vec<PenalizedGateway> = Vec::new();
+        for gateway in available {
+            let metric = metrics.get(&gateway.gateway_id).cloned().unwrap_or_default();
+
+            let method_affinity = config.method_affinity(&gateway.gateway_id, &method);
+            let amount_fit = config.amount_fit(&gateway.gateway_id, &amount_bucket);
+            let time_multiplier = config.time_multiplier(&gateway.gateway_id, now);
+            if let Some(window) = config
+                .maintenance_window(&gateway.gateway_id, &method, &issuing_bank, now)
+                .filter(|w| w.mode == MaintenanceMode::Penalize)
+            {
+                penalized_gateways.push(PenalizedGateway {
+                    gateway_id: gateway.gateway_id.clone(),
+                    maintenance_window_id: window.window_id,
+                    penalty_multiplier: window.penalty_multiplier,
+                });
+            }
+
+            let bank_affinity = config.issuer_affinity(&gateway.gateway_id, &issuing_bank);
+
+            let fee_slabs = config.fee_slabs(&gateway.gateway_id, &method);
+            let expected_fee = expected_fee_minor(
+                fee_slabs,
+                &method,
+                card.card_network.as_deref(),
+                card.card_type.as_deref(),
+                req.amount_minor,
+            );
+            expected_fees.insert(gateway.gateway_id.clone(), expected_fee);
 
             candidates.push(GatewayCandidate {
                 gateway,
@@ -183,16 +331,33 @@ impl PaymentService {
                     bank_affinity,
                     amount_fit,
                     time_multiplier,
+                    cost_score: cost_score(expected_fee, req.amount_minor),
+                    metric_level: metric.level,
+                    signal_windows: metric.windows,
                 },
             });
         }
 
-        let ranked = rank_gateways(&candidates, &to_weights(&weights));
-        let mut ranked = apply_experiment_override(ranked, experiment_ctx.as_ref().and_then(|c| c.forced_gateway.clone()));
-        let bandit_segment = format!("{}:{}", method, amount_bucket);
-        if experiment_ctx.as_ref().and_then(|c| c.forced_gateway.clone()).is_none() {
-            ranked = self
-                .apply_bandit_if_enabled(&bandit_segment, ranked)
+        let penalties = penalized_gateways
+            .iter()
+            .map(|p| (p.gateway_id.clone(), p.penalty_multiplier))
+            .collect();
+        let ranked = apply_boosts(apply_penalties(rank_gateways(&candidates, &weights), &penalties), &boosts);
+        let forced_gateway = variant.and_then(|v| v.override_gateway.clone());
+        let mut ranked = apply_experiment_override(ranked, forced_gateway.clone());
+        let bandit_segments = config.bandit_definitions.segments(&SegmentContext {
+            payment_method: &method,
+            amount_bucket: &amount_bucket,
+            issuer: &issuing_bank,
+            card_network: card.card_network.as_deref(),
+            merchant_id: &req.merchant_id,
+            at: now,
+        });
+        let mut bandit_selection: Option<BanditSelection> = None;
+        let bandit_enabled = variant.and_then(|v| v.bandit_enabled);
+        if forced_gateway.is_none() && bandit_enabled != Some(false) {
+            (ranked, bandit_selection) = self
+                .apply_bandit_if_enabled(&config, &bandit_segments, ranked, bandit_enabled == Some(true))
                 .await
                 .map_err(internal)?;
         }
@@ -203,17 +368,24 @@ impl PaymentService {
             ));
         }
 
-        let policy = self
-            .retry_policy_repo
-            .get_for_merchant(&req.merchant_id)
-            .await
-            .map_err(internal)?;
+        let policy = match variant.and_then(|v| v.retry_policy.as_ref()) {
+            Some(retry) => RetryPolicy {
+                merchant_id: req.merchant_id.clone(),
+                max_attempts: retry.max_attempts,
+                latency_budget_ms: retry.latency_budget_ms,
+                retry_on_timeout: retry.retry_on_timeout,
+                enabled: true,
+            },
+            None => config.retry_policy(&req.merchant_id),
+        };
 
         let payment_id = Uuid::new_v4();
         let retry_started = Instant::now();
 
         let mut final_result: Option<(RankedGateway, GatewayResult, i32, String)> = None;
+        let mut bandit_attempts: Vec<(RankedGateway, bool, i32)> = Vec::new();
         let mut pending_verification_gateway: Option<String> = None;
+        let mut reserved_volume_gateway: Option<String> = None;
 
         let max_attempts = attempt_limit(&policy) as usize;
         for (idx, ranked_gateway) in ranked.iter().take(max_attempts).enumerate() {
@@ -226,10 +398,12 @@ impl PaymentService {
                 continue;
             };
 
-            let (circuit_allowed, was_probe, circuit_state, circuit_reason) = self
-                .check_circuit(&ranked_gateway.gateway_id, &method)
-                .await
-                .map_err(internal)?;
+            let (circuit_allowed, was_probe, circuit_state, circuit_reason) = check_circuit(
+                circuit_views.get(&ranked_gateway.gateway_id),
+                &config.circuit_thresholds(&ranked_gateway.gateway_id, &method),
+                &ranked_gateway.gateway_id,
+                &method,
+            );
 
             if !circuit_allowed {
                 self.payment_attempts_repo
@@ -249,16 +423,98 @@ impl PaymentService {
                 continue;
             }
 
+            let bulkhead_decision = BulkheadDecision::or_fail_open(
+                self.bulkhead.try_acquire(&selected_gateway).await,
+                &selected_gateway.gateway_id,
+            );
+            if let Some(reason) = bulkhead_decision.skip_reason() {
+                self.payment_attempts_repo
+                    .insert(NewPaymentAttempt {
+                        payment_id,
+                        attempt_number,
+                        gateway_used: ranked_gateway.gateway_id.clone(),
+                        status: "SKIPPED".to_string(),
+                        error_code: None,
+                        latency_ms: 0,
+                        circuit_breaker_state: Some(circuit_state),
+                        fallback_reason: Some(reason.to_string()),
+                        transaction_ref: None,
+                    })
+                    .await
+                    .map_err(internal)?;
+                continue;
+            }
+
+            let volume_target = config
+                .volume_targets
+                .iter()
+                .find(|t| t.gateway_id == selected_gateway.gateway_id && has_cap(t));
+            let volume_reservation = match volume_target {
+                Some(target) => match self
+                    .volume_store
+                    .reserve(target, req.amount_minor, lease_ttl_ms(selected_gateway.timeout_ms), now)
+                    .await
+                {
+                    Ok(lease) => Some(lease),
+                    Err(e) => {
+                        tracing::warn!("volume reservation failed for {}: {}", selected_gateway.gateway_id, e);
+                        None
+                    }
+                },
+                None => None,
+            };
+            if matches!(volume_reservation, Some(None)) {
+                if let BulkheadDecision::Acquired(Some(lease)) = &bulkhead_decision {
+                    let _ = self.bulkhead.release(&selected_gateway.gateway_id, lease).await;
+                }
+                self.payment_attempts_repo
+                    .insert(NewPaymentAttempt {
+                        payment_id,
+                        attempt_number,
+                        gateway_used: ranked_gateway.gateway_id.clone(),
+                        status: "SKIPPED".to_string(),
+                        error_code: None,
+                        latency_ms: 0,
+                        circuit_breaker_state: Some(circuit_state),
+                        fallback_reason: Some("volume_cap".to_string()),
+                        transaction_ref: None,
+                    })
+                    .await
+                    .map_err(internal)?;
+                continue;
+            }
+
             let gateway_request = GatewayRequest {
                 amount_minor: req.amount_minor,
                 currency: req.currency.clone(),
                 merchant_id: req.merchant_id.clone(),
             };
 
-            let (gateway_result, latency_ms) = self
+            let call_result = self
                 .execute_gateway_call(&selected_gateway, &context, gateway_request)
-                .await
-                .map_err(internal)?;
+                .await;
+            if let BulkheadDecision::Acquired(Some(lease)) = &bulkhead_decision {
+                let _ = self.bulkhead.release(&selected_gateway.gateway_id, lease).await;
+            }
+            if let Some(lease) = volume_reservation.flatten() {
+                if call_result
+                    .as_ref()
+                    .is_ok_and(|(r, _)| matches!(r.response.status, PaymentStatus::Success))
+                {
+                    if let Err(e) = self.volume_store.commit(&lease, now).await {
+                        tracing::warn!("volume commit failed for {}: {}", lease.gateway_id, e);
+                    }
+                    reserved_volume_gateway = Some(selected_gateway.gateway_id.clone());
+                } else {
+                    let _ = self.volume_store.release(&lease).await;
+                }
+            }
+            let (gateway_result, latency_ms) = call_result.map_err(internal)?;
+            bandit_attempts.push((
+                ranked_gateway.clone(),
+                matches!(gateway_result.response.status, PaymentStatus::Success),
+                latency_ms,
+            ));
 
             self.payment_attempts_repo
                 .insert(NewPaymentAttempt {
@@ -280,6 +536,7 @@ impl PaymentService {
                 .map_err(internal)?;
 
             self.update_circuit_state(
+                &config,
                 &ranked_gateway.gateway_id,
                 &method,
                 &format!("{:?}", gateway_result.response.status).to_uppercase(),
@@ -288,16 +545,11 @@ impl PaymentService {
             .await
             .map_err(internal)?;
 
-            let error_class = if let Some(code) = &gateway_result.response.error_code {
-                Some(
-                    self.error_classification_repo
-                        .classify(&ranked_gateway.gateway_id, code)
-                        .await
-                        .map_err(internal)?,
-                )
-            } else {
-                None
-            };
+            let error_class = gateway_result
+                .response
+                .error_code
+                .as_ref()
+                .map(|code| config.classify_error(&ranked_gateway.gateway_id, code));
 
             match classify_attempt_result(
                 &gateway_result.response.status,
@@ -350,7 +602,37 @@ impl PaymentService {
             }
         }
 
+        let selected_segment = bandit_selection
+            .as_ref()
+            .map(|s| BanditSegment::new(&s.definition_id, &s.segment));
+        let tracked = config.tracked_bandit_segments(&bandit_segments, selected_segment.as_ref());
+        if !tracked.is_empty() && !bandit_attempts.is_empty() {
+            let bandit_repo = self.bandit_repo.clone();
+            let config = config.clone();
+            let expected_fees = expected_fees.clone();
+            let amount_minor = req.amount_minor;
+            tokio::spawn(async move {
+                for segment in &tracked {
+                    record_bandit_attempts(&bandit_repo, &config, segment, &bandit_attempts, amount_minor, &expected_fees)
+                        .await;
+                }
+            });
+        }
+
         let Some((selected, gateway_result, latency_ms, outcome_reason)) = final_result else {
+            if let Some(exp) = &experiment_ctx {
+                self.experiments_repo
+                    .record_result(
+                        exp.experiment_id,
+                        &exp.variant.variant_name,
+                        hour_floor(chrono::Utc::now()),
+                        false,
+                        retry_started.elapsed().as_millis() as i32,
+                        req.amount_minor,
+                    )
+                    .await
+                    .map_err(internal)?;
+            }
             return Err((
                 axum::http::StatusCode::SERVICE_UNAVAILABLE,
                 err("RETRY_EXHAUSTED", "no gateway could complete payment within retry budget"),
@@ -369,7 +651,7 @@ impl PaymentService {
         }
 
         let routing_reason = format!(
-            "reason={}, top_score={:.4}, runner_up={}, experiment={}",
+            "reason={}, top_score={:.4}, runner_up={}, experiment={}, rule={}, capped={}, maintenance={}",
             outcome_reason,
             selected.score,
             ranked
@@ -378,8 +660,17 @@ impl PaymentService {
                 .unwrap_or_else(|| "none".to_string()),
             experiment_ctx
                 .as_ref()
-                .map(|e| format!("{}:{}", e.experiment_id, e.variant))
-                .unwrap_or_else(|| "none".to_string())
+                .map(|e| format!("{}:{}", e.experiment_id, e.variant.variant_name))
+                .unwrap_or_else(|| "none".to_string()),
+            primary_rule_id
+                .map(|id| id.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+            if capped_available.is_empty() {
+                "none".to_string()
+            } else {
+                capped_available.join("|")
+            },
+            maintenance_summary(&dropped_gateways)
         );
 
         let payment_input = PaymentRecordInput {
@@ -389,6 +680,8 @@ impl PaymentService {
             request_hash,
             req: req.clone(),
             issuing_bank: Some(issuing_bank.clone()),
+            card_network: card.card_network.clone(),
+            card_type: card.card_type.clone(),
             gateway_used: gateway_result.gateway_used.clone(),
             routing_strategy: "SCORING_ENGINE_FALLBACK".to_string(),
             routing_reason: routing_reason.clone(),
@@ -397,6 +690,7 @@ impl PaymentService {
             gateway_response_code: gateway_result.response.gateway_response_code.clone(),
             error_message: gateway_result.response.error_message.clone(),
             latency_ms: retry_started.elapsed().as_millis() as i32,
+            expected_fee_minor: expected_fees.get(&gateway_result.gateway_used).copied().flatten(),
         };
 
         let event = PaymentEvent {
@@ -426,17 +720,30 @@ impl PaymentService {
         tx.commit().await.map_err(|e| internal(e.into()))?;
 
         self.routing_decisions_repo
-            .insert(
+            .insert(NewRoutingDecision {
                 payment_id,
-                &selected.gateway_id,
-                selected.score,
-                ranked.get(1).map(|r| r.gateway_id.as_str()),
-                ranked.get(1).map(|r| r.score),
-                "SCORING_ENGINE_FALLBACK",
-                &routing_reason,
-                serde_json::to_value(&selected.breakdown).map_err(|e| internal(e.into()))?,
-                serde_json::to_value(&ranked).map_err(|e| internal(e.into()))?,
-            )
+                selected_gateway: selected.gateway_id.clone(),
+                selected_score: selected.score,
+                runner_up_gateway: ranked.get(1).map(|r| r.gateway_id.clone()),
+                runner_up_score: ranked.get(1).map(|r| r.score),
+                strategy: "SCORING_ENGINE_FALLBACK".to_string(),
+                reason_summary: routing_reason.clone(),
+                score_breakdown_json: serde_json::to_value(&selected.breakdown).map_err(|e| internal(e.into()))?,
+                ranked_gateways_json: serde_json::to_value(&ranked).map_err(|e| internal(e.into()))?,
+                matched_rule_id: primary_rule_id,
+                matched_rule_ids: serde_json::to_value(&rule_outcome.matched_rule_ids)
+                    .map_err(|e| internal(e.into()))?,
+                dropped_gateways_json: serde_json::to_value(&dropped_gateways).map_err(|e| internal(e.into()))?,
+                penalized_gateways_json: serde_json::to_value(&penalized_gateways).map_err(|e| internal(e.into()))?,
+                scoring_profile_id: scoring_profile.as_ref().map(|p| p.profile_id.clone()),
+                scoring_profile_version: scoring_profile.as_ref().map(|p| p.version),
+                bandit_propensity: bandit_selection.as_ref().map(|s| s.propensity()),
+                bandit_selection_json: bandit_selection
+                    .as_ref()
+                    .map(serde_json::to_value)
+                    .transpose()
+                    .map_err(|e| internal(e.into()))?,
+            })
             .await
             .map_err(internal)?;
 
@@ -444,7 +751,7 @@ impl PaymentService {
             self.experiments_repo
                 .record_result(
                     exp.experiment_id,
-                    &exp.variant,
+                    &exp.variant.variant_name,
                     hour_floor(chrono::Utc::now()),
                     matches!(gateway_result.response.status, PaymentStatus::Success),
                     latency_ms,
@@ -454,14 +761,14 @@ impl PaymentService {
                 .map_err(internal)?;
         }
 
-        let _ = self
-            .bandit_repo
-            .update_outcome(
-                &bandit_segment,
-                &selected.gateway_id,
-                matches!(gateway_result.response.status, PaymentStatus::Success),
-            )
-            .await;
+        if matches!(gateway_result.response.status, PaymentStatus::Success)
+            && reserved_volume_gateway.as_deref() != Some(gateway_result.gateway_used.as_str())
+        {
+            let _ = self
+                .volume_store
+                .record(&gateway_result.gateway_used, req.amount_minor, chrono::Utc::now())
+                .await;
+        }
 
         Ok(CreatePaymentResponse {
             payment_id,
@@ -474,32 +781,6 @@ impl PaymentService {
         })
     }
 
-    async fn check_circuit(
-        &self,
-        gateway_id: &str,
-        method: &str,
-    ) -> anyhow::Result<(bool, bool, String, String)> {
-        let override_state = self.circuit_store.get_override(gateway_id, method).await?;
-        if override_state.as_deref() == Some("FORCE_OPEN") {
-            return Ok((false, false, "OPEN".to_string(), "manual_force_open".to_string()));
-        }
-        if override_state.as_deref() == Some("FORCE_CLOSED") {
-            return Ok((true, false, "CLOSED".to_string(), "manual_force_closed".to_string()));
-        }
-
-        let thresholds = self
-            .circuit_breaker_config_repo
-            .get_thresholds(gateway_id, method)
-            .await?;
-        let snapshot = self.circuit_store.get_snapshot(gateway_id, method).await?;
-
-        match pre_call_decision(&snapshot, &thresholds, chrono::Utc::now()) {
-            CircuitDecision::Allow => Ok((true, false, format!("{:?}", snapshot.state).to_uppercase(), "allow".to_string())),
-            CircuitDecision::Probe => Ok((true, true, "HALF_OPEN".to_string(), "probe".to_string())),
-            CircuitDecision::Reject(reason) => Ok((false, false, format!("{:?}", snapshot.state).to_uppercase(), reason)),
-        }
-    }
-
     async fn execute_gateway_call(
         &self,
         gateway: &GatewayConfig,
@@ -558,37 +839,27 @@ impl PaymentService {
 
     async fn update_circuit_state(
         &self,
+        config: &RoutingConfigSnapshot,
         gateway_id: &str,
         method: &str,
         status: &str,
         was_probe: bool,
     ) -> anyhow::Result<()> {
         let now = chrono::Utc::now();
-        self.circuit_store
-            .write_result(gateway_id, method, status, now)
-            .await?;
-
-        let snapshot = self.circuit_store.get_snapshot(gateway_id, method).await?;
-        let thresholds = self
-            .circuit_breaker_config_repo
-            .get_thresholds(gateway_id, method)
-            .await?;
-        let (failure_rate_2m, _) = self
+        let observation = self
             .circuit_store
-            .aggregate_window(gateway_id, method, 2, now)
-            .await?;
-        let (_, timeout_rate_5m) = self
-            .circuit_store
-            .aggregate_window(gateway_id, method, 5, now)
+            .record_result(gateway_id, method, status, now)
             .await?;
+        let snapshot = observation.snapshot;
+        let thresholds = config.circuit_thresholds(gateway_id, method);
 
         let prev_state = format!("{:?}", snapshot.state);
         let prev_failure_rate = snapshot.failure_rate_2m;
         let updated = apply_transition(
             snapshot,
             &thresholds,
-            failure_rate_2m,
-            timeout_rate_5m,
+            observation.failure_rate_2m,
+            observation.timeout_rate_5m,
             status,
             was_probe,
             now,
@@ -638,33 +909,12 @@ impl PaymentService {
         Ok(())
     }
 
-    async fn resolve_issuing_bank(
-        &self,
-        req: &CreatePaymentRequest,
-        context: &crate::domain::context::PaymentContext,
-    ) -> anyhow::Result<String> {
-        if let PaymentInstrument::Card(card) = &req.instrument {
-            if let Some(bank) = self
-                .scoring_config_repo
-                .resolve_bank_from_bin(&card.number)
-                .await?
-            {
-                return Ok(bank.to_uppercase());
-            }
-        }
-
-        Ok(context
-            .issuing_bank
-            .clone()
-            .unwrap_or_else(|| "UNKNOWN".to_string())
-            .to_uppercase())
-    }
-
     async fn resolve_experiment(
         &self,
         req: &CreatePaymentRequest,
         method: &str,
         amount_bucket: &str,
+        card: &CardAttributes,
     ) -> anyhow::Result<Option<ResolvedExperiment>> {
         let active = self.experiments_repo.get_active_with_filters().await?;
         let input = ExperimentMatchInput {
@@ -672,65 +922,208 @@ impl PaymentService {
             amount_minor: req.amount_minor,
             merchant_id: req.merchant_id.clone(),
             amount_bucket: amount_bucket.to_string(),
+            card_network: card.card_network.clone(),
+            card_type: card.card_type.clone(),
         };
 
         for (exp, filter) in active {
-            if !experiment_matches(&filter, &input) {
+            if !experiment_matches(&filter, &input) || !is_exposed(&req.customer_id, exp.experiment_id, exp.exposure_pct) {
                 continue;
             }
 
-            let assignment = assign_variant(&req.customer_id, exp.experiment_id, exp.traffic_control_pct);
+            let assignment = assign_variant(&req.customer_id, exp.experiment_id, &exp.variants);
             self.experiments_repo
                 .upsert_assignment(exp.experiment_id, &req.customer_id, &assignment.variant, assignment.bucket)
                 .await?;
 
-            let forced_gateway = if assignment.variant == "treatment" {
-                Some(exp.treatment_gateway.clone())
-            } else {
-                None
+            let Some(variant) = exp.variants.into_iter().find(|v| v.variant_name == assignment.variant) else {
+                continue;
             };
-
             return Ok(Some(ResolvedExperiment {
                 experiment_id: exp.experiment_id,
-                variant: assignment.variant,
-                forced_gateway,
+                variant,
             }));
         }
 
         Ok(None)
     }
 
+    async fn read_routing_state(
+        &self,
+        method: &str,
+        issuing_bank: &str,
+        signal_blend: &SignalBlend,
+        gateway_ids: &[String],
+        volume_ids: &[String],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> anyhow::Result<RoutingReads> {
+        let metric_keys = metric_keys(gateway_ids, method, issuing_bank, signal_blend);
+        let view_keys = CircuitStoreRedis::view_keys(method, gateway_ids);
+        let usage_keys = VolumeStoreRedis::usage_keys(volume_ids, now);
+        let keys: Vec<&String> = metric_keys.iter().chain(&view_keys).chain(&usage_keys).collect();
+        let values: Vec<Option<String>> = if keys.is_empty() {
+            Vec::new()
+        } else {
+            let mut conn = self.metrics_hot_store.redis.conn().await?;
+            redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?
+        };
+        if values.len() != keys.len() {
+            anyhow::bail!("MGET returned {} values for {} keys", values.len(), keys.len());
+        }
+
+        let (metric_values, rest) = values.split_at(metric_keys.len());
+        let (view_values, usage_values) = rest.split_at(view_keys.len());
+        Ok(RoutingReads {
+            metrics: blend_gateway_metrics(gateway_ids, signal_blend, &parse_metrics(metric_values)),
+            circuit_views: CircuitStoreRedis::views_from_values(method, gateway_ids, view_values)?,
+            usages: VolumeStoreRedis::usages_from_values(volume_ids, usage_values),
+        })
+    }
+
     async fn apply_bandit_if_enabled(
         &self,
-        segment: &str,
+        config: &RoutingConfigSnapshot,
+        segments: &[BanditSegment],
         ranked: Vec<RankedGateway>,
-    ) -> anyhow::Result<Vec<RankedGateway>> {
-        if !self.bandit_repo.is_enabled(segment).await? {
-            return Ok(ranked);
-        }
+        force: bool,
+    ) -> anyhow::Result<(Vec<RankedGateway>, Option<BanditSelection>)> {
+        let enabled = segments.iter().find(|s| config.bandit_enabled(s));
+        let Some(segment) = enabled.or_else(|| segments.first().filter(|_| force)) else {
+            return Ok((ranked, None));
+        };
 
         let gateway_ids: Vec<String> = ranked.iter().map(|r| r.gateway_id.clone()).collect();
-        let sampled = self.bandit_repo.sample_scores(segment, &gateway_ids).await?;
-        let mut score_map = std::collections::HashMap::new();
-        for (gateway, score) in sampled {
-            score_map.insert(gateway, score);
+        let policy = config.bandit_policy(segment);
+        let chain = config.bandit_definitions.fallback_chain(&segment.definition_id);
+        let mut state_segment = segment.clone();
+        let mut arms = self.bandit_repo.posteriors(&state_segment, &gateway_ids).await?;
+        for (definition, coarser) in chain.iter().zip(chain.iter().skip(1)) {
+            if observations(&arms) >= definition.min_observations {
+                break;
+            }
+            let Some(next) = segments.iter().find(|s| s.definition_id == coarser.definition_id) else {
+                break;
+            };
+            state_segment = next.clone();
+            arms = self.bandit_repo.posteriors(&state_segment, &gateway_ids).await?;
         }
 
-        let mut reordered = ranked;
-        reordered.sort_by(|a, b| {
-            let sa = score_map.get(&a.gateway_id).copied().unwrap_or(0.0);
-            let sb = score_map.get(&b.gateway_id).copied().unwrap_or(0.0);
-            sb.partial_cmp(&sa).unwrap_or(std::cmp::Ordering::Equal)
-        });
-        Ok(reordered)
+        let mut eligible: Vec<(String, f64, f64)> = arms
+            .iter()
+            .filter(|(_, alpha, beta)| passes_floor(*alpha, *beta, policy.safety.min_success_rate))
+            .cloned()
+            .collect();
+        if eligible.is_empty() {
+            eligible = arms;
+        }
+        let excluded: Vec<String> = gateway_ids
+            .iter()
+            .filter(|g| !eligible.iter().any(|(e, _, _)| e == *g))
+            .cloned()
+            .collect();
+        let ranked: Vec<RankedGateway> = ranked
+            .into_iter()
+            .filter(|r| !excluded.contains(&r.gateway_id))
+            .collect();
+
+        let mut score_map = std::collections::HashMap::new();
+        let propensities = match policy.algorithm {
+            BanditAlgorithm::Thompson => {
+                for (gateway, alpha, beta) in &eligible {
+                    score_map.insert(gateway.clone(), thompson::sample(*alpha, *beta));
+                }
+                thompson::selection_probabilities(&eligible, thompson::PROPENSITY_DRAWS, &mut rand::thread_rng())
+            }
+            BanditAlgorithm::LinUcb => {
+                let models = self.bandit_repo.linear_models(&state_segment, &gateway_ids).await?;
+                for r in &ranked {
+                    let x = bandit_features(&r.breakdown);
+                    let score = match models.get(&r.gateway_id) {
+                        Some(model) => model.ucb(&x, policy.exploration),
+                        None => LinearModel::new(FEATURE_DIM).ucb(&x, policy.exploration),
+                    };
+                    score_map.insert(r.gateway_id.clone(), score);
+                }
+                let scores: Vec<(String, f64)> = score_map.iter().map(|(g, s)| (g.clone(), *s)).collect();
+                greedy_probabilities(&scores)
+            }
+        };
+
+        let Some(engine_top) = ranked.first().map(|r| r.gateway_id.clone()) else {
+            return Ok((ranked, None));
+        };
+        let share = policy.safety.max_exploration_share.unwrap_or(1.0);
+        let explored = rand::random::<f64>() < share;
+        let reordered = if explored {
+            let mut reordered = ranked;
+            reordered.sort_by(|a, b| {
+                let sa = score_map.get(&a.gateway_id).copied().unwrap_or(f64::NEG_INFINITY);
+                let sb = score_map.get(&b.gateway_id).copied().unwrap_or(f64::NEG_INFINITY);
+                sb.partial_cmp(&sa).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            reordered
+        } else {
+            ranked
+        };
+        let selection = BanditSelection {
+            definition_id: segment.definition_id.clone(),
+            segment: segment.key.clone(),
+            state_definition_id: (state_segment != *segment).then(|| state_segment.definition_id.clone()),
+            state_segment: (state_segment != *segment).then(|| state_segment.key.clone()),
+            algorithm: policy.algorithm,
+            chosen_gateway: reordered[0].gateway_id.clone(),
+            scores: score_map,
+            propensities: mix_propensities(&propensities, &engine_top, share),
+            explored,
+            excluded,
+        };
+        Ok((reordered, Some(selection)))
     }
 }
 
 #[derive(Debug, Clone)]
 struct ResolvedExperiment {
     experiment_id: uuid::Uuid,
-    variant: String,
-    forced_gateway: Option<String>,
+    variant: ExperimentVariant,
+}
+
+struct RoutingReads {
+    metrics: std::collections::HashMap<String, GatewayMetricInput>,
+    circuit_views: std::collections::HashMap<String, CircuitView>,
+    usages: std::collections::HashMap<String, VolumeUsage>,
+}
+
+async fn record_bandit_attempts(
+    bandit_repo: &BanditRepo,
+    config: &RoutingConfigSnapshot,
+    segment: &BanditSegment,
+    attempts: &[(RankedGateway, bool, i32)],
+    amount_minor: i64,
+    expected_fees: &std::collections::HashMap<String, Option<i64>>,
+) {
+    let policy = config.bandit_policy(segment);
+    for (ranked, success, latency_ms) in attempts {
+        let value = bandit_reward(
+            &policy.reward,
+            &AttemptReward {
+                success: *success,
+                latency_ms: *latency_ms,
+                expected_fee_minor: expected_fees.get(&ranked.gateway_id).copied().flatten(),
+                amount_minor,
+            },
+        );
+        if let Err(e) = bandit_repo.update_outcome(segment, &ranked.gateway_id, value).await {
+            tracing::warn!("bandit update failed for {}: {}", ranked.gateway_id, e);
+        }
+        if policy.algorithm == BanditAlgorithm::LinUcb {
+            if let Err(e) = bandit_repo
+                .update_linear(segment, &ranked.gateway_id, &bandit_features(&ranked.breakdown), value)
+                .await
+            {
+                tracing::warn!("linear bandit update failed for {}: {}", ranked.gateway_id, e);
+            }
+        }
+    }
 }
 
 fn apply_experiment_override(mut ranked: Vec<RankedGateway>, forced_gateway: Option<String>) -> Vec<RankedGateway> {
@@ -743,6 +1136,19 @@ fn apply_experiment_override(mut ranked: Vec<RankedGateway>, forced_gateway: Opt
     ranked
 }
 
+fn maintenance_summary(dropped: &[DroppedGateway]) -> String {
+    let ids: Vec<&str> = dropped
+        .iter()
+        .filter(|d| d.maintenance_window_id.is_some())
+        .map(|d| d.gateway_id.as_str())
+        .collect();
+    if ids.is_empty() {
+        "none".to_string()
+    } else {
+        ids.join("|")
+    }
+}
+
 fn hour_floor(ts: chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::Utc> {
     let secs = ts.timestamp() - (ts.minute() as i64 * 60) - ts.second() as i64;
     chrono::DateTime::<chrono::Utc>::from_timestamp(secs, 0).unwrap_or(ts)
@@ -755,17 +1161,46 @@ fn find_gateway_config(candidates: &[GatewayCandidate], gateway_id: &str) -> Opt
         .map(|c| c.gateway.clone())
 }
 
-fn to_weights(w: &crate::repo::scoring_config_repo::ScoringWeights) -> ScoreWeights {
-    ScoreWeights {
-        success_rate_weight: w.success_rate_weight,
-        latency_weight: w.latency_weight,
-        method_affinity_weight: w.method_affinity_weight,
-        bank_affinity_weight: w.bank_affinity_weight,
-        amount_fit_weight: w.amount_fit_weight,
-        time_weight: w.time_weight,
+fn check_circuit(
+    view: Option<&CircuitView>,
+    thresholds: &crate::repo::circuit_breaker_config_repo::CircuitThresholds,
+    gateway_id: &str,
+    method: &str,
+) -> (bool, bool, String, String) {
+    match view.and_then(|v| v.override_state.as_deref()) {
+        Some("FORCE_OPEN") => return (false, false, "OPEN".to_string(), "manual_force_open".to_string()),
+        Some("FORCE_CLOSED") => return (true, false, "CLOSED".to_string(), "manual_force_closed".to_string()),
+        _ => {}
+    }
+
+    let snapshot = view
+        .map(|v| v.snapshot.clone())
+        .unwrap_or_else(|| crate::circuit::state::CircuitSnapshot::new(gateway_id, method));
+    match pre_call_decision(&snapshot, thresholds, chrono::Utc::now()) {
+        CircuitDecision::Allow => (true, false, format!("{:?}", snapshot.state).to_uppercase(), "allow".to_string()),
+        CircuitDecision::Probe => (true, true, "HALF_OPEN".to_string(), "probe".to_string()),
+        CircuitDecision::Reject(reason) => (false, false, format!("{:?}", snapshot.state).to_uppercase(), reason),
     }
 }
 
+fn resolve_issuing_bank(
+    config: &RoutingConfigSnapshot,
+    req: &CreatePaymentRequest,
+    context: &crate::domain::context::PaymentContext,
+) -> String {
+    let alias_type = match &req.instrument {
+        PaymentInstrument::Card(card) => {
+            if let Some(bank) = config.bank_from_bin(&card.number) {
+                return config.canonical_issuer(Some(AliasType::BinBank), Some(bank));
+            }
+            None
+        }
+        PaymentInstrument::Upi(_) => Some(AliasType::UpiHandle),
+        PaymentInstrument::Netbanking(_) => Some(AliasType::NetbankingCode),
+    };
+    config.canonical_issuer(alias_type, context.issuing_bank.as_deref())
+}
+
 fn validate_request(req: &CreatePaymentRequest) -> Result<(), (axum::http::StatusCode, ErrorEnvelope)> {
     if req.amount_minor <= 0 {
         return Err((
@@ -820,3 +1255,9 @@ fn internal(e: anyhow::Error) -> (axum::http::StatusCode, ErrorEnvelope) {
         err("INTERNAL_ERROR", &e.to_string()),
     )
 }
+
+fn observations(arms: &[(String, f64, f64)]) -> f64 {
+    arms.iter()
+        .map(|(_, alpha, beta)| (alpha - PRIOR_ALPHA).max(0.0) + (beta - PRIOR_BETA).max(0.0))
+        .sum()
+}
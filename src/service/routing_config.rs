@@ -0,0 +1,410 @@
+use crate::bandit::policy::BanditPolicyConfig;
+use crate::bandit::segments::{BanditSegment, SegmentCatalog};
+use crate::bins::index::{BinIndex, BinRecord, CardAttributes};
+use crate::domain::routing_rule::{RoutingRule, DEFAULT_RULE_SET};
+use crate::gateways::GatewayConfig;
+use crate::metrics::amount_bucket::AmountBucketer;
+use crate::repo::amount_buckets_repo::AmountBucketsRepo;
+use crate::repo::bandit_repo::BanditRepo;
+use crate::repo::bins_repo::BinsRepo;
+use crate::repo::circuit_breaker_config_repo::{CircuitBreakerConfigRepo, CircuitThresholds};
+use crate::repo::error_classification_repo::{ErrorClass, ErrorClassificationRepo};
+use crate::repo::gateways_repo::GatewaysRepo;
+use crate::repo::issuers_repo::IssuersRepo;
+use crate::repo::maintenance_windows_repo::MaintenanceWindowsRepo;
+use crate::repo::retry_policy_repo::{RetryPolicy, RetryPolicyRepo};
+use crate::repo::routing_rules_repo::RoutingRulesRepo;
+use crate::repo::scoring_config_repo::{ScoringConfigRepo, TimePenalty};
+use crate::repo::scoring_profiles_repo::ScoringProfilesRepo;
+use crate::repo::volume_targets_repo::VolumeTargetsRepo;
+use crate::scoring::blend::SignalBlend;
+use crate::scoring::cost::{card_network, FeeSlab};
+use crate::scoring::issuers::{issuer_affinity, AliasType, IssuerRegistry};
+use crate::scoring::profiles::{select_assignment, ProfileAssignment, ResolvedProfile, DEFAULT_PROFILE_ID};
+use crate::scoring::schedule::{active_window, local_hour_and_day, MaintenanceWindow};
+use crate::scoring::types::ScoreWeights;
+use crate::volume::pacing::VolumeTarget;
+use anyhow::Result;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+pub const CONFIG_CHANNEL: &str = "routing_config_changed";
+const DEFAULT_AFFINITY: f64 = 0.7;
+const DEFAULT_AMOUNT_FIT: f64 = 0.7;
+const DEFAULT_TIME_MULTIPLIER: f64 = 1.0;
+
+#[derive(Debug, Clone, Default)]
+pub struct RoutingConfigSnapshot {
+    pub version: u64,
+    pub loaded_at: chrono::DateTime<chrono::Utc>,
+    pub gateways: Vec<GatewayConfig>,
+    pub default_weights: ScoreWeights,
+    pub profiles: HashMap<String, ResolvedProfile>,
+    pub profile_assignments: Vec<ProfileAssignment>,
+    pub method_affinity: HashMap<(String, String), f64>,
+    pub amount_buckets: AmountBucketer,
+    pub amount_fit: HashMap<(String, String), f64>,
+    pub time_penalties: Vec<TimePenalty>,
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    pub bin_banks: HashMap<String, String>,
+    pub bins: BinIndex,
+    pub issuers: IssuerRegistry,
+    pub issuer_affinity: HashMap<(String, String), f64>,
+    pub fee_slabs: HashMap<(String, String), Vec<FeeSlab>>,
+    pub routing_rules: Vec<RoutingRule>,
+    pub volume_targets: Vec<VolumeTarget>,
+    pub retry_policies: HashMap<String, RetryPolicy>,
+    pub error_classes: HashMap<(String, String), ErrorClass>,
+    pub circuit_thresholds: HashMap<(String, String), CircuitThresholds>,
+    pub bandit_segments: HashSet<BanditSegment>,
+    pub bandit_policies: HashMap<BanditSegment, BanditPolicyConfig>,
+    pub bandit_definitions: SegmentCatalog,
+}
+
+impl RoutingConfigSnapshot {
+    pub fn enabled_gateways(&self, method: &str) -> Vec<GatewayConfig> {
+        self.gateways
+            .iter()
+            .filter(|g| g.is_enabled && g.supported_methods.iter().any(|m| m == method))
+            .cloned()
+            .collect()
+    }
+
+    pub fn resolve_profile(&self, merchant_id: &str, payment_method: &str, amount_bucket: &str) -> Option<ResolvedProfile> {
+        let profile_id = select_assignment(&self.profile_assignments, merchant_id, payment_method, amount_bucket)
+            .map(|a| a.profile_id.as_str())
+            .unwrap_or(DEFAULT_PROFILE_ID);
+        self.profiles.get(profile_id).cloned()
+    }
+
+    pub fn weights_for(&self, profile: Option<&ResolvedProfile>) -> ScoreWeights {
+        profile
+            .map(|p| p.weights.clone())
+            .unwrap_or_else(|| self.default_weights.clone())
+    }
+
+    pub fn blend_for(&self, profile: Option<&ResolvedProfile>) -> SignalBlend {
+        profile.map(|p| p.signal_blend.clone()).unwrap_or_default()
+    }
+
+    pub fn method_affinity(&self, gateway_id: &str, method: &str) -> f64 {
+        self.method_affinity
+            .get(&(gateway_id.to_string(), method.to_string()))
+            .copied()
+            .unwrap_or(DEFAULT_AFFINITY)
+    }
+
+    pub fn amount_bucket(&self, currency: &str, amount_minor: i64) -> String {
+        self.amount_buckets.bucket(currency, amount_minor)
+    }
+
+    pub fn amount_fit(&self, gateway_id: &str, amount_bucket: &str) -> f64 {
+        self.amount_fit
+            .get(&(gateway_id.to_string(), amount_bucket.to_string()))
+            .copied()
+            .unwrap_or(DEFAULT_AMOUNT_FIT)
+    }
+
+    pub fn time_multiplier(&self, gateway_id: &str, now: chrono::DateTime<chrono::Utc>) -> f64 {
+        let matching = self.time_penalties.iter().filter(|p| p.gateway_id == gateway_id).filter(|p| {
+            let (hour, day) = local_hour_and_day(now, p.timezone);
+            p.hour_of_day == hour && p.day_of_month.is_none_or(|d| d == day)
+        });
+        let mut best: Option<&TimePenalty> = None;
+        for penalty in matching {
+            if best.is_none_or(|b| b.day_of_month.is_none() && penalty.day_of_month.is_some()) {
+                best = Some(penalty);
+            }
+        }
+        best.map(|p| p.multiplier).unwrap_or(DEFAULT_TIME_MULTIPLIER)
+    }
+
+    pub fn maintenance_window(
+        &self,
+        gateway_id: &str,
+        method: &str,
+        issuer: &str,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Option<&MaintenanceWindow> {
+        active_window(&self.maintenance_windows, gateway_id, method, issuer, now)
+    }
+
+    pub fn bin_info(&self, card_number: &str) -> Option<&BinRecord> {
+        self.bins.lookup(card_number)
+    }
+
+    pub fn card_attributes(&self, card_number: &str) -> CardAttributes {
+        match self.bin_info(card_number) {
+            Some(bin) => CardAttributes {
+                card_network: Some(bin.card_network.clone()),
+                card_type: Some(bin.card_type.as_str().to_string()),
+            },
+            None => CardAttributes {
+                card_network: card_network(card_number).map(str::to_string),
+                card_type: None,
+            },
+        }
+    }
+
+    pub fn bank_from_bin(&self, card_number: &str) -> Option<&str> {
+        if let Some(issuer) = self.bin_info(card_number).and_then(|b| b.issuer_id.as_deref()) {
+            return Some(issuer);
+        }
+        let prefix = card_number.get(..6)?;
+        self.bin_banks.get(prefix).map(String::as_str)
+    }
+
+    pub fn canonical_issuer(&self, alias_type: Option<AliasType>, raw: Option<&str>) -> String {
+        self.issuers.canonical(alias_type, raw)
+    }
+
+    pub fn issuer_affinity(&self, gateway_id: &str, issuer: &str) -> f64 {
+        issuer_affinity(&self.issuer_affinity, gateway_id, issuer)
+    }
+
+    pub fn fee_slabs(&self, gateway_id: &str, method: &str) -> &[FeeSlab] {
+        self.fee_slabs
+            .get(&(gateway_id.to_string(), method.to_string()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn has_rule_set(&self, rule_set: &str) -> bool {
+        rule_set == DEFAULT_RULE_SET || self.routing_rules.iter().any(|r| r.rule_set == rule_set)
+    }
+
+    pub fn routing_rules_for(&self, rule_set: &str) -> Vec<RoutingRule> {
+        self.routing_rules
+            .iter()
+            .filter(|r| r.rule_set == rule_set)
+            .cloned()
+            .collect()
+    }
+
+    pub fn retry_policy(&self, merchant_id: &str) -> RetryPolicy {
+        self.retry_policies
+            .get(merchant_id)
+            .cloned()
+            .unwrap_or_else(|| RetryPolicy::default_for(merchant_id))
+    }
+
+    pub fn classify_error(&self, gateway_id: &str, error_code: &str) -> ErrorClass {
+        self.error_classes
+            .get(&(gateway_id.to_string(), error_code.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn circuit_thresholds(&self, gateway_id: &str, method: &str) -> CircuitThresholds {
+        self.circuit_thresholds
+            .get(&(gateway_id.to_string(), method.to_string()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn bandit_enabled(&self, segment: &BanditSegment) -> bool {
+        self.bandit_segments.contains(segment)
+    }
+
+    pub fn tracked_bandit_segments(&self, segments: &[BanditSegment], selected: Option<&BanditSegment>) -> Vec<BanditSegment> {
+        let mut tracked: Vec<BanditSegment> = Vec::new();
+        for segment in segments.iter().filter(|s| self.bandit_enabled(s) || selected == Some(*s)) {
+            for definition in self.bandit_definitions.fallback_chain(&segment.definition_id) {
+                let Some(member) = segments.iter().find(|s| s.definition_id == definition.definition_id) else {
+                    continue;
+                };
+                if !tracked.contains(member) {
+                    tracked.push(member.clone());
+                }
+            }
+        }
+        tracked
+    }
+
+    pub fn bandit_policy(&self, segment: &BanditSegment) -> BanditPolicyConfig {
+        self.bandit_policies
+            .get(segment)
+            .cloned()
+            .unwrap_or_else(|| BanditPolicyConfig::for_segment(segment))
+    }
+}
+
+#[derive(Clone)]
+pub struct RoutingConfigStore {
+    inner: Arc<RwLock<Arc<RoutingConfigSnapshot>>>,
+}
+
+impl RoutingConfigStore {
+    pub fn new(snapshot: RoutingConfigSnapshot) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(Arc::new(snapshot))),
+        }
+    }
+
+    pub fn current(&self) -> Arc<RoutingConfigSnapshot> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn swap(&self, mut snapshot: RoutingConfigSnapshot) -> u64 {
+        let mut guard = self.inner.write().unwrap_or_else(|e| e.into_inner());
+        snapshot.version = guard.version + 1;
+        let version = snapshot.version;
+        *guard = Arc::new(snapshot);
+        version
+    }
+}
+
+#[derive(Clone)]
+pub struct RoutingConfigLoader {
+    pub gateways_repo: GatewaysRepo,
+    pub scoring_config_repo: ScoringConfigRepo,
+    pub scoring_profiles_repo: ScoringProfilesRepo,
+    pub routing_rules_repo: RoutingRulesRepo,
+    pub volume_targets_repo: VolumeTargetsRepo,
+    pub retry_policy_repo: RetryPolicyRepo,
+    pub error_classification_repo: ErrorClassificationRepo,
+    pub circuit_breaker_config_repo: CircuitBreakerConfigRepo,
+    pub bandit_repo: BanditRepo,
+    pub issuers_repo: IssuersRepo,
+    pub bins_repo: BinsRepo,
+    pub amount_buckets_repo: AmountBucketsRepo,
+    pub maintenance_windows_repo: MaintenanceWindowsRepo,
+}
+
+impl RoutingConfigLoader {
+    pub async fn load(&self) -> Result<RoutingConfigSnapshot> {
+        let (gateways, weights, profiles, profile_assignments, method_affinity, amount_fit, time_penalties, bin_banks) = tokio::try_join!(
+            self.gateways_repo.list_all(),
+            self.scoring_config_repo.load_weights(),
+            self.scoring_profiles_repo.current_all(),
+            self.scoring_profiles_repo.list_assignments(),
+            self.scoring_config_repo.all_method_affinity(),
+            self.scoring_config_repo.all_amount_fit(),
+            self.scoring_config_repo.all_time_penalties(),
+            self.scoring_config_repo.bin_bank_map(),
+        )?;
+        let (
+            fee_slabs,
+            routing_rules,
+            volume_targets,
+            retry_policies,
+            error_classes,
+            circuit_thresholds,
+            bandit_segments,
+            bandit_policies,
+            bandit_definitions,
+            issuer_aliases,
+            issuer_affinity,
+            bins,
+            amount_buckets,
+            maintenance_windows,
+        ) = tokio::try_join!(
+            self.scoring_config_repo.all_fee_slabs(),
+            self.routing_rules_repo.list_enabled(),
+            self.volume_targets_repo.list_enabled(),
+            self.retry_policy_repo.list_all(),
+            self.error_classification_repo.list_all(),
+            self.circuit_breaker_config_repo.list_all(),
+            self.bandit_repo.enabled_segments(),
+            self.bandit_repo.list_policies(),
+            self.bandit_repo.list_definitions(),
+            self.issuers_repo.list_aliases(),
+            self.issuers_repo.all_affinity(),
+            self.bins_repo.list_all(),
+            self.amount_buckets_repo.list_all(),
+            self.maintenance_windows_repo.list_current(),
+        )?;
+
+        let mut slabs_by_key: HashMap<(String, String), Vec<FeeSlab>> = HashMap::new();
+        for slab in fee_slabs {
+            slabs_by_key
+                .entry((slab.gateway_id.clone(), slab.payment_method.clone()))
+                .or_default()
+                .push(slab);
+        }
+
+        Ok(RoutingConfigSnapshot {
+            version: 0,
+            loaded_at: chrono::Utc::now(),
+            gateways,
+            default_weights: ScoreWeights {
+                success_rate_weight: weights.success_rate_weight,
+                latency_weight: weights.latency_weight,
+                method_affinity_weight: weights.method_affinity_weight,
+                bank_affinity_weight: weights.bank_affinity_weight,
+                amount_fit_weight: weights.amount_fit_weight,
+                time_weight: weights.time_weight,
+                cost_weight: weights.cost_weight,
+            },
+            profiles: profiles.into_iter().map(|p| (p.profile_id.clone(), p)).collect(),
+            profile_assignments,
+            method_affinity,
+            amount_buckets: AmountBucketer::new(amount_buckets),
+            amount_fit,
+            time_penalties,
+            maintenance_windows,
+            bin_banks,
+            bins: BinIndex::build(bins),
+            issuers: IssuerRegistry::new(issuer_aliases),
+            issuer_affinity,
+            fee_slabs: slabs_by_key,
+            routing_rules,
+            volume_targets,
+            retry_policies: retry_policies.into_iter().map(|p| (p.merchant_id.clone(), p)).collect(),
+            error_classes,
+            circuit_thresholds,
+            bandit_segments: bandit_segments.into_iter().collect(),
+            bandit_policies: bandit_policies.into_iter().map(|p| (p.bandit_segment(), p)).collect(),
+            bandit_definitions: SegmentCatalog::new(bandit_definitions),
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct RoutingConfigReloader {
+    pub pool: PgPool,
+    pub loader: RoutingConfigLoader,
+    pub store: RoutingConfigStore,
+    pub fallback_interval: std::time::Duration,
+}
+
+impl RoutingConfigReloader {
+    pub async fn run(self) {
+        loop {
+            if let Err(err) = self.listen().await {
+                tracing::error!("routing config listener error: {}", err);
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        }
+    }
+
+    async fn listen(&self) -> Result<()> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(CONFIG_CHANNEL).await?;
+        self.reload("listener_connected").await;
+
+        loop {
+            tokio::select! {
+                notification = listener.recv() => {
+                    let notification = notification?;
+                    self.reload(notification.payload()).await;
+                }
+                _ = tokio::time::sleep(self.fallback_interval) => {
+                    self.reload("fallback_interval").await;
+                }
+            }
+        }
+    }
+
+    async fn reload(&self, cause: &str) {
+        match self.loader.load().await {
+            Ok(snapshot) => {
+                let version = self.store.swap(snapshot);
+                tracing::info!("routing config snapshot v{} loaded ({})", version, cause);
+            }
+            Err(err) => tracing::error!("routing config reload failed ({}): {}", cause, err),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use crate::repo::volume_targets_repo::VolumeTargetsRepo;
+use crate::volume::pacing::{periods, usage_delta, VolumeUsage};
+use crate::volume::store_redis::VolumeStoreRedis;
+use anyhow::Result;
+
+#[derive(Clone)]
+pub struct VolumeReconciler {
+    pub volume_targets_repo: VolumeTargetsRepo,
+    pub volume_store: VolumeStoreRedis,
+    pub interval: std::time::Duration,
+}
+
+impl VolumeReconciler {
+    pub async fn run(self) {
+        loop {
+            if let Err(err) = self.tick().await {
+                tracing::error!("volume reconciler error: {}", err);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    async fn tick(&self) -> Result<()> {
+        let targets = self.volume_targets_repo.list().await?;
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now();
+        let p = periods(now);
+        let gateway_ids: Vec<String> = targets.iter().map(|t| t.gateway_id.clone()).collect();
+        let observed = self.volume_store.read_usages(&gateway_ids, now).await?;
+        let settled = self
+            .volume_targets_repo
+            .settled_volume(p.day_start, p.month_start)
+            .await?;
+        for gateway_id in gateway_ids {
+            let delta = usage_delta(
+                settled.get(&gateway_id).copied().unwrap_or_default(),
+                observed.get(&gateway_id).copied().unwrap_or_default(),
+            );
+            if delta != VolumeUsage::default() {
+                self.volume_store.adjust(&gateway_id, delta, now).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+pub mod pacing;
+pub mod store_redis;
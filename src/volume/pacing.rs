@@ -0,0 +1,160 @@
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Offset, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const SETTLEMENT_OFFSET_SECS: i32 = 5 * 3600 + 30 * 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeTarget {
+    pub gateway_id: String,
+    pub monthly_commit_minor: Option<i64>,
+    pub daily_cap_minor: Option<i64>,
+    pub monthly_cap_minor: Option<i64>,
+    pub pace_boost: f64,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VolumeUsage {
+    pub daily_minor: i64,
+    pub monthly_minor: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct VolumePeriods {
+    pub day: String,
+    pub month: String,
+    pub day_start: DateTime<Utc>,
+    pub month_start: DateTime<Utc>,
+    pub month_end: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VolumeProgress {
+    pub gateway_id: String,
+    pub day: String,
+    pub month: String,
+    pub daily_minor: i64,
+    pub daily_cap_minor: Option<i64>,
+    pub daily_remaining_minor: Option<i64>,
+    pub monthly_minor: i64,
+    pub monthly_cap_minor: Option<i64>,
+    pub monthly_commit_minor: Option<i64>,
+    pub commit_pct: Option<f64>,
+    pub expected_pct: f64,
+    pub pace_ratio: Option<f64>,
+    pub capped: bool,
+    pub boost: f64,
+}
+
+fn settlement_tz() -> FixedOffset {
+    FixedOffset::east_opt(SETTLEMENT_OFFSET_SECS).unwrap_or(Utc.fix())
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Utc> {
+    let naive = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    settlement_tz()
+        .from_local_datetime(&naive)
+        .single()
+        .map(|d| d.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&naive))
+}
+
+pub fn periods(now: DateTime<Utc>) -> VolumePeriods {
+    let local = now.with_timezone(&settlement_tz()).date_naive();
+    let first = local.with_day(1).unwrap_or(local);
+    let next_month = if first.month() == 12 {
+        NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+    } else {
+        NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+    }
+    .unwrap_or(first);
+
+    VolumePeriods {
+        day: local.format("%Y-%m-%d").to_string(),
+        month: local.format("%Y-%m").to_string(),
+        day_start: local_midnight(local),
+        month_start: local_midnight(first),
+        month_end: local_midnight(next_month),
+    }
+}
+
+pub fn month_elapsed_fraction(now: DateTime<Utc>) -> f64 {
+    let p = periods(now);
+    let total = (p.month_end - p.month_start).num_seconds() as f64;
+    if total <= 0.0 {
+        return 1.0;
+    }
+    ((now - p.month_start).num_seconds() as f64 / total).clamp(0.0, 1.0)
+}
+
+pub fn has_cap(target: &VolumeTarget) -> bool {
+    target.daily_cap_minor.is_some() || target.monthly_cap_minor.is_some()
+}
+
+pub fn usage_delta(settled: VolumeUsage, observed: VolumeUsage) -> VolumeUsage {
+    VolumeUsage {
+        daily_minor: settled.daily_minor - observed.daily_minor,
+        monthly_minor: settled.monthly_minor - observed.monthly_minor,
+    }
+}
+
+pub fn is_capped(target: &VolumeTarget, usage: &VolumeUsage, amount_minor: i64) -> bool {
+    let over = |cap: Option<i64>, used: i64| cap.is_some_and(|cap| used + amount_minor > cap);
+    over(target.daily_cap_minor, usage.daily_minor) || over(target.monthly_cap_minor, usage.monthly_minor)
+}
+
+pub fn pace_ratio(target: &VolumeTarget, usage: &VolumeUsage, now: DateTime<Utc>) -> Option<f64> {
+    let commit = target.monthly_commit_minor.filter(|c| *c > 0)?;
+    let expected = commit as f64 * month_elapsed_fraction(now);
+    if expected <= 0.0 {
+        return None;
+    }
+    Some(usage.monthly_minor as f64 / expected)
+}
+
+pub fn pace_boost(target: &VolumeTarget, usage: &VolumeUsage, now: DateTime<Utc>) -> f64 {
+    match pace_ratio(target, usage, now) {
+        Some(ratio) if ratio < 1.0 => target.pace_boost * (1.0 - ratio).clamp(0.0, 1.0),
+        _ => 0.0,
+    }
+}
+
+pub fn progress(target: &VolumeTarget, usage: &VolumeUsage, now: DateTime<Utc>) -> VolumeProgress {
+    let p = periods(now);
+    VolumeProgress {
+        gateway_id: target.gateway_id.clone(),
+        day: p.day,
+        month: p.month,
+        daily_minor: usage.daily_minor,
+        daily_cap_minor: target.daily_cap_minor,
+        daily_remaining_minor: target.daily_cap_minor.map(|cap| (cap - usage.daily_minor).max(0)),
+        monthly_minor: usage.monthly_minor,
+        monthly_cap_minor: target.monthly_cap_minor,
+        monthly_commit_minor: target.monthly_commit_minor,
+        commit_pct: target
+            .monthly_commit_minor
+            .filter(|c| *c > 0)
+            .map(|c| usage.monthly_minor as f64 / c as f64),
+        expected_pct: month_elapsed_fraction(now),
+        pace_ratio: pace_ratio(target, usage, now),
+        capped: target.daily_cap_minor.is_some_and(|c| usage.daily_minor >= c)
+            || target.monthly_cap_minor.is_some_and(|c| usage.monthly_minor >= c),
+        boost: if target.enabled { pace_boost(target, usage, now) } else { 0.0 },
+    }
+}
+
+pub fn validate_target(target: &VolumeTarget) -> Result<(), String> {
+    for (name, value) in [
+        ("monthly_commit_minor", target.monthly_commit_minor),
+        ("daily_cap_minor", target.daily_cap_minor),
+        ("monthly_cap_minor", target.monthly_cap_minor),
+    ] {
+        if value.is_some_and(|v| v < 0) {
+            return Err(format!("{} must be >= 0", name));
+        }
+    }
+    if !(0.0..=1.0).contains(&target.pace_boost) {
+        return Err("pace_boost must be between 0.0 and 1.0".to_string());
+    }
+    Ok(())
+}
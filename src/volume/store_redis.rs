@@ -0,0 +1,206 @@
+use crate::redis_conn::SharedRedis;
+use crate::volume::pacing::{periods, VolumeTarget, VolumeUsage};
+use anyhow::Result;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const DAY_TTL_SECS: i64 = 3 * 24 * 3600;
+const MONTH_TTL_SECS: i64 = 40 * 24 * 3600;
+
+const RESERVE_SCRIPT: &str = r#"
+local now_ms = tonumber(ARGV[1])
+local amount = tonumber(ARGV[2])
+local daily_cap = tonumber(ARGV[3])
+local monthly_cap = tonumber(ARGV[4])
+redis.call('ZREMRANGEBYSCORE', KEYS[3], '-inf', now_ms)
+local in_flight = 0
+for _, member in ipairs(redis.call('ZRANGE', KEYS[3], 0, -1)) do
+    in_flight = in_flight + tonumber(string.match(member, ':(%d+)$') or '0')
+end
+local daily = tonumber(redis.call('GET', KEYS[1]) or '0') + in_flight + amount
+local monthly = tonumber(redis.call('GET', KEYS[2]) or '0') + in_flight + amount
+if (daily_cap >= 0 and daily > daily_cap) or (monthly_cap >= 0 and monthly > monthly_cap) then
+    return 0
+end
+redis.call('ZADD', KEYS[3], ARGV[5], ARGV[6])
+redis.call('PEXPIRE', KEYS[3], ARGV[7])
+return 1
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VolumeLease {
+    pub gateway_id: String,
+    pub lease_id: String,
+    pub amount_minor: i64,
+}
+
+impl VolumeLease {
+    pub fn new(gateway_id: &str, amount_minor: i64) -> Self {
+        Self {
+            gateway_id: gateway_id.to_string(),
+            lease_id: Uuid::new_v4().to_string(),
+            amount_minor,
+        }
+    }
+
+    pub fn member(&self) -> String {
+        format!("{}:{}", self.lease_id, self.amount_minor)
+    }
+}
+
+#[derive(Clone)]
+pub struct VolumeStoreRedis {
+    pub redis: SharedRedis,
+}
+
+impl VolumeStoreRedis {
+    pub fn new(redis: SharedRedis) -> Self {
+        Self { redis }
+    }
+
+    fn day_key(gateway_id: &str, day: &str) -> String {
+        format!("volume:{}:day:{}", gateway_id.to_lowercase(), day)
+    }
+
+    fn month_key(gateway_id: &str, month: &str) -> String {
+        format!("volume:{}:month:{}", gateway_id.to_lowercase(), month)
+    }
+
+    fn in_flight_key(gateway_id: &str) -> String {
+        format!("volume:{}:inflight", gateway_id.to_lowercase())
+    }
+
+    pub async fn read_usages(
+        &self,
+        gateway_ids: &[String],
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<HashMap<String, VolumeUsage>> {
+        if gateway_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let keys = Self::usage_keys(gateway_ids, now);
+        let mut conn = self.redis.conn().await?;
+        let values: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+        Ok(Self::usages_from_values(gateway_ids, &values))
+    }
+
+    pub fn usage_keys(gateway_ids: &[String], now: chrono::DateTime<chrono::Utc>) -> Vec<String> {
+        let p = periods(now);
+        gateway_ids
+            .iter()
+            .flat_map(|g| [Self::day_key(g, &p.day), Self::month_key(g, &p.month)])
+            .collect()
+    }
+
+    pub fn usages_from_values(gateway_ids: &[String], values: &[Option<String>]) -> HashMap<String, VolumeUsage> {
+        let counter = |v: Option<&Option<String>>| {
+            v.and_then(|v| v.as_deref())
+                .and_then(|v| v.parse::<i64>().ok())
+                .unwrap_or(0)
+        };
+        gateway_ids
+            .iter()
+            .zip(values.chunks(2))
+            .map(|(g, v)| {
+                (
+                    g.clone(),
+                    VolumeUsage {
+                        daily_minor: counter(v.first()),
+                        monthly_minor: counter(v.get(1)),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub async fn reserve(
+        &self,
+        target: &VolumeTarget,
+        amount_minor: i64,
+        lease_ttl_ms: i64,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<VolumeLease>> {
+        let mut conn = self.redis.conn().await?;
+        let p = periods(now);
+        let lease = VolumeLease::new(&target.gateway_id, amount_minor);
+        let now_ms = now.timestamp_millis();
+        let reserved: i64 = redis::Script::new(RESERVE_SCRIPT)
+            .key(Self::day_key(&target.gateway_id, &p.day))
+            .key(Self::month_key(&target.gateway_id, &p.month))
+            .key(Self::in_flight_key(&target.gateway_id))
+            .arg(now_ms)
+            .arg(amount_minor)
+            .arg(target.daily_cap_minor.unwrap_or(-1))
+            .arg(target.monthly_cap_minor.unwrap_or(-1))
+            .arg(now_ms + lease_ttl_ms)
+            .arg(lease.member())
+            .arg(lease_ttl_ms)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok((reserved == 1).then_some(lease))
+    }
+
+    pub async fn commit(&self, lease: &VolumeLease, now: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        let mut conn = self.redis.conn().await?;
+        let p = periods(now);
+        let day_key = Self::day_key(&lease.gateway_id, &p.day);
+        let month_key = Self::month_key(&lease.gateway_id, &p.month);
+        let _: () = redis::pipe()
+            .atomic()
+            .zrem(Self::in_flight_key(&lease.gateway_id), lease.member())
+            .ignore()
+            .incr(&day_key, lease.amount_minor)
+            .ignore()
+            .expire(&day_key, DAY_TTL_SECS)
+            .ignore()
+            .incr(&month_key, lease.amount_minor)
+            .ignore()
+            .expire(&month_key, MONTH_TTL_SECS)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn release(&self, lease: &VolumeLease) -> Result<()> {
+        let mut conn = self.redis.conn().await?;
+        let _: i64 = redis::cmd("ZREM")
+            .arg(Self::in_flight_key(&lease.gateway_id))
+            .arg(lease.member())
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn record(&self, gateway_id: &str, amount_minor: i64, now: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        self.adjust(
+            gateway_id,
+            VolumeUsage {
+                daily_minor: amount_minor,
+                monthly_minor: amount_minor,
+            },
+            now,
+        )
+        .await
+    }
+
+    pub async fn adjust(&self, gateway_id: &str, delta: VolumeUsage, now: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        let mut conn = self.redis.conn().await?;
+        let p = periods(now);
+        let day_key = Self::day_key(gateway_id, &p.day);
+        let month_key = Self::month_key(gateway_id, &p.month);
+        let _: () = redis::pipe()
+            .atomic()
+            .incr(&day_key, delta.daily_minor)
+            .ignore()
+            .expire(&day_key, DAY_TTL_SECS)
+            .ignore()
+            .incr(&month_key, delta.monthly_minor)
+            .ignore()
+            .expire(&month_key, MONTH_TTL_SECS)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use payments_gateway::metrics::amount_bucket::{
+    default_buckets, plan_remap, removed_ids, validate_buckets, validate_unique_ids, AmountBucket, AmountBucketer,
+};
+use payments_gateway::service::routing_config::RoutingConfigSnapshot;
+use std::collections::HashMap;
+
+fn bucket(id: &str, min: i64, max: Option<i64>) -> AmountBucket {
+    AmountBucket {
+        bucket_id: id.to_string(),
+        min_amount_minor: min,
+        max_amount_minor: max,
+    }
+}
+
+fn ids(buckets: &[AmountBucket]) -> Vec<String> {
+    buckets.iter().map(|b| b.bucket_id.clone()).collect()
+}
+
+#[test]
+fn per_currency_buckets_fall_back_to_default_set() {
+    let mut by_currency = HashMap::new();
+    by_currency.insert(
+        "usd".to_string(),
+        vec![bucket("usd_gte_50", 5_000, None), bucket("usd_lt_50", 0, Some(5_000))],
+    );
+    let bucketer = AmountBucketer::new(by_currency);
+
+    assert_eq!(bucketer.currencies(), vec!["INR", "USD"]);
+    assert_eq!(bucketer.bucket("USD", 4_999), "usd_lt_50");
+    assert_eq!(bucketer.bucket("usd", 5_000), "usd_gte_50");
+    assert_eq!(bucketer.bucket("EUR", 60_000), "500_2000");
+    assert_eq!(bucketer.bucket("INR", 49_999), "lt_500");
+    assert_eq!(bucketer.bucket("INR", -1), "lt_500");
+
+    let snapshot = RoutingConfigSnapshot::default();
+    assert_eq!(snapshot.amount_bucket("INR", 1_000_000), "gt_10000");
+}
+
+#[test]
+fn validation_requires_contiguous_open_ended_sets() {
+    assert!(validate_buckets(&default_buckets()).is_ok());
+    assert!(validate_buckets(&[]).is_err());
+    assert!(validate_buckets(&[bucket("a", 10, None)]).is_err());
+    assert!(validate_buckets(&[bucket("a", 0, Some(100)), bucket("b", 150, None)]).is_err());
+    assert!(validate_buckets(&[bucket("a", 0, Some(100)), bucket("b", 100, Some(200))]).is_err());
+    assert!(validate_buckets(&[bucket("a", 0, None), bucket("b", 100, None)]).is_err());
+    assert!(validate_buckets(&[bucket("a", 0, Some(100)), bucket("a", 100, None)]).is_err());
+    assert!(validate_buckets(&[bucket("a:b", 0, None)]).is_err());
+}
+
+#[test]
+fn changed_boundaries_keep_ids_without_remap() {
+    let current = ids(&default_buckets());
+    let next = vec![
+        bucket("lt_500", 0, Some(40_000)),
+        bucket("500_2000", 40_000, Some(200_000)),
+        bucket("2000_10000", 200_000, Some(1_000_000)),
+        bucket("gt_10000", 1_000_000, None),
+    ];
+    let remap = HashMap::new();
+    assert_eq!(plan_remap(&current, &next, &remap).unwrap(), Vec::new());
+    assert!(removed_ids(&current, &next, &remap).is_empty());
+}
+
+#[test]
+fn bucket_ids_are_unique_across_currencies() {
+    let inr: HashMap<String, String> = ids(&default_buckets()).into_iter().map(|id| (id, "INR".to_string())).collect();
+    let usd = vec![bucket("usd_lt_10", 0, Some(1_000)), bucket("usd_gte_10", 1_000, None)];
+    assert!(validate_unique_ids(&usd, &inr).is_ok());
+
+    let reused = vec![bucket("lt_500", 0, Some(1_000)), bucket("usd_gte_10", 1_000, None)];
+    assert_eq!(
+        validate_unique_ids(&reused, &inr),
+        Err("bucket lt_500 is already defined for INR".to_string())
+    );
+}
+
+#[test]
+fn merged_buckets_need_a_remap_to_a_new_id() {
+    let current = ids(&default_buckets());
+    let next = vec![
+        bucket("lt_500", 0, Some(50_000)),
+        bucket("500_10000", 50_000, Some(1_000_000)),
+        bucket("gt_10000", 1_000_000, None),
+    ];
+
+    let empty = HashMap::new();
+    assert_eq!(removed_ids(&current, &next, &empty), vec!["500_2000", "2000_10000"]);
+
+    let mut remap = HashMap::new();
+    remap.insert("500_2000".to_string(), "500_10000".to_string());
+    remap.insert("2000_10000".to_string(), "500_10000".to_string());
+    assert!(removed_ids(&current, &next, &remap).is_empty());
+    assert_eq!(
+        plan_remap(&current, &next, &remap).unwrap(),
+        vec![
+            ("2000_10000".to_string(), "500_10000".to_string()),
+            ("500_2000".to_string(), "500_10000".to_string()),
+        ]
+    );
+
+    let mut bad_target = HashMap::new();
+    bad_target.insert("500_2000".to_string(), "missing".to_string());
+    assert!(plan_remap(&current, &next, &bad_target).is_err());
+
+    let mut kept_source = HashMap::new();
+    kept_source.insert("lt_500".to_string(), "500_10000".to_string());
+    assert!(plan_remap(&current, &next, &kept_source).is_err());
+}
@@ -0,0 +1,41 @@
+use payments_gateway::bandit::discount::{decay_factor, decayed, summarize, validate_half_life};
+
+#[test]
+fn decay_halves_evidence_each_half_life_and_keeps_the_prior() {
+    assert_eq!(decay_factor(0.0, Some(60.0)), 1.0);
+    assert!((decay_factor(60.0, Some(60.0)) - 0.5).abs() < 1e-12);
+    assert!((decay_factor(120.0, Some(60.0)) - 0.25).abs() < 1e-12);
+    assert_eq!(decay_factor(10_000.0, None), 1.0);
+    assert_eq!(decay_factor(-5.0, Some(60.0)), 1.0);
+
+    let (alpha, beta) = decayed(101.0, 21.0, 60.0, Some(60.0));
+    assert!((alpha - 51.0).abs() < 1e-9);
+    assert!((beta - 11.0).abs() < 1e-9);
+
+    let (alpha, beta) = decayed(101.0, 21.0, 1e9, Some(60.0));
+    assert!((alpha - 1.0).abs() < 1e-9 && (beta - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn decayed_posterior_widens_and_loses_sample_size() {
+    let fresh = summarize(901.0, 101.0);
+    let (alpha, beta) = decayed(901.0, 101.0, 3.0 * 1440.0, Some(1440.0));
+    let stale = summarize(alpha, beta);
+
+    assert!((fresh.effective_sample_size - 1000.0).abs() < 1e-9);
+    assert!((stale.effective_sample_size - 125.0).abs() < 1e-9);
+    assert!(stale.credible_width > fresh.credible_width * 2.0);
+    assert!((fresh.mean - stale.mean).abs() < 0.01);
+
+    let prior = summarize(1.0, 1.0);
+    assert_eq!(prior.effective_sample_size, 0.0);
+    assert!(prior.credible_width <= 1.0);
+}
+
+#[test]
+fn half_life_must_be_positive_when_set() {
+    assert!(validate_half_life(None).is_ok());
+    assert!(validate_half_life(Some(30.0)).is_ok());
+    assert!(validate_half_life(Some(0.0)).is_err());
+    assert!(validate_half_life(Some(f64::NAN)).is_err());
+}
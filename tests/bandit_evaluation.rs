@@ -0,0 +1,116 @@
+use payments_gateway::bandit::evaluation::{
+    evaluate, greedy_probabilities, logged_reward, BanditTarget, EvaluationEvent, EventCandidate, RewardModel,
+    ScoringEnginePolicy,
+};
+use payments_gateway::bandit::policy::BanditPolicyConfig;
+use payments_gateway::bandit::segments::{BanditSegment, DEFAULT_DEFINITION_ID};
+use payments_gateway::bandit::thompson::selection_probabilities;
+use payments_gateway::domain::routing_decision::BanditSelection;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+
+fn candidate(gateway_id: &str, engine_score: f64) -> EventCandidate {
+    EventCandidate {
+        gateway_id: gateway_id.to_string(),
+        engine_score,
+        features: vec![1.0, 0.9, 0.8, 0.5, 0.5, 0.5, 1.0, 0.5],
+    }
+}
+
+fn segment() -> BanditSegment {
+    BanditSegment::new(DEFAULT_DEFINITION_ID, "UPI:lt_500")
+}
+
+fn event(logged: &str, reward: f64) -> EvaluationEvent {
+    EvaluationEvent {
+        segment: segment(),
+        state_segment: segment(),
+        candidates: vec![candidate("a", 0.9), candidate("b", 0.7)],
+        logged_gateway: logged.to_string(),
+        propensity: 0.5,
+        reward,
+    }
+}
+
+fn logged_events() -> Vec<EvaluationEvent> {
+    let mut events = Vec::new();
+    for i in 0..100 {
+        events.push(event("a", if i % 5 == 0 { 0.0 } else { 1.0 }));
+        events.push(event("b", if i % 2 == 0 { 0.0 } else { 1.0 }));
+    }
+    events
+}
+
+#[test]
+fn greedy_probabilities_split_ties() {
+    let p = greedy_probabilities(&[("a".to_string(), 0.4), ("b".to_string(), 0.9), ("c".to_string(), 0.9)]);
+    assert_eq!(p["a"], 0.0);
+    assert_eq!(p["b"], 0.5);
+    assert_eq!(p["c"], 0.5);
+}
+
+#[test]
+fn thompson_selection_probabilities_sum_to_one_and_favor_the_better_arm() {
+    let arms = vec![("a".to_string(), 90.0, 10.0), ("b".to_string(), 50.0, 50.0)];
+    let p = selection_probabilities(&arms, 2000, &mut StdRng::seed_from_u64(1));
+    assert!((p.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!(p["a"] > 0.99);
+
+    let even = selection_probabilities(&[("a".to_string(), 1.0, 1.0), ("b".to_string(), 1.0, 1.0)], 4000, &mut StdRng::seed_from_u64(2));
+    assert!((even["a"] - 0.5).abs() < 0.05);
+}
+
+#[test]
+fn ips_and_doubly_robust_recover_the_target_success_rate() {
+    let events = logged_events();
+    let model = RewardModel::fit(&events);
+    assert_eq!(logged_reward(&events), Some(0.65));
+
+    let engine = evaluate(&events, &ScoringEnginePolicy, &model);
+    assert_eq!(engine.events, 200);
+    assert!((engine.ips.unwrap() - 0.8).abs() < 1e-9);
+    assert!((engine.snips.unwrap() - 0.8).abs() < 1e-9);
+    assert!((engine.doubly_robust.unwrap() - 0.8).abs() < 0.01);
+    assert!((engine.agreement_rate.unwrap() - 0.5).abs() < 1e-9);
+    assert!((engine.effective_sample_size - 100.0).abs() < 1e-9);
+
+    let models = HashMap::new();
+    let mut posteriors = HashMap::new();
+    posteriors.insert((segment(), "a".to_string()), (1.0, 1000.0));
+    posteriors.insert((segment(), "b".to_string()), (1000.0, 1.0));
+    let bandit = BanditTarget {
+        name: "THOMPSON".to_string(),
+        default: BanditPolicyConfig::thompson("*"),
+        segments: HashMap::new(),
+        posteriors,
+        models,
+        draws: 500,
+    };
+    let estimate = evaluate(&events, &bandit, &model);
+    assert_eq!(estimate.policy, "THOMPSON");
+    assert!((estimate.ips.unwrap() - 0.5).abs() < 1e-9);
+    assert!((estimate.doubly_robust.unwrap() - 0.5).abs() < 0.01);
+}
+
+#[test]
+fn bandit_selection_reports_the_chosen_gateway_propensity() {
+    let selection = BanditSelection {
+        definition_id: DEFAULT_DEFINITION_ID.to_string(),
+        segment: "UPI:lt_500".to_string(),
+        state_definition_id: None,
+        state_segment: None,
+        algorithm: Default::default(),
+        chosen_gateway: "a".to_string(),
+        scores: HashMap::from([("a".to_string(), 0.7), ("b".to_string(), 0.4)]),
+        propensities: HashMap::from([("a".to_string(), 0.62), ("b".to_string(), 0.38)]),
+        explored: true,
+        excluded: Vec::new(),
+    };
+    assert_eq!(selection.propensity(), 0.62);
+
+    let json = serde_json::to_value(&selection).unwrap();
+    assert_eq!(json["algorithm"], "THOMPSON");
+    let parsed: BanditSelection = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed.chosen_gateway, "a");
+}
@@ -0,0 +1,47 @@
+use payments_gateway::bandit::policy::BanditPolicyConfig;
+use payments_gateway::bandit::reward::{reward, validate_reward, AttemptReward, RewardConfig};
+
+fn attempt(success: bool, latency_ms: i32, fee: Option<i64>) -> AttemptReward {
+    AttemptReward {
+        success,
+        latency_ms,
+        expected_fee_minor: fee,
+        amount_minor: 10_000,
+    }
+}
+
+#[test]
+fn default_reward_is_plain_success() {
+    let config = RewardConfig::default();
+    assert_eq!(reward(&config, &attempt(true, 5_000, Some(300))), 1.0);
+    assert_eq!(reward(&config, &attempt(false, 50, None)), 0.0);
+    assert_eq!(BanditPolicyConfig::thompson("UPI:lt_500").reward, config);
+}
+
+#[test]
+fn slow_or_expensive_successes_earn_fractional_reward() {
+    let config = RewardConfig {
+        latency_target_ms: Some(1_000),
+        latency_penalty: 0.4,
+        cost_penalty: 10.0,
+    };
+
+    assert!((reward(&config, &attempt(true, 800, None)) - 1.0).abs() < 1e-12);
+    assert!((reward(&config, &attempt(true, 1_500, None)) - 0.8).abs() < 1e-12);
+    assert!((reward(&config, &attempt(true, 10_000, None)) - 0.6).abs() < 1e-12);
+    assert!((reward(&config, &attempt(true, 800, Some(200))) - 0.8).abs() < 1e-12);
+    assert_eq!(reward(&config, &attempt(true, 10_000, Some(2_000))), 0.0);
+    assert_eq!(reward(&config, &attempt(false, 100, Some(0))), 0.0);
+}
+
+#[test]
+fn reward_config_is_validated_and_parsed_with_defaults() {
+    let parsed: RewardConfig = serde_json::from_str(r#"{"latency_target_ms": 1200}"#).unwrap();
+    assert_eq!(parsed.latency_target_ms, Some(1200));
+    assert_eq!(parsed.latency_penalty, 0.0);
+    assert!(validate_reward(&parsed).is_ok());
+
+    assert!(validate_reward(&RewardConfig { latency_target_ms: Some(0), ..RewardConfig::default() }).is_err());
+    assert!(validate_reward(&RewardConfig { latency_penalty: 1.5, ..RewardConfig::default() }).is_err());
+    assert!(validate_reward(&RewardConfig { cost_penalty: -1.0, ..RewardConfig::default() }).is_err());
+}
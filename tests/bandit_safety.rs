@@ -0,0 +1,100 @@
+use payments_gateway::bandit::evaluation::{BanditTarget, EvaluationEvent, EventCandidate, TargetPolicy};
+use payments_gateway::bandit::policy::BanditPolicyConfig;
+use payments_gateway::bandit::segments::{BanditSegment, DEFAULT_DEFINITION_ID};
+use payments_gateway::bandit::safety::{
+    mix_propensities, passes_floor, validate_safety, warm_start_prior, SafetyConfig, DEFAULT_WARM_START_STRENGTH,
+};
+use std::collections::HashMap;
+
+#[test]
+fn warm_start_uses_history_capped_by_strength() {
+    let (alpha, beta) = warm_start_prior(Some(0.9), 10_000.0, 20.0);
+    assert!((alpha - 19.0).abs() < 1e-9);
+    assert!((beta - 3.0).abs() < 1e-9);
+
+    let (alpha, beta) = warm_start_prior(Some(0.0), 4.0, 20.0);
+    assert!((alpha - 1.0).abs() < 1e-9);
+    assert!((beta - 5.0).abs() < 1e-9);
+
+    assert_eq!(warm_start_prior(None, 0.0, 20.0), (1.0, 1.0));
+    assert_eq!(warm_start_prior(Some(0.8), 100.0, 0.0), (1.0, 1.0));
+}
+
+#[test]
+fn floor_and_exploration_share_limit_the_bandit() {
+    assert!(passes_floor(1.0, 1.0, None));
+    assert!(!passes_floor(1.0, 21.0, Some(0.5)));
+    assert!(passes_floor(19.0, 3.0, Some(0.5)));
+    assert!(passes_floor(1.0, 1.0, Some(0.6)));
+    assert!(!passes_floor(1.0, 1.5, Some(0.6)));
+
+    let bandit = HashMap::from([("a".to_string(), 0.2), ("b".to_string(), 0.8)]);
+    let mixed = mix_propensities(&bandit, "a", 0.1);
+    assert!((mixed["a"] - 0.92).abs() < 1e-9);
+    assert!((mixed["b"] - 0.08).abs() < 1e-9);
+    assert!((mixed.values().sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn evaluation_respects_segment_safety_settings() {
+    let segment = BanditSegment::new(DEFAULT_DEFINITION_ID, "UPI:lt_500");
+    let event = EvaluationEvent {
+        segment: segment.clone(),
+        state_segment: segment.clone(),
+        candidates: ["engine_pick", "axis_mock", "other"]
+            .iter()
+            .enumerate()
+            .map(|(i, g)| EventCandidate {
+                gateway_id: g.to_string(),
+                engine_score: 1.0 - i as f64 * 0.1,
+                features: vec![1.0; 8],
+            })
+            .collect(),
+        logged_gateway: "engine_pick".to_string(),
+        propensity: 1.0,
+        reward: 1.0,
+    };
+    let posteriors = HashMap::from([
+        ((segment.clone(), "engine_pick".to_string()), (30.0, 10.0)),
+        ((segment.clone(), "axis_mock".to_string()), (1.0, 21.0)),
+        ((segment.clone(), "other".to_string()), (1000.0, 1.0)),
+    ]);
+    let mut config = BanditPolicyConfig::thompson("UPI:lt_500");
+    config.safety = SafetyConfig {
+        max_exploration_share: Some(0.25),
+        min_success_rate: Some(0.5),
+        warm_start_strength: DEFAULT_WARM_START_STRENGTH,
+    };
+    let mut target = BanditTarget {
+        name: "CURRENT_BANDIT".to_string(),
+        default: BanditPolicyConfig::thompson("*"),
+        segments: HashMap::from([(segment.clone(), config)]),
+        posteriors,
+        models: HashMap::new(),
+        draws: 200,
+    };
+
+    let p = target.probabilities(&event);
+    assert!((p["engine_pick"] - 0.75).abs() < 1e-9);
+    assert!((p["other"] - 0.25).abs() < 1e-9);
+    assert_eq!(p.get("axis_mock").copied().unwrap_or(0.0), 0.0);
+
+    target
+        .posteriors
+        .insert((segment, "engine_pick".to_string()), (1.0, 1000.0));
+    let p = target.probabilities(&event);
+    assert!((p["other"] - 1.0).abs() < 1e-9);
+    assert_eq!(p.get("engine_pick").copied().unwrap_or(0.0), 0.0);
+}
+
+#[test]
+fn safety_config_defaults_and_validation() {
+    let parsed: SafetyConfig = serde_json::from_str(r#"{"min_success_rate": 0.6}"#).unwrap();
+    assert_eq!(parsed.warm_start_strength, DEFAULT_WARM_START_STRENGTH);
+    assert_eq!(parsed.max_exploration_share, None);
+    assert!(validate_safety(&parsed).is_ok());
+
+    assert!(validate_safety(&SafetyConfig { max_exploration_share: Some(1.5), ..SafetyConfig::default() }).is_err());
+    assert!(validate_safety(&SafetyConfig { min_success_rate: Some(-0.1), ..SafetyConfig::default() }).is_err());
+    assert!(validate_safety(&SafetyConfig { warm_start_strength: f64::NAN, ..SafetyConfig::default() }).is_err());
+}
@@ -0,0 +1,166 @@
+use chrono::TimeZone;
+use payments_gateway::bandit::segments::{
+    hour_band, validate_definitions, BanditSegment, SegmentCatalog, SegmentContext, SegmentDefinition,
+    SegmentDimension, DEFAULT_DEFINITION_ID,
+};
+use payments_gateway::metrics::amount_bucket::AmountBucketer;
+use payments_gateway::service::routing_config::RoutingConfigSnapshot;
+
+fn definition(id: &str, dimensions: Vec<SegmentDimension>, fallback: Option<&str>) -> SegmentDefinition {
+    SegmentDefinition {
+        definition_id: id.to_string(),
+        dimensions,
+        fallback_definition_id: fallback.map(str::to_string),
+        min_observations: 50.0,
+        priority: 10,
+        timezone: "UTC".to_string(),
+    }
+}
+
+fn context() -> SegmentContext<'static> {
+    SegmentContext {
+        payment_method: "card",
+        amount_bucket: "lt_500",
+        issuer: "hdfc",
+        card_network: None,
+        merchant_id: "m:1",
+        at: chrono::Utc.with_ymd_and_hms(2026, 3, 2, 14, 0, 0).unwrap(),
+    }
+}
+
+#[test]
+fn keys_follow_the_definition_dimensions() {
+    let ctx = context();
+    assert_eq!(
+        SegmentDefinition::default_definition().segment(&ctx),
+        BanditSegment::new(DEFAULT_DEFINITION_ID, "CARD:lt_500")
+    );
+
+    let detailed = definition(
+        "issuer_hour",
+        vec![
+            SegmentDimension::PaymentMethod,
+            SegmentDimension::Issuer,
+            SegmentDimension::CardNetwork,
+            SegmentDimension::Merchant,
+            SegmentDimension::HourBand,
+        ],
+        None,
+    );
+    let key = detailed.key(&ctx);
+    assert_eq!(key, "CARD:HDFC:UNKNOWN:m_1:H12");
+    assert_eq!(detailed.value(&key, SegmentDimension::Issuer), Some("HDFC"));
+    assert_eq!(detailed.value(&key, SegmentDimension::AmountBucket), None);
+
+    assert_eq!(hour_band(0), "H00");
+    assert_eq!(hour_band(5), "H00");
+    assert_eq!(hour_band(23), "H18");
+}
+
+#[test]
+fn keys_are_validated_against_dimensions() {
+    let buckets = AmountBucketer::default();
+    let default = SegmentDefinition::default_definition();
+    assert!(default.validate_key("UPI:lt_500", &buckets).is_ok());
+    assert!(default.validate_key("UPI", &buckets).is_err());
+    assert!(default.validate_key("UPI:huge", &buckets).is_err());
+    assert!(default.validate_key("upi:lt_500", &buckets).is_err());
+
+    let hourly = definition("hourly", vec![SegmentDimension::HourBand], None);
+    assert!(hourly.validate_key("H06", &buckets).is_ok());
+    assert!(hourly.validate_key("H07", &buckets).is_err());
+}
+
+#[test]
+fn definitions_must_fall_back_to_coarser_definitions() {
+    let default = SegmentDefinition::default_definition();
+    let issuer = definition(
+        "issuer",
+        vec![SegmentDimension::PaymentMethod, SegmentDimension::AmountBucket, SegmentDimension::Issuer],
+        Some(DEFAULT_DEFINITION_ID),
+    );
+    assert!(validate_definitions(&[default.clone(), issuer.clone()]).is_ok());
+
+    let finer = definition(
+        "method",
+        vec![SegmentDimension::PaymentMethod, SegmentDimension::HourBand],
+        Some("issuer"),
+    );
+    assert!(validate_definitions(&[default.clone(), issuer.clone(), finer]).is_err());
+
+    let mut looping = default.clone();
+    looping.fallback_definition_id = Some("issuer".to_string());
+    let mut looping_issuer = issuer.clone();
+    looping_issuer.dimensions = looping.dimensions.clone();
+    assert!(validate_definitions(&[looping, looping_issuer]).is_err());
+
+    assert!(validate_definitions(&[issuer]).is_err());
+    assert!(validate_definitions(&[default.clone(), definition("bad id", vec![SegmentDimension::Issuer], None)]).is_err());
+    assert!(validate_definitions(&[default, definition("unknown", vec![SegmentDimension::Issuer], Some("missing"))]).is_err());
+}
+
+#[test]
+fn catalog_orders_by_priority_and_walks_fallbacks() {
+    let issuer = definition(
+        "issuer",
+        vec![SegmentDimension::PaymentMethod, SegmentDimension::AmountBucket, SegmentDimension::Issuer],
+        Some(DEFAULT_DEFINITION_ID),
+    );
+    let catalog = SegmentCatalog::new(vec![issuer]);
+    assert_eq!(catalog.definitions().len(), 2);
+
+    let segments = catalog.segments(&context());
+    assert_eq!(segments[0], BanditSegment::new("issuer", "CARD:lt_500:HDFC"));
+    assert_eq!(segments[1], BanditSegment::new(DEFAULT_DEFINITION_ID, "CARD:lt_500"));
+
+    let chain: Vec<&str> = catalog
+        .fallback_chain("issuer")
+        .iter()
+        .map(|d| d.definition_id.as_str())
+        .collect();
+    assert_eq!(chain, vec!["issuer", DEFAULT_DEFINITION_ID]);
+    assert!(catalog.fallback_chain("missing").is_empty());
+}
+
+#[test]
+fn hour_bands_use_the_definition_timezone() {
+    let mut hourly = definition("hourly", vec![SegmentDimension::HourBand], None);
+    let ctx = context();
+    assert_eq!(hourly.key(&ctx), "H12");
+    hourly.timezone = "Asia/Kolkata".to_string();
+    assert_eq!(hourly.key(&ctx), "H18");
+
+    let default = SegmentDefinition::default_definition();
+    hourly.timezone = "Mars/Olympus".to_string();
+    assert!(validate_definitions(&[default, hourly]).is_err());
+}
+
+#[test]
+fn only_enabled_segments_and_their_fallbacks_are_tracked() {
+    let issuer = definition(
+        "issuer",
+        vec![SegmentDimension::PaymentMethod, SegmentDimension::AmountBucket, SegmentDimension::Issuer],
+        Some(DEFAULT_DEFINITION_ID),
+    );
+    let merchant = definition("merchant", vec![SegmentDimension::Merchant], None);
+    let mut snapshot = RoutingConfigSnapshot {
+        bandit_definitions: SegmentCatalog::new(vec![issuer, merchant]),
+        ..Default::default()
+    };
+    let segments = snapshot.bandit_definitions.segments(&context());
+    assert!(snapshot.tracked_bandit_segments(&segments, None).is_empty());
+
+    let issuer_segment = BanditSegment::new("issuer", "CARD:lt_500:HDFC");
+    snapshot.bandit_segments.insert(issuer_segment.clone());
+    assert_eq!(
+        snapshot.tracked_bandit_segments(&segments, None),
+        vec![issuer_segment, BanditSegment::new(DEFAULT_DEFINITION_ID, "CARD:lt_500")]
+    );
+
+    let merchant_segment = BanditSegment::new("merchant", "m_1");
+    snapshot.bandit_segments.clear();
+    assert_eq!(
+        snapshot.tracked_bandit_segments(&segments, Some(&merchant_segment)),
+        vec![merchant_segment]
+    );
+}
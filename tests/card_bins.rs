@@ -0,0 +1,154 @@
+use payments_gateway::bins::import::parse_csv;
+use payments_gateway::bins::index::{validate_card_filters, BinIndex, BinRecord, CardType};
+use payments_gateway::domain::experiment::ExperimentFilter;
+use payments_gateway::domain::routing_rule::RuleConditions;
+use payments_gateway::experiments::filter::{matches, MatchInput};
+use payments_gateway::router::rules::{conditions_match, RuleInput};
+use payments_gateway::scoring::cost::{select_slab, FeeSlab};
+use payments_gateway::service::routing_config::RoutingConfigSnapshot;
+use uuid::Uuid;
+
+#[test]
+fn eight_digit_bins_win_over_six_digit_and_ranges_resolve() {
+    let index = BinIndex::build(vec![
+        bin("411111", "411111", "VISA", CardType::Credit, Some("HDFC")),
+        bin("41111122", "41111122", "VISA", CardType::Prepaid, Some("YES")),
+        bin("60800000", "60849999", "RUPAY", CardType::Debit, Some("SBI")),
+        bin("60820000", "60820999", "RUPAY", CardType::Credit, Some("KOTAK")),
+    ]);
+    assert_eq!(index.len(), 4);
+
+    assert_eq!(index.lookup("4111111111111111").unwrap().card_type, CardType::Credit);
+    assert_eq!(index.lookup("4111112233334444").unwrap().issuer_id.as_deref(), Some("YES"));
+    assert_eq!(index.lookup("6084999900001111").unwrap().issuer_id.as_deref(), Some("SBI"));
+    assert_eq!(index.lookup("6082050000001111").unwrap().issuer_id.as_deref(), Some("KOTAK"));
+    assert_eq!(index.lookup("6082100000001111").unwrap().issuer_id.as_deref(), Some("SBI"));
+    assert!(index.lookup("6085000000001111").is_none());
+    assert!(index.lookup("4111").is_none());
+    assert!(index.lookup("4111 1122 3333 4444").is_some());
+}
+
+#[test]
+fn csv_import_validates_rows() {
+    let csv = "bin_start,bin_end,card_network,card_type,issuer_country,issuer_id\n\
+               411111,,visa,credit,in,hdfc\n\
+               60800000,60849999,RUPAY,DEBIT,IN,SBI\n\
+               12345,,VISA,CREDIT,IN,HDFC\n\
+               500000,400000,VISA,CREDIT,IN,HDFC\n\
+               522222,,DISCOVER,CREDIT,US,\n\
+               533333,,MASTERCARD,GIFT,IN,\n\
+               411111,,VISA,DEBIT,IN,HDFC\n";
+    let (records, errors) = parse_csv(csv);
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].bin_end, "411111");
+    assert_eq!(records[0].card_network, "VISA");
+    assert_eq!(records[0].issuer_country.as_deref(), Some("IN"));
+    assert_eq!(records[0].issuer_id.as_deref(), Some("HDFC"));
+    assert_eq!(errors.iter().map(|e| e.line).collect::<Vec<_>>(), vec![4, 5, 6, 7, 8]);
+}
+
+#[test]
+fn snapshot_uses_bin_index_for_issuer_and_card_attributes() {
+    let snapshot = RoutingConfigSnapshot {
+        bins: BinIndex::build(vec![bin("60800000", "60849999", "RUPAY", CardType::Debit, Some("SBI"))]),
+        ..Default::default()
+    };
+    let card = snapshot.card_attributes("6083000011112222");
+    assert_eq!(card.card_network.as_deref(), Some("RUPAY"));
+    assert_eq!(card.card_type.as_deref(), Some("DEBIT"));
+    assert_eq!(snapshot.bank_from_bin("6083000011112222"), Some("SBI"));
+
+    let unknown = snapshot.card_attributes("5555555555554444");
+    assert_eq!(unknown.card_network.as_deref(), Some("MASTERCARD"));
+    assert_eq!(unknown.card_type, None);
+}
+
+#[test]
+fn rules_experiments_and_fees_use_card_type() {
+    let conditions = RuleConditions {
+        card_type: Some("DEBIT".to_string()),
+        card_network: Some("RUPAY".to_string()),
+        ..RuleConditions::default()
+    };
+    assert!(conditions_match(&conditions, &rule_input(Some("RUPAY"), Some("DEBIT"))));
+    assert!(!conditions_match(&conditions, &rule_input(Some("RUPAY"), Some("CREDIT"))));
+    assert!(!conditions_match(&conditions, &rule_input(Some("RUPAY"), None)));
+
+    let filter = ExperimentFilter {
+        experiment_id: Uuid::new_v4(),
+        payment_method: None,
+        min_amount_minor: None,
+        max_amount_minor: None,
+        merchant_id: None,
+        amount_bucket: None,
+        card_network: None,
+        card_type: Some("credit".to_string()),
+    };
+    let input = |card_type: Option<&str>| MatchInput {
+        payment_method: "CARD".to_string(),
+        amount_minor: 10_000,
+        merchant_id: "m1".to_string(),
+        amount_bucket: "lt_500".to_string(),
+        card_network: Some("VISA".to_string()),
+        card_type: card_type.map(str::to_string),
+    };
+    assert!(matches(&filter, &input(Some("CREDIT"))));
+    assert!(!matches(&filter, &input(Some("DEBIT"))));
+
+    let slabs = vec![
+        slab("ANY", "ANY", 2.0),
+        slab("ANY", "DEBIT", 0.4),
+        slab("RUPAY", "ANY", 0.0),
+        slab("VISA", "DEBIT", 0.9),
+    ];
+    let pick = |network: Option<&str>, card_type: Option<&str>| {
+        select_slab(&slabs, "CARD", network, card_type, 100_000).map(|s| s.pct_fee)
+    };
+    assert_eq!(pick(Some("VISA"), Some("DEBIT")), Some(0.9));
+    assert_eq!(pick(Some("MASTERCARD"), Some("DEBIT")), Some(0.4));
+    assert_eq!(pick(Some("RUPAY"), Some("DEBIT")), Some(0.0));
+    assert_eq!(pick(Some("VISA"), Some("CREDIT")), Some(2.0));
+    assert_eq!(pick(None, None), Some(2.0));
+
+    assert!(validate_card_filters(Some("rupay"), Some("debit")).is_ok());
+    assert!(validate_card_filters(Some("discover"), None).is_err());
+    assert!(validate_card_filters(None, Some("gift")).is_err());
+}
+
+fn bin(start: &str, end: &str, network: &str, card_type: CardType, issuer: Option<&str>) -> BinRecord {
+    BinRecord {
+        bin_start: start.to_string(),
+        bin_end: end.to_string(),
+        card_network: network.to_string(),
+        card_type,
+        issuer_country: Some("IN".to_string()),
+        issuer_id: issuer.map(str::to_string),
+    }
+}
+
+fn rule_input(card_network: Option<&str>, card_type: Option<&str>) -> RuleInput {
+    RuleInput {
+        payment_method: "CARD".to_string(),
+        merchant_id: "m1".to_string(),
+        issuing_bank: "SBI".to_string(),
+        amount_minor: 10_000,
+        amount_bucket: "lt_500".to_string(),
+        card_network: card_network.map(str::to_string),
+        card_type: card_type.map(str::to_string),
+    }
+}
+
+fn slab(network: &str, card_type: &str, pct_fee: f64) -> FeeSlab {
+    FeeSlab {
+        gateway_id: "g1".to_string(),
+        payment_method: "CARD".to_string(),
+        card_network: network.to_string(),
+        card_type: card_type.to_string(),
+        min_amount_minor: 0,
+        max_amount_minor: None,
+        pct_fee,
+        fixed_fee_minor: 0,
+        gst_pct: 18.0,
+    }
+}
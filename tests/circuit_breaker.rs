@@ -1,6 +1,9 @@
+use payments_gateway::circuit::evaluator::rejects_all_calls;
 use payments_gateway::circuit::state::{CircuitSnapshot, CircuitState};
+use payments_gateway::circuit::store_redis::{result_fields, window_rates, CircuitStoreRedis};
 use payments_gateway::circuit::transitions::apply_transition;
 use payments_gateway::repo::circuit_breaker_config_repo::CircuitThresholds;
+use std::collections::HashMap;
 
 #[test]
 fn opens_when_failure_rate_crosses_threshold() {
@@ -25,6 +28,43 @@ fn closes_half_open_on_success_streak() {
     assert_eq!(out.state, CircuitState::Closed);
 }
 
+#[test]
+fn views_pair_override_and_snapshot_per_gateway() {
+    let gateways = vec!["g1".to_string(), "g2".to_string()];
+    let keys = CircuitStoreRedis::view_keys("UPI", &gateways);
+    assert_eq!(keys.len(), 4);
+    assert!(keys[0].starts_with("circuit:manual_override:g1"));
+    assert!(keys[1].starts_with("circuit:state:g1"));
+
+    let mut open = CircuitSnapshot::new("g2", "UPI");
+    open.state = CircuitState::Open;
+    let values = vec![
+        Some("FORCE_CLOSED".to_string()),
+        None,
+        None,
+        Some(serde_json::to_string(&open).unwrap()),
+    ];
+    let views = CircuitStoreRedis::views_from_values("UPI", &gateways, &values).unwrap();
+    assert_eq!(views["g1"].override_state.as_deref(), Some("FORCE_CLOSED"));
+    assert_eq!(views["g1"].snapshot.state, CircuitState::Closed);
+    assert_eq!(views["g2"].override_state, None);
+    assert_eq!(views["g2"].snapshot.state, CircuitState::Open);
+}
+
+#[test]
+fn open_circuits_reject_all_calls_until_cooldown_or_override() {
+    let now = chrono::Utc::now();
+    let mut snapshot = CircuitSnapshot::new("g1", "UPI");
+    assert!(!rejects_all_calls(None, &snapshot, now));
+    assert!(rejects_all_calls(Some("FORCE_OPEN"), &snapshot, now));
+
+    snapshot.state = CircuitState::Open;
+    snapshot.cooldown_until = Some(now + chrono::Duration::seconds(30));
+    assert!(rejects_all_calls(None, &snapshot, now));
+    assert!(!rejects_all_calls(Some("FORCE_CLOSED"), &snapshot, now));
+    assert!(!rejects_all_calls(None, &snapshot, now + chrono::Duration::seconds(31)));
+}
+
 fn defaults() -> CircuitThresholds {
     CircuitThresholds {
         failure_rate_threshold_2m: 0.40,
@@ -38,3 +78,27 @@ fn defaults() -> CircuitThresholds {
         half_open_consecutive_failure_reopen: 3,
     }
 }
+
+#[test]
+fn result_fields_match_attempt_status() {
+    assert_eq!(result_fields("SUCCESS"), &["total", "success"]);
+    assert_eq!(result_fields("TIMEOUT"), &["total", "failed", "timeout"]);
+    assert_eq!(result_fields("FAILURE"), &["total", "failed"]);
+}
+
+#[test]
+fn window_rates_sum_minute_buckets() {
+    let buckets = vec![
+        bucket(&[("total", "4"), ("failed", "2"), ("timeout", "1")]),
+        bucket(&[("total", "6"), ("failed", "1"), ("success", "5")]),
+        HashMap::new(),
+    ];
+
+    assert_eq!(window_rates(&buckets), (0.3, 0.1));
+    assert_eq!(window_rates(&buckets[..1]), (0.5, 0.25));
+    assert_eq!(window_rates(&[]), (0.0, 0.0));
+}
+
+fn bucket(fields: &[(&str, &str)]) -> HashMap<String, String> {
+    fields.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
@@ -0,0 +1,98 @@
+use payments_gateway::bandit::benchmark::{replay, LinUcbReplay, LoggedDecision, ReplayPolicy};
+use payments_gateway::bandit::linucb::{features, LinearModel, FEATURE_DIM};
+use payments_gateway::bandit::policy::{validate_exploration, BanditAlgorithm};
+use payments_gateway::scoring::types::ScoreBreakdown;
+
+fn breakdown(success_rate_score: f64, bank_affinity: f64) -> ScoreBreakdown {
+    ScoreBreakdown {
+        success_rate_score,
+        latency_score: 0.5,
+        method_affinity: 0.7,
+        bank_affinity,
+        amount_fit: 0.7,
+        time_weight: 1.0,
+        cost_score: 0.5,
+        final_score: 0.0,
+        metric_level: None,
+        signal_windows: Vec::new(),
+    }
+}
+
+fn decision(chosen: &str, success: bool) -> LoggedDecision {
+    LoggedDecision {
+        segment: "UPI:lt_500".to_string(),
+        candidates: vec![
+            ("hdfc_mock".to_string(), features(&breakdown(0.9, 1.0))),
+            ("icici_mock".to_string(), features(&breakdown(0.9, 0.2))),
+        ],
+        chosen_gateway: chosen.to_string(),
+        success,
+    }
+}
+
+#[test]
+fn sherman_morrison_updates_match_ridge_regression() {
+    let mut model = LinearModel::new(2);
+    model.update(&[1.0, 0.0], 1.0);
+    model.update(&[1.0, 1.0], 0.0);
+
+    let theta = model.theta();
+    assert!((theta[0] - 0.4).abs() < 1e-12);
+    assert!((theta[1] + 0.2).abs() < 1e-12);
+    assert_eq!(model.observations, 2);
+    assert!(model.is_valid(2));
+    assert!(!model.is_valid(FEATURE_DIM));
+}
+
+#[test]
+fn exploration_bonus_shrinks_with_observations() {
+    let x = features(&breakdown(0.9, 1.0));
+    let mut model = LinearModel::new(FEATURE_DIM);
+    let cold = model.ucb(&x, 1.0) - model.ucb(&x, 0.0);
+    for _ in 0..200 {
+        model.update(&x, 1.0);
+    }
+    let warm = model.ucb(&x, 1.0) - model.ucb(&x, 0.0);
+
+    assert!(warm < cold / 10.0);
+    assert!((model.ucb(&x, 0.0) - 1.0).abs() < 0.05);
+}
+
+#[test]
+fn replay_only_scores_events_where_policy_matches_log() {
+    struct AlwaysHdfc;
+    impl ReplayPolicy for AlwaysHdfc {
+        fn name(&self) -> String {
+            "ALWAYS_HDFC".to_string()
+        }
+        fn choose(&mut self, _: &LoggedDecision) -> Option<String> {
+            Some("hdfc_mock".to_string())
+        }
+        fn learn(&mut self, _: &LoggedDecision) {}
+    }
+
+    let log = vec![
+        decision("hdfc_mock", true),
+        decision("icici_mock", false),
+        decision("hdfc_mock", false),
+        decision("hdfc_mock", true),
+    ];
+    let report = replay(&log, &mut AlwaysHdfc);
+    assert_eq!(report.events, 4);
+    assert_eq!(report.matched, 3);
+    assert_eq!(report.successes, 2);
+
+    let linucb = replay(&log, &mut LinUcbReplay::new(0.5));
+    assert!(linucb.matched >= 1);
+    assert!(linucb.successes <= linucb.matched);
+}
+
+#[test]
+fn algorithm_parsing_and_exploration_validation() {
+    assert_eq!(BanditAlgorithm::parse("linucb"), Some(BanditAlgorithm::LinUcb));
+    assert_eq!(BanditAlgorithm::parse("THOMPSON"), Some(BanditAlgorithm::Thompson));
+    assert_eq!(BanditAlgorithm::parse("epsilon"), None);
+    assert_eq!(serde_json::to_value(BanditAlgorithm::LinUcb).unwrap(), "LINUCB");
+    assert!(validate_exploration(0.0).is_ok());
+    assert!(validate_exploration(-1.0).is_err());
+}
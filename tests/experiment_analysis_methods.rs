@@ -0,0 +1,113 @@
+use chrono::TimeZone;
+use payments_gateway::domain::experiment::{AnalysisMethod, ExperimentResultRow};
+use payments_gateway::experiments::analyzer::{
+    always_valid_p_value, analyze, bayesian_comparison, two_sided_p_value, EXPECTED_LOSS_THRESHOLD,
+};
+use uuid::Uuid;
+
+fn row(experiment_id: Uuid, variant: &str, hour: u32, total: i64, success: i64) -> ExperimentResultRow {
+    ExperimentResultRow {
+        experiment_id,
+        variant: variant.to_string(),
+        date_hour: chrono::Utc.with_ymd_and_hms(2026, 3, 2, hour, 0, 0).unwrap(),
+        total_requests: total,
+        successful_requests: success,
+        failed_requests: total - success,
+        avg_latency_ms: 800,
+        p95_latency_ms: 1600,
+        total_revenue_minor: 0,
+    }
+}
+
+fn hourly(hours: u32, control_success: i64, treatment_success: i64) -> Vec<ExperimentResultRow> {
+    let experiment_id = Uuid::new_v4();
+    (0..hours)
+        .flat_map(|h| {
+            vec![
+                row(experiment_id, "control", h, 200, control_success),
+                row(experiment_id, "treatment", h, 200, treatment_success),
+            ]
+        })
+        .collect()
+}
+
+#[test]
+fn p_values_match_reference_normal_quantiles() {
+    assert!((two_sided_p_value(1.959964) - 0.05).abs() < 1e-6);
+    assert!((two_sided_p_value(-2.575829) - 0.01).abs() < 1e-6);
+    assert!((two_sided_p_value(3.290527) - 0.001).abs() < 1e-7);
+    assert!((two_sided_p_value(6.0) - 1.973e-9).abs() < 1e-11);
+    assert_eq!(two_sided_p_value(0.0), 1.0);
+}
+
+#[test]
+fn sequential_analysis_does_not_call_borderline_effects() {
+    let rows = hourly(10, 180, 184);
+
+    let fixed = analyze(&rows, 100, AnalysisMethod::FixedHorizon);
+    assert_eq!(fixed.winner.as_deref(), Some("treatment"));
+    assert!(fixed.p_value < 0.05);
+
+    let sequential = analyze(&rows, 100, AnalysisMethod::Sequential);
+    assert_eq!(sequential.method, "SEQUENTIAL");
+    assert_eq!(sequential.correction, "HOLM");
+    assert_eq!(sequential.winner, None);
+    assert_eq!(sequential.recommendation, "continue experiment");
+    assert!(sequential.p_value > fixed.p_value);
+    assert!((sequential.z_score - fixed.z_score).abs() < 1e-12);
+}
+
+#[test]
+fn sequential_analysis_declares_clear_winners_and_never_loses_evidence() {
+    let rows = hourly(10, 180, 190);
+    let out = analyze(&rows, 100, AnalysisMethod::Sequential);
+    assert_eq!(out.winner.as_deref(), Some("treatment"));
+    assert_eq!(out.recommendation, "promote treatment");
+    assert!(out.p_value < 0.001);
+
+    let mut longer = rows.clone();
+    let experiment_id = rows[0].experiment_id;
+    for h in 10..20 {
+        longer.push(row(experiment_id, "control", h, 200, 190));
+        longer.push(row(experiment_id, "treatment", h, 200, 180));
+    }
+    let before = always_valid_p_value(&rows, "treatment", 100);
+    let after = always_valid_p_value(&longer, "treatment", 100);
+    assert!(after <= before);
+    assert_eq!(always_valid_p_value(&rows, "treatment", 100_000), 1.0);
+}
+
+#[test]
+fn bayesian_analysis_reports_probability_and_expected_loss() {
+    let better = analyze(&hourly(10, 180, 190), 100, AnalysisMethod::Bayesian);
+    assert_eq!(better.method, "BAYESIAN");
+    assert_eq!(better.correction, "NONE");
+    assert_eq!(better.winner.as_deref(), Some("treatment"));
+    let probability = better.probability_to_beat_control.unwrap();
+    assert!(probability > 0.99);
+    assert!(better.expected_loss.unwrap() < EXPECTED_LOSS_THRESHOLD);
+    assert_eq!(better.comparisons[0].probability_to_beat_control, Some(probability));
+
+    let tied = analyze(&hourly(10, 180, 180), 100, AnalysisMethod::Bayesian);
+    assert_eq!(tied.winner, None);
+    assert_eq!(tied.recommendation, "continue experiment");
+    assert!((tied.probability_to_beat_control.unwrap() - 0.5).abs() < 0.05);
+    assert!(tied.expected_loss.unwrap() > 0.0);
+
+    let worse = analyze(&hourly(10, 190, 180), 100, AnalysisMethod::Bayesian);
+    assert_eq!(worse.winner.as_deref(), Some("control"));
+    assert_eq!(worse.recommendation, "keep control");
+
+    let small = analyze(&hourly(1, 180, 190), 500, AnalysisMethod::Bayesian);
+    assert_eq!(small.recommendation, "insufficient sample size");
+    assert_eq!(small.probability_to_beat_control, None);
+}
+
+#[test]
+fn bayesian_comparison_is_deterministic() {
+    let rows = hourly(5, 170, 175);
+    assert_eq!(bayesian_comparison(&rows, "treatment"), bayesian_comparison(&rows, "treatment"));
+    assert_eq!(AnalysisMethod::parse("bayesian"), Some(AnalysisMethod::Bayesian));
+    assert_eq!(AnalysisMethod::parse("thompson"), None);
+    assert_eq!(AnalysisMethod::default(), AnalysisMethod::FixedHorizon);
+}
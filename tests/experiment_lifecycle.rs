@@ -0,0 +1,190 @@
+use chrono::TimeZone;
+use payments_gateway::domain::experiment::{Experiment, ExperimentResultRow, ExperimentStatus, RampStep};
+use payments_gateway::experiments::analyzer::{evaluate_guardrails, GuardrailConfig, GuardrailDecision};
+use payments_gateway::experiments::assigner::{is_exposed, legacy_variants};
+use payments_gateway::experiments::lifecycle::{
+    initial_exposure, initial_status, manual_transition, next_transition, step_results, validate_ramp, LifecycleAction,
+};
+use uuid::Uuid;
+
+fn at(hour: u32, minute: u32) -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+}
+
+fn step(exposure_pct: i32, hold_minutes: i64) -> RampStep {
+    RampStep {
+        exposure_pct,
+        hold_minutes,
+    }
+}
+
+fn experiment(status: ExperimentStatus, ramp_schedule: Vec<RampStep>, ramp_step: Option<i32>) -> Experiment {
+    Experiment {
+        experiment_id: Uuid::new_v4(),
+        name: "ramp".to_string(),
+        status: status.as_str().to_string(),
+        traffic_control_pct: 50,
+        traffic_treatment_pct: 50,
+        treatment_gateway: "hdfc_mock".to_string(),
+        start_date: at(9, 0),
+        end_date: Some(at(18, 0)),
+        created_by: "ops".to_string(),
+        variants: legacy_variants(50, 50, "hdfc_mock"),
+        exposure_pct: ramp_step
+            .and_then(|i| ramp_schedule.get(i as usize))
+            .map(|s| s.exposure_pct)
+            .unwrap_or(100),
+        ramp_schedule,
+        ramp_step,
+        ramped_at: Some(at(10, 0)),
+        analysis_method: "FIXED_HORIZON".to_string(),
+    }
+}
+
+fn guardrail(should_pause: bool) -> GuardrailDecision {
+    GuardrailDecision {
+        should_pause,
+        reason: should_pause.then(|| "treatment_success_rate_below_guardrail".to_string()),
+        variant: Some("treatment".to_string()),
+        control_success_rate: 0.95,
+        treatment_success_rate: if should_pause { 0.85 } else { 0.95 },
+        control_p95_latency_ms: 1900,
+        treatment_p95_latency_ms: 1900,
+        treatment_total_requests: 1000,
+        sufficient_samples: true,
+    }
+}
+
+#[test]
+fn ramp_schedules_are_validated() {
+    assert!(validate_ramp(&[]).is_ok());
+    assert!(validate_ramp(&[step(1, 60), step(5, 60), step(20, 120), step(50, 0)]).is_ok());
+    assert!(validate_ramp(&[step(5, 60), step(5, 60)]).is_err());
+    assert!(validate_ramp(&[step(20, 60), step(5, 60)]).is_err());
+    assert!(validate_ramp(&[step(0, 60)]).is_err());
+    assert!(validate_ramp(&[step(101, 60)]).is_err());
+    assert!(validate_ramp(&[step(10, -1)]).is_err());
+
+    assert_eq!(initial_exposure(&[step(1, 60), step(5, 60)]), (1, Some(0)));
+    assert_eq!(initial_exposure(&[]), (100, None));
+    assert_eq!(initial_status(at(12, 0), at(9, 0)), ExperimentStatus::Scheduled);
+    assert_eq!(initial_status(at(9, 0), at(9, 0)), ExperimentStatus::Running);
+}
+
+#[test]
+fn scheduled_experiments_start_and_end_on_time() {
+    let scheduled = experiment(ExperimentStatus::Scheduled, Vec::new(), None);
+    assert_eq!(next_transition(&scheduled, None, at(8, 59)), None);
+    let started = next_transition(&scheduled, None, at(9, 0)).unwrap();
+    assert_eq!(started.action, LifecycleAction::Started);
+    assert_eq!(started.to_status, ExperimentStatus::Running);
+    assert!(started.restart_ramp_clock);
+
+    let running = experiment(ExperimentStatus::Running, Vec::new(), None);
+    assert_eq!(next_transition(&running, Some(&guardrail(false)), at(17, 59)), None);
+    let completed = next_transition(&running, Some(&guardrail(false)), at(18, 0)).unwrap();
+    assert_eq!(completed.action, LifecycleAction::Completed);
+
+    let paused = experiment(ExperimentStatus::Paused, Vec::new(), None);
+    assert_eq!(next_transition(&paused, None, at(12, 0)), None);
+    assert_eq!(
+        next_transition(&paused, None, at(19, 0)).map(|t| t.to_status),
+        Some(ExperimentStatus::Completed)
+    );
+    assert_eq!(next_transition(&experiment(ExperimentStatus::Stopped, Vec::new(), None), None, at(19, 0)), None);
+}
+
+#[test]
+fn ramp_advances_after_hold_while_guardrails_pass() {
+    let ramp = vec![step(1, 60), step(5, 60), step(20, 120), step(50, 0)];
+    let exp = experiment(ExperimentStatus::Running, ramp.clone(), Some(0));
+
+    assert_eq!(next_transition(&exp, Some(&guardrail(false)), at(10, 59)), None);
+    let ramped = next_transition(&exp, Some(&guardrail(false)), at(11, 0)).unwrap();
+    assert_eq!(ramped.action, LifecycleAction::Ramped);
+    assert_eq!(ramped.exposure_pct, Some(5));
+    assert_eq!(ramped.ramp_step, Some(1));
+    assert!(ramped.restart_ramp_clock);
+
+    let paused = next_transition(&exp, Some(&guardrail(true)), at(11, 0)).unwrap();
+    assert_eq!(paused.action, LifecycleAction::GuardrailPaused);
+    assert_eq!(paused.to_status, ExperimentStatus::Paused);
+    assert_eq!(paused.exposure_pct, None);
+
+    let last = experiment(ExperimentStatus::Running, ramp, Some(3));
+    assert_eq!(next_transition(&last, Some(&guardrail(false)), at(17, 0)), None);
+}
+
+fn result(experiment_id: Uuid, variant: &str, hour: u32, total: i64) -> ExperimentResultRow {
+    ExperimentResultRow {
+        experiment_id,
+        variant: variant.to_string(),
+        date_hour: at(hour, 0),
+        total_requests: total,
+        successful_requests: total * 95 / 100,
+        failed_requests: total - total * 95 / 100,
+        avg_latency_ms: 800,
+        p95_latency_ms: 1600,
+        total_revenue_minor: 0,
+    }
+}
+
+#[test]
+fn ramp_holds_until_the_current_step_has_enough_samples() {
+    let exp = experiment(ExperimentStatus::Running, vec![step(1, 60), step(5, 60)], Some(0));
+    let config = GuardrailConfig::default();
+    let results = vec![
+        result(exp.experiment_id, "control", 9, 5000),
+        result(exp.experiment_id, "treatment", 9, 5000),
+        result(exp.experiment_id, "control", 10, 400),
+        result(exp.experiment_id, "treatment", 10, 40),
+    ];
+
+    let step = step_results(&exp, &results);
+    assert_eq!(step.len(), 2);
+    let thin = evaluate_guardrails(&step, &config);
+    assert!(!thin.should_pause);
+    assert!(!thin.sufficient_samples);
+    assert_eq!(next_transition(&exp, Some(&thin), at(12, 0)), None);
+    assert_eq!(next_transition(&exp, None, at(12, 0)), None);
+
+    let mut enough = step.clone();
+    enough.push(result(exp.experiment_id, "treatment", 11, 80));
+    let decision = evaluate_guardrails(&enough, &config);
+    assert!(decision.sufficient_samples);
+    assert_eq!(
+        next_transition(&exp, Some(&decision), at(12, 0)).map(|t| t.action),
+        Some(LifecycleAction::Ramped)
+    );
+}
+
+#[test]
+fn manual_actions_follow_the_lifecycle() {
+    let pause = manual_transition(ExperimentStatus::Running, LifecycleAction::Paused).unwrap();
+    assert_eq!(pause.to_status, ExperimentStatus::Paused);
+    let resume = manual_transition(ExperimentStatus::Paused, LifecycleAction::Resumed).unwrap();
+    assert_eq!(resume.to_status, ExperimentStatus::Running);
+    assert!(resume.restart_ramp_clock);
+    assert!(manual_transition(ExperimentStatus::Scheduled, LifecycleAction::Stopped).is_ok());
+
+    assert!(manual_transition(ExperimentStatus::Scheduled, LifecycleAction::Paused).is_err());
+    assert!(manual_transition(ExperimentStatus::Running, LifecycleAction::Resumed).is_err());
+    assert!(manual_transition(ExperimentStatus::Completed, LifecycleAction::Stopped).is_err());
+    assert!(manual_transition(ExperimentStatus::Running, LifecycleAction::Ramped).is_err());
+}
+
+#[test]
+fn exposure_only_grows_as_the_ramp_advances() {
+    let experiment_id = Uuid::new_v4();
+    let mut exposed_at_5 = 0;
+    for i in 0..5000 {
+        let customer = format!("cust-{}", i);
+        if is_exposed(&customer, experiment_id, 5) {
+            exposed_at_5 += 1;
+            assert!(is_exposed(&customer, experiment_id, 20));
+        }
+        assert!(!is_exposed(&customer, experiment_id, 0));
+        assert!(is_exposed(&customer, experiment_id, 100));
+    }
+    assert!((exposed_at_5 as f64 / 5000.0 - 0.05).abs() < 0.015);
+}
@@ -0,0 +1,168 @@
+use payments_gateway::domain::experiment::{AnalysisMethod, ExperimentResultRow, ExperimentVariant, VariantRetryPolicy};
+use payments_gateway::experiments::analyzer::{analyze, evaluate_guardrails, holm_adjust, GuardrailConfig};
+use payments_gateway::experiments::assigner::{assign_variant, validate_variants};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+fn variant(name: &str, traffic_pct: i32, gateway: Option<&str>) -> ExperimentVariant {
+    ExperimentVariant {
+        variant_name: name.to_string(),
+        traffic_pct,
+        override_gateway: gateway.map(str::to_string),
+        scoring_profile_id: None,
+        bandit_enabled: None,
+        rule_set: None,
+        retry_policy: None,
+    }
+}
+
+fn row(experiment_id: Uuid, variant: &str, total: i64, success: i64, p95: i32) -> ExperimentResultRow {
+    ExperimentResultRow {
+        experiment_id,
+        variant: variant.to_string(),
+        date_hour: chrono::Utc::now(),
+        total_requests: total,
+        successful_requests: success,
+        failed_requests: total - success,
+        avg_latency_ms: p95 / 2,
+        p95_latency_ms: p95,
+        total_revenue_minor: 0,
+    }
+}
+
+#[test]
+fn assignment_follows_variant_traffic_split() {
+    let experiment_id = Uuid::new_v4();
+    let variants = vec![
+        variant("control", 40, None),
+        variant("psp_a", 20, Some("razorpay_mock")),
+        variant("psp_b", 20, Some("cashfree_mock")),
+        variant("psp_c", 20, Some("payu_mock")),
+    ];
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for i in 0..10_000 {
+        let assignment = assign_variant(&format!("cust-{}", i), experiment_id, &variants);
+        let expected = variants.iter().find(|v| v.variant_name == assignment.variant).unwrap();
+        assert_eq!(assignment.override_gateway, expected.override_gateway);
+        *counts.entry(assignment.variant).or_default() += 1;
+    }
+    assert!((counts["control"] as f64 / 10_000.0 - 0.4).abs() < 0.03);
+    for name in ["psp_a", "psp_b", "psp_c"] {
+        assert!((counts[name] as f64 / 10_000.0 - 0.2).abs() < 0.03);
+    }
+
+    let again = assign_variant("cust-7", experiment_id, &variants);
+    assert_eq!(again.variant, assign_variant("cust-7", experiment_id, &variants).variant);
+}
+
+#[test]
+fn variants_are_validated() {
+    assert!(validate_variants(&[variant("control", 50, None), variant("a", 50, Some("g"))]).is_ok());
+    assert!(validate_variants(&[variant("control", 100, None)]).is_err());
+    assert!(validate_variants(&[variant("control", 50, None), variant("a", 40, Some("g"))]).is_err());
+    assert!(validate_variants(&[variant("a", 50, None), variant("b", 50, Some("g"))]).is_err());
+    assert!(validate_variants(&[variant("control", 50, Some("g")), variant("a", 50, None)]).is_err());
+    assert!(validate_variants(&[variant("control", 50, None), variant("a", 25, None), variant("a", 25, None)]).is_err());
+    assert!(validate_variants(&[variant("control", 120, None), variant("a", -20, None)]).is_err());
+}
+
+#[test]
+fn holm_adjustment_is_monotone_and_capped() {
+    let adjusted = holm_adjust(&[0.01, 0.04, 0.03]);
+    assert!((adjusted[0] - 0.03).abs() < 1e-12);
+    assert!((adjusted[2] - 0.06).abs() < 1e-12);
+    assert!((adjusted[1] - 0.06).abs() < 1e-12);
+    assert_eq!(holm_adjust(&[0.9, 0.8]), vec![1.0, 1.0]);
+    assert!(holm_adjust(&[]).is_empty());
+}
+
+#[test]
+fn analysis_corrects_for_multiple_variants() {
+    let id = Uuid::new_v4();
+    let rows = vec![
+        row(id, "control", 2000, 1800, 2000),
+        row(id, "psp_a", 2000, 1838, 2000),
+        row(id, "psp_b", 2000, 1800, 2000),
+        row(id, "psp_c", 2000, 1790, 2000),
+    ];
+
+    let out = analyze(&rows, 100, AnalysisMethod::FixedHorizon);
+    assert_eq!(out.correction, "HOLM");
+    assert_eq!(out.comparisons.len(), 3);
+    let a = out.comparisons.iter().find(|c| c.variant == "psp_a").unwrap();
+    assert!(a.p_value < 0.05);
+    assert!(a.adjusted_p_value > a.p_value);
+    assert!(!a.is_significant);
+    assert_eq!(out.winner, None);
+    assert_eq!(out.recommendation, "continue experiment");
+
+    let rows = vec![
+        row(id, "control", 2000, 1800, 2000),
+        row(id, "psp_a", 2000, 1940, 2000),
+        row(id, "psp_b", 2000, 1880, 2000),
+        row(id, "psp_c", 2000, 1790, 2000),
+    ];
+    let out = analyze(&rows, 100, AnalysisMethod::FixedHorizon);
+    assert_eq!(out.winner.as_deref(), Some("psp_a"));
+    assert_eq!(out.recommendation, "promote psp_a");
+    assert!((out.treatment_success_rate - 0.97).abs() < 1e-9);
+}
+
+#[test]
+fn guardrails_check_every_variant() {
+    let id = Uuid::new_v4();
+    let rows = vec![
+        row(id, "control", 1000, 950, 1900),
+        row(id, "psp_a", 1000, 955, 1800),
+        row(id, "psp_b", 1000, 940, 3500),
+    ];
+    let decision = evaluate_guardrails(&rows, &GuardrailConfig::default());
+    assert!(decision.should_pause);
+    assert_eq!(decision.variant.as_deref(), Some("psp_b"));
+    assert_eq!(decision.reason.as_deref(), Some("treatment_latency_above_guardrail"));
+}
+
+#[test]
+fn variants_can_change_the_routing_policy() {
+    let strategy = ExperimentVariant {
+        scoring_profile_id: Some("cost_first".to_string()),
+        bandit_enabled: Some(true),
+        rule_set: Some("no_icici".to_string()),
+        retry_policy: Some(VariantRetryPolicy {
+            max_attempts: 1,
+            latency_budget_ms: 5000,
+            retry_on_timeout: false,
+        }),
+        ..variant("strategy", 50, None)
+    };
+    assert!(strategy.changes_routing());
+    assert!(!variant("control", 50, None).changes_routing());
+    assert!(validate_variants(&[variant("control", 50, None), strategy.clone()]).is_ok());
+
+    let bandit_control = ExperimentVariant {
+        bandit_enabled: Some(false),
+        ..variant("control", 50, None)
+    };
+    assert!(validate_variants(&[bandit_control, strategy.clone()]).is_err());
+
+    let no_attempts = ExperimentVariant {
+        retry_policy: Some(VariantRetryPolicy {
+            max_attempts: 0,
+            latency_budget_ms: 5000,
+            retry_on_timeout: false,
+        }),
+        ..strategy.clone()
+    };
+    assert!(validate_variants(&[variant("control", 50, None), no_attempts]).is_err());
+
+    let blank_rule_set = ExperimentVariant {
+        rule_set: Some(" ".to_string()),
+        ..strategy
+    };
+    assert!(validate_variants(&[variant("control", 50, None), blank_rule_set]).is_err());
+
+    let parsed: ExperimentVariant =
+        serde_json::from_str(r#"{"variant_name": "b", "traffic_pct": 50, "bandit_enabled": false}"#).unwrap();
+    assert_eq!(parsed.bandit_enabled, Some(false));
+    assert_eq!(parsed.rule_set, None);
+}
@@ -0,0 +1,90 @@
+use payments_gateway::gateways::bulkhead::{lease_ttl_ms, live_leases, BulkheadDecision, BulkheadLimits, GatewayBulkhead};
+use payments_gateway::gateways::GatewayConfig;
+
+#[test]
+fn limits_treat_missing_and_non_positive_as_unlimited() {
+    let mut g = gateway(None, None);
+    assert!(BulkheadLimits::from_config(&g).is_unlimited());
+
+    g.max_in_flight = Some(-5);
+    g.max_tps = Some(50);
+    let limits = BulkheadLimits::from_config(&g);
+    assert_eq!(limits.max_in_flight, 0);
+    assert_eq!(limits.max_tps, 50);
+    assert!(!limits.is_unlimited());
+}
+
+#[test]
+fn saturation_maps_to_bulkhead_full_skip_reason() {
+    assert_eq!(BulkheadDecision::from_code(1, None).skip_reason(), Some("bulkhead_full"));
+    assert_eq!(BulkheadDecision::from_code(2, None).skip_reason(), Some("bulkhead_full"));
+
+    let acquired = BulkheadDecision::from_code(0, Some("lease-1".to_string()));
+    assert_eq!(acquired, BulkheadDecision::Acquired(Some("lease-1".to_string())));
+    assert_eq!(acquired.skip_reason(), None);
+}
+
+#[test]
+fn lease_outlives_gateway_timeout() {
+    assert_eq!(lease_ttl_ms(1200), 6200);
+    assert_eq!(lease_ttl_ms(0), 5100);
+}
+
+#[test]
+fn in_flight_limit_is_checked_before_tps() {
+    let limits = BulkheadLimits::from_config(&gateway(Some(2), Some(10)));
+    assert_eq!(limits.admission_code(1, 9), 0);
+    assert_eq!(limits.admission_code(2, 0), 1);
+    assert_eq!(limits.admission_code(2, 10), 1);
+    assert_eq!(limits.admission_code(1, 10), 2);
+
+    let tps_only = BulkheadLimits::from_config(&gateway(None, Some(3)));
+    assert_eq!(tps_only.admission_code(1_000, 2), 0);
+    assert_eq!(tps_only.admission_code(0, 3), 2);
+
+    let unlimited = BulkheadLimits::from_config(&gateway(None, None));
+    assert_eq!(unlimited.admission_code(1_000, 1_000), 0);
+}
+
+#[test]
+fn expired_leases_free_in_flight_slots() {
+    let now_ms = 1_000_000;
+    let expiries = [now_ms - 1, now_ms, now_ms + 1, now_ms + lease_ttl_ms(1200)];
+    assert_eq!(live_leases(&expiries, now_ms), 2);
+
+    let limits = BulkheadLimits::from_config(&gateway(Some(3), None));
+    assert_eq!(limits.admission_code(live_leases(&expiries, now_ms), 0), 0);
+    assert_eq!(limits.admission_code(expiries.len() as i64, 0), 1);
+}
+
+#[test]
+fn tps_is_counted_per_second() {
+    let first = GatewayBulkhead::tps_key("HDFC_Mock", 1_700_000_000);
+    assert_eq!(first, "bulkhead:tps:hdfc_mock:1700000000");
+    assert_ne!(first, GatewayBulkhead::tps_key("hdfc_mock", 1_700_000_001));
+}
+
+#[test]
+fn redis_errors_fail_open_without_a_lease() {
+    let decision = BulkheadDecision::or_fail_open(Err(anyhow::anyhow!("connection refused")), "hdfc_mock");
+    assert_eq!(decision, BulkheadDecision::Acquired(None));
+    assert_eq!(decision.skip_reason(), None);
+
+    let full = BulkheadDecision::or_fail_open(Ok(BulkheadDecision::InFlightFull), "hdfc_mock");
+    assert_eq!(full.skip_reason(), Some("bulkhead_full"));
+}
+
+fn gateway(max_in_flight: Option<i32>, max_tps: Option<i32>) -> GatewayConfig {
+    GatewayConfig {
+        gateway_id: "hdfc_mock".to_string(),
+        gateway_name: "HDFC".to_string(),
+        adapter_type: "MOCK".to_string(),
+        is_enabled: true,
+        priority: 1,
+        supported_methods: vec!["UPI".to_string()],
+        timeout_ms: 1200,
+        mock_behavior: None,
+        max_in_flight,
+        max_tps,
+    }
+}
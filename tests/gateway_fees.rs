@@ -0,0 +1,119 @@
+use payments_gateway::gateways::GatewayConfig;
+use payments_gateway::scoring::cost::{card_network, cost_score, expected_fee_minor, select_slab, FeeSlab};
+use payments_gateway::scoring::engine::rank_gateways;
+use payments_gateway::scoring::hierarchy::MetricLevel;
+use payments_gateway::scoring::types::{GatewayCandidate, ScoreInputs, ScoreWeights};
+
+#[test]
+fn detects_card_networks() {
+    assert_eq!(card_network("4111111111111111"), Some("VISA"));
+    assert_eq!(card_network("5555555555554444"), Some("MASTERCARD"));
+    assert_eq!(card_network("2223000048400011"), Some("MASTERCARD"));
+    assert_eq!(card_network("6521000000000000"), Some("RUPAY"));
+    assert_eq!(card_network("378282246310005"), Some("AMEX"));
+    assert_eq!(card_network("9999"), None);
+}
+
+#[test]
+fn network_specific_slab_wins_over_any() {
+    let slabs = vec![
+        slab("g1", "CARD", "ANY", 0, None, 2.0, 0),
+        slab("g1", "CARD", "RUPAY", 0, Some(200_000), 0.0, 0),
+    ];
+    assert_eq!(select_slab(&slabs, "CARD", Some("RUPAY"), None, 100_000).unwrap().card_network, "RUPAY");
+    assert_eq!(select_slab(&slabs, "CARD", Some("RUPAY"), None, 500_000).unwrap().card_network, "ANY");
+    assert_eq!(select_slab(&slabs, "CARD", Some("VISA"), None, 100_000).unwrap().card_network, "ANY");
+    assert!(select_slab(&slabs, "UPI", None, None, 100_000).is_none());
+}
+
+#[test]
+fn fee_includes_fixed_component_and_gst() {
+    let slabs = vec![
+        slab("g1", "NETBANKING", "ANY", 0, Some(1_000_000), 1.5, 0),
+        slab("g1", "NETBANKING", "ANY", 1_000_001, None, 1.0, 500),
+    ];
+    assert_eq!(expected_fee_minor(&slabs, "NETBANKING", None, None, 100_000), Some(1770));
+    assert_eq!(expected_fee_minor(&slabs, "NETBANKING", None, None, 2_000_000), Some(24_190));
+}
+
+#[test]
+fn cost_score_is_normalized() {
+    assert_eq!(cost_score(Some(0), 100_000), 1.0);
+    assert_eq!(cost_score(Some(5_000), 100_000), 0.0);
+    assert!((cost_score(Some(1_500), 100_000) - 0.5).abs() < 1e-9);
+    assert_eq!(cost_score(None, 100_000), 0.5);
+}
+
+#[test]
+fn cost_weight_trades_success_rate_for_lower_fee() {
+    let cheap = candidate("cheap", 0.93, 1.0);
+    let reliable = candidate("reliable", 0.95, 0.2);
+
+    let mut weights = ScoreWeights {
+        success_rate_weight: 0.35,
+        latency_weight: 0.25,
+        method_affinity_weight: 0.15,
+        bank_affinity_weight: 0.12,
+        amount_fit_weight: 0.08,
+        time_weight: 0.05,
+        cost_weight: 0.0,
+    };
+    let ranked = rank_gateways(&[cheap.clone(), reliable.clone()], &weights);
+    assert_eq!(ranked[0].gateway_id, "reliable");
+
+    weights.success_rate_weight = 0.30;
+    weights.cost_weight = 0.05;
+    let ranked = rank_gateways(&[cheap, reliable], &weights);
+    assert_eq!(ranked[0].gateway_id, "cheap");
+    assert_eq!(ranked[0].breakdown.cost_score, 1.0);
+}
+
+fn slab(
+    gateway_id: &str,
+    method: &str,
+    network: &str,
+    min: i64,
+    max: Option<i64>,
+    pct_fee: f64,
+    fixed_fee_minor: i64,
+) -> FeeSlab {
+    FeeSlab {
+        gateway_id: gateway_id.to_string(),
+        payment_method: method.to_string(),
+        card_network: network.to_string(),
+        card_type: "ANY".to_string(),
+        min_amount_minor: min,
+        max_amount_minor: max,
+        pct_fee,
+        fixed_fee_minor,
+        gst_pct: 18.0,
+    }
+}
+
+fn candidate(id: &str, success_rate: f64, cost_score: f64) -> GatewayCandidate {
+    GatewayCandidate {
+        gateway: GatewayConfig {
+            gateway_id: id.to_string(),
+            gateway_name: id.to_string(),
+            adapter_type: "MOCK".to_string(),
+            is_enabled: true,
+            priority: 1,
+            supported_methods: vec!["CARD".to_string()],
+            timeout_ms: 1000,
+            mock_behavior: None,
+            max_in_flight: None,
+            max_tps: None,
+        },
+        inputs: ScoreInputs {
+            success_rate,
+            p95_latency_ms: 800,
+            method_affinity: 0.8,
+            bank_affinity: 0.5,
+            amount_fit: 0.8,
+            time_multiplier: 1.0,
+            cost_score,
+            metric_level: MetricLevel::GatewayMethodBank,
+            signal_windows: Vec::new(),
+        },
+    }
+}
@@ -0,0 +1,67 @@
+use payments_gateway::scoring::issuers::{
+    issuer_affinity, AliasType, IssuerAlias, IssuerRegistry, DEFAULT_ISSUER_AFFINITY, UNKNOWN_ISSUER,
+    UNKNOWN_ISSUER_AFFINITY,
+};
+use payments_gateway::service::routing_config::RoutingConfigSnapshot;
+use std::collections::HashMap;
+
+#[test]
+fn upi_handles_and_netbanking_codes_map_to_one_issuer() {
+    let registry = registry();
+    assert_eq!(registry.canonical(Some(AliasType::UpiHandle), Some("okhdfcbank")), "HDFC");
+    assert_eq!(registry.canonical(Some(AliasType::NetbankingCode), Some("HDFC")), "HDFC");
+    assert_eq!(registry.canonical(Some(AliasType::BinBank), Some("HDFC")), "HDFC");
+    assert_eq!(registry.canonical(Some(AliasType::NetbankingCode), Some("UTIB")), "AXIS");
+}
+
+#[test]
+fn unmapped_values_pass_through_and_missing_is_unknown() {
+    let registry = registry();
+    assert_eq!(registry.canonical(Some(AliasType::UpiHandle), Some("paytm")), "PAYTM");
+    assert_eq!(registry.canonical(Some(AliasType::UpiHandle), Some("UTIB")), "UTIB");
+    assert_eq!(registry.canonical(None, Some("utib")), "AXIS");
+    assert_eq!(registry.canonical(None, Some("BIN:411111")), "BIN:411111");
+    assert_eq!(registry.canonical(Some(AliasType::UpiHandle), None), UNKNOWN_ISSUER);
+    assert_eq!(registry.canonical(None, Some("  ")), UNKNOWN_ISSUER);
+}
+
+#[test]
+fn affinity_comes_from_table_with_defaults() {
+    let mut table = HashMap::new();
+    table.insert(("hdfc_mock".to_string(), "HDFC".to_string()), 1.0);
+    table.insert(("icici_mock".to_string(), "HDFC".to_string()), 0.3);
+
+    assert_eq!(issuer_affinity(&table, "hdfc_mock", "HDFC"), 1.0);
+    assert_eq!(issuer_affinity(&table, "icici_mock", "HDFC"), 0.3);
+    assert_eq!(issuer_affinity(&table, "axis_mock", "HDFC"), DEFAULT_ISSUER_AFFINITY);
+    assert_eq!(issuer_affinity(&table, "axis_mock", UNKNOWN_ISSUER), UNKNOWN_ISSUER_AFFINITY);
+}
+
+#[test]
+fn snapshot_resolves_upi_handle_to_matching_gateway_affinity() {
+    let mut snapshot = RoutingConfigSnapshot {
+        issuers: registry(),
+        ..Default::default()
+    };
+    snapshot
+        .issuer_affinity
+        .insert(("hdfc_mock".to_string(), "HDFC".to_string()), 1.0);
+
+    let issuer = snapshot.canonical_issuer(Some(AliasType::UpiHandle), Some("OKHDFCBANK"));
+    assert_eq!(snapshot.issuer_affinity("hdfc_mock", &issuer), 1.0);
+    assert_eq!(snapshot.issuer_affinity("razorpay_real", &issuer), DEFAULT_ISSUER_AFFINITY);
+}
+
+fn registry() -> IssuerRegistry {
+    let alias = |alias_type, alias: &str, issuer_id: &str| IssuerAlias {
+        alias_type,
+        alias: alias.to_string(),
+        issuer_id: issuer_id.to_string(),
+    };
+    IssuerRegistry::new(vec![
+        alias(AliasType::UpiHandle, "OKHDFCBANK", "HDFC"),
+        alias(AliasType::NetbankingCode, "HDFC", "HDFC"),
+        alias(AliasType::NetbankingCode, "UTIB", "AXIS"),
+        alias(AliasType::BinBank, "HDFC", "HDFC"),
+    ])
+}
@@ -0,0 +1,119 @@
+use chrono::TimeZone;
+use payments_gateway::scoring::schedule::{
+    active_window, apply_penalties, parse_timezone, validate_window, MaintenanceMode, MaintenanceWindow,
+    MaintenanceWindowInput,
+};
+use payments_gateway::scoring::types::{RankedGateway, ScoreBreakdown};
+use std::collections::HashMap;
+use payments_gateway::service::routing_config::RoutingConfigSnapshot;
+use uuid::Uuid;
+
+fn at(hour: u32, minute: u32) -> chrono::DateTime<chrono::Utc> {
+    chrono::Utc.with_ymd_and_hms(2026, 3, 10, hour, minute, 0).unwrap()
+}
+
+fn window(mode: MaintenanceMode, methods: &[&str], issuers: &[&str], multiplier: f64) -> MaintenanceWindow {
+    MaintenanceWindow {
+        window_id: Uuid::new_v4(),
+        gateway_id: "hdfc_mock".to_string(),
+        starts_at: at(20, 30),
+        ends_at: at(22, 30),
+        payment_methods: methods.iter().map(|m| m.to_string()).collect(),
+        issuers: issuers.iter().map(|i| i.to_string()).collect(),
+        mode,
+        penalty_multiplier: multiplier,
+        reason: None,
+    }
+}
+
+#[test]
+fn windows_match_gateway_time_method_and_issuer() {
+    let w = window(MaintenanceMode::Exclude, &["UPI"], &["SBI"], 0.0);
+
+    assert!(w.applies("hdfc_mock", "upi", "sbi", at(21, 0)));
+    assert!(w.applies("hdfc_mock", "UPI", "SBI", at(20, 30)));
+    assert!(!w.applies("hdfc_mock", "UPI", "SBI", at(22, 30)));
+    assert!(!w.applies("hdfc_mock", "CARD", "SBI", at(21, 0)));
+    assert!(!w.applies("hdfc_mock", "UPI", "HDFC", at(21, 0)));
+    assert!(!w.applies("icici_mock", "UPI", "SBI", at(21, 0)));
+
+    let everything = window(MaintenanceMode::Exclude, &[], &[], 0.0);
+    assert!(everything.applies("hdfc_mock", "NETBANKING", "UNKNOWN", at(21, 0)));
+}
+
+#[test]
+fn exclude_wins_over_penalize_and_strongest_penalty_wins() {
+    let mild = window(MaintenanceMode::Penalize, &[], &[], 0.8);
+    let harsh = window(MaintenanceMode::Penalize, &[], &[], 0.3);
+    let exclude = window(MaintenanceMode::Exclude, &["CARD"], &[], 0.0);
+    let windows = vec![mild, harsh.clone(), exclude.clone()];
+
+    let upi = active_window(&windows, "hdfc_mock", "UPI", "SBI", at(21, 0)).unwrap();
+    assert_eq!(upi.window_id, harsh.window_id);
+    let card = active_window(&windows, "hdfc_mock", "CARD", "SBI", at(21, 0)).unwrap();
+    assert_eq!(card.window_id, exclude.window_id);
+    assert!(active_window(&windows, "hdfc_mock", "UPI", "SBI", at(23, 0)).is_none());
+
+    let snapshot = RoutingConfigSnapshot {
+        maintenance_windows: windows,
+        ..Default::default()
+    };
+    assert_eq!(
+        snapshot.maintenance_window("hdfc_mock", "CARD", "SBI", at(21, 0)).map(|w| w.mode),
+        Some(MaintenanceMode::Exclude)
+    );
+}
+
+#[test]
+fn window_input_validation() {
+    let input = |mode: MaintenanceMode, multiplier: Option<f64>, ends_at| MaintenanceWindowInput {
+        gateway_id: "hdfc_mock".to_string(),
+        starts_at: at(20, 30),
+        ends_at,
+        payment_methods: Vec::new(),
+        issuers: Vec::new(),
+        mode,
+        penalty_multiplier: multiplier,
+        reason: Some("bank downtime".to_string()),
+    };
+
+    assert!(validate_window(&input(MaintenanceMode::Exclude, None, at(22, 30))).is_ok());
+    assert!(validate_window(&input(MaintenanceMode::Penalize, Some(0.5), at(22, 30))).is_ok());
+    assert!(validate_window(&input(MaintenanceMode::Penalize, Some(1.5), at(22, 30))).is_err());
+    assert!(validate_window(&input(MaintenanceMode::Exclude, Some(0.5), at(22, 30))).is_err());
+    assert!(validate_window(&input(MaintenanceMode::Exclude, None, at(20, 0))).is_err());
+
+    assert_eq!(parse_timezone("Asia/Kolkata"), Some(chrono_tz::Asia::Kolkata));
+    assert!(parse_timezone("Mars/Olympus").is_none());
+}
+
+fn ranked(gateway_id: &str, score: f64) -> RankedGateway {
+    RankedGateway {
+        gateway_id: gateway_id.to_string(),
+        score,
+        breakdown: ScoreBreakdown {
+            success_rate_score: score,
+            latency_score: score,
+            method_affinity: score,
+            bank_affinity: score,
+            amount_fit: score,
+            time_weight: score,
+            cost_score: score,
+            final_score: score,
+            metric_level: None,
+            signal_windows: Vec::new(),
+        },
+    }
+}
+
+#[test]
+fn penalties_scale_the_final_score_and_reorder() {
+    let penalties = HashMap::from([("hdfc_mock".to_string(), 0.5)]);
+    let out = apply_penalties(vec![ranked("hdfc_mock", 0.9), ranked("icici_mock", 0.6)], &penalties);
+    assert_eq!(out[0].gateway_id, "icici_mock");
+    assert_eq!(out[1].gateway_id, "hdfc_mock");
+    assert!((out[1].score - 0.45).abs() < 1e-12);
+
+    let untouched = apply_penalties(vec![ranked("hdfc_mock", 0.9)], &HashMap::new());
+    assert_eq!(untouched[0].score, 0.9);
+}
@@ -0,0 +1,51 @@
+use payments_gateway::scoring::hierarchy::{shrink, smooth, LevelStats, MetricLevel, SmoothedMetric, PRIOR_STRENGTH};
+
+#[test]
+fn no_data_falls_back_to_global_prior() {
+    let out = smooth(None, None, None);
+    assert_eq!(out, SmoothedMetric::default());
+    assert_eq!(out.level, MetricLevel::GlobalPrior);
+    assert_eq!((out.success_rate, out.p95_latency_ms), (0.5, 1500));
+}
+
+#[test]
+fn finest_level_with_data_is_reported() {
+    let gateway = stats(0.9, 800, 1_000);
+    let method = stats(0.95, 600, 400);
+
+    assert_eq!(smooth(None, None, Some(gateway)).level, MetricLevel::Gateway);
+    assert_eq!(smooth(None, Some(method), Some(gateway)).level, MetricLevel::GatewayMethod);
+    assert_eq!(
+        smooth(Some(stats(0.7, 900, 3)), Some(method), Some(gateway)).level,
+        MetricLevel::GatewayMethodBank
+    );
+    assert_eq!(
+        smooth(Some(stats(0.7, 900, 0)), Some(method), Some(gateway)).level,
+        MetricLevel::GatewayMethod
+    );
+}
+
+#[test]
+fn small_samples_shrink_toward_parent() {
+    let parent = SmoothedMetric {
+        success_rate: 0.9,
+        p95_latency_ms: 800,
+        level: MetricLevel::GatewayMethod,
+    };
+
+    let sparse = shrink(&stats(0.0, 3000, 2), &parent, PRIOR_STRENGTH, MetricLevel::GatewayMethodBank);
+    assert!((sparse.success_rate - 18.0 / 22.0).abs() < 1e-9);
+    assert_eq!(sparse.p95_latency_ms, 1000);
+
+    let dense = shrink(&stats(0.6, 3000, 10_000), &parent, PRIOR_STRENGTH, MetricLevel::GatewayMethodBank);
+    assert!((dense.success_rate - 0.6).abs() < 0.001);
+    assert!(dense.p95_latency_ms > 2990);
+}
+
+fn stats(success_rate: f64, p95_latency_ms: i32, total_requests: u64) -> LevelStats {
+    LevelStats {
+        success_rate,
+        p95_latency_ms,
+        total_requests,
+    }
+}
@@ -1,14 +1,15 @@
-use payments_gateway::domain::experiment::{ExperimentFilter, ExperimentResultRow};
+use payments_gateway::domain::experiment::{AnalysisMethod, ExperimentFilter, ExperimentResultRow};
 use payments_gateway::experiments::analyzer::{analyze, evaluate_guardrails, GuardrailConfig};
-use payments_gateway::experiments::assigner::assign_variant;
+use payments_gateway::experiments::assigner::{assign_variant, legacy_variants};
 use payments_gateway::experiments::filter::{matches, MatchInput};
 use uuid::Uuid;
 
 #[test]
 fn assignment_is_deterministic() {
     let id = Uuid::new_v4();
-    let a = assign_variant("cust-1", id, 90);
-    let b = assign_variant("cust-1", id, 90);
+    let variants = legacy_variants(90, 10, "razorpay_mock");
+    let a = assign_variant("cust-1", id, &variants);
+    let b = assign_variant("cust-1", id, &variants);
     assert_eq!(a.variant, b.variant);
     assert_eq!(a.bucket, b.bucket);
 }
@@ -22,6 +23,8 @@ fn filter_matching_works() {
         max_amount_minor: Some(10_000),
         merchant_id: Some("m1".to_string()),
         amount_bucket: Some("500_2000".to_string()),
+        card_network: None,
+        card_type: None,
     };
 
     let input = MatchInput {
@@ -29,6 +32,8 @@ fn filter_matching_works() {
         amount_minor: 5000,
         merchant_id: "m1".to_string(),
         amount_bucket: "500_2000".to_string(),
+        card_network: None,
+        card_type: None,
     };
 
     assert!(matches(&filter, &input));
@@ -63,7 +68,7 @@ fn z_test_returns_winner_for_clear_gap() {
         },
     ];
 
-    let out = analyze(&rows, 100);
+    let out = analyze(&rows, 100, AnalysisMethod::FixedHorizon);
     assert!(out.is_significant);
     assert_eq!(out.winner.as_deref(), Some("treatment"));
 }
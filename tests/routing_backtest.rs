@@ -0,0 +1,275 @@
+use payments_gateway::gateways::GatewayConfig;
+use payments_gateway::scoring::backtest::{
+    AttemptOutcome, BacktestCase, BacktestScenario, BacktestStrategy, Backtester, MetricHistory, MetricSnapshot,
+};
+use payments_gateway::scoring::blend::{SignalBlend, WindowWeight};
+use payments_gateway::scoring::hierarchy::MetricLevel;
+use payments_gateway::scoring::profiles::{ProfileAssignment, ResolvedProfile};
+use payments_gateway::scoring::schedule::{MaintenanceMode, MaintenanceWindow};
+use payments_gateway::scoring::types::ScoreWeights;
+use payments_gateway::service::routing_config::RoutingConfigSnapshot;
+use std::collections::HashMap;
+
+#[test]
+fn latency_heavy_weights_change_top_choice() {
+    let now = chrono::Utc::now();
+    let gateways = vec![gateway("fast_mock", 2), gateway("reliable_mock", 1)];
+    let mut metrics = MetricHistory::default();
+    metrics.insert("fast_mock", "UPI", "HDFC", snapshot(now, 0.50, 300));
+    metrics.insert("reliable_mock", "UPI", "HDFC", snapshot(now, 0.99, 1500));
+    let config = snapshot_with(gateways);
+
+    let backtester = Backtester {
+        config: &config,
+        metrics: &metrics,
+    };
+    let cases = vec![case(now, vec![])];
+
+    let baseline = backtester.run(&cases, &BacktestScenario::baseline());
+    assert_eq!(baseline.top_choice_changed, 0);
+    assert_eq!(baseline.selection_share.get("reliable_mock"), Some(&1.0));
+
+    let latency_heavy = BacktestScenario {
+        name: "latency_heavy".to_string(),
+        strategy: BacktestStrategy::Scoring,
+        weights: Some(ScoreWeights {
+            success_rate_weight: 0.05,
+            latency_weight: 0.80,
+            method_affinity_weight: 0.05,
+            bank_affinity_weight: 0.05,
+            amount_fit_weight: 0.03,
+            time_weight: 0.02,
+            cost_weight: 0.0,
+        }),
+        method_affinity: HashMap::new(),
+        amount_fit: HashMap::new(),
+    };
+    let report = backtester.run(&cases, &latency_heavy);
+    assert_eq!(report.top_choice_changed, 1);
+    assert!(report.success_rate_delta < -0.15);
+    assert!(report.latency_delta_ms < 0.0);
+}
+
+#[test]
+fn recorded_attempt_outcome_overrides_snapshot_estimate() {
+    let now = chrono::Utc::now();
+    let gateways = vec![gateway("g1", 1)];
+    let mut metrics = MetricHistory::default();
+    metrics.insert("g1", "UPI", "HDFC", snapshot(now, 0.95, 500));
+    let config = snapshot_with(gateways);
+    let backtester = Backtester {
+        config: &config,
+        metrics: &metrics,
+    };
+
+    let failed = case(
+        now,
+        vec![AttemptOutcome {
+            gateway_id: "g1".to_string(),
+            success: false,
+            latency_ms: 900,
+        }],
+    );
+    assert_eq!(backtester.estimate_outcome(&failed, "g1"), Some((0.0, 900.0)));
+
+    let stale = case(now + chrono::Duration::minutes(30), vec![]);
+    assert_eq!(backtester.estimate_outcome(&stale, "g1"), None);
+}
+
+#[test]
+fn priority_strategy_picks_lowest_priority_value() {
+    let now = chrono::Utc::now();
+    let gateways = vec![gateway("second", 2), gateway("first", 1)];
+    let metrics = MetricHistory::default();
+    let config = snapshot_with(gateways);
+    let backtester = Backtester {
+        config: &config,
+        metrics: &metrics,
+    };
+    let scenario = BacktestScenario {
+        strategy: BacktestStrategy::Priority,
+        ..BacktestScenario::baseline()
+    };
+    let report = backtester.run(&[case(now, vec![])], &scenario);
+    assert_eq!(report.selection_share.get("first"), Some(&1.0));
+}
+
+#[test]
+fn backtest_applies_live_maintenance_windows() {
+    let now = chrono::Utc::now();
+    let mut metrics = MetricHistory::default();
+    metrics.insert("fast_mock", "UPI", "HDFC", snapshot(now, 0.50, 300));
+    metrics.insert("reliable_mock", "UPI", "HDFC", snapshot(now, 0.99, 1500));
+    let config = snapshot_with(vec![gateway("fast_mock", 2), gateway("reliable_mock", 1)]);
+    let cases = vec![case(now, vec![])];
+
+    let baseline = Backtester {
+        config: &config,
+        metrics: &metrics,
+    }
+    .run(&cases, &BacktestScenario::baseline());
+    assert_eq!(baseline.selection_share.get("reliable_mock"), Some(&1.0));
+
+    for (mode, multiplier) in [(MaintenanceMode::Penalize, 0.5), (MaintenanceMode::Exclude, 1.0)] {
+        let mut config = config.clone();
+        config.maintenance_windows = vec![MaintenanceWindow {
+            window_id: uuid::Uuid::new_v4(),
+            gateway_id: "reliable_mock".to_string(),
+            starts_at: now - chrono::Duration::hours(1),
+            ends_at: now + chrono::Duration::hours(1),
+            payment_methods: Vec::new(),
+            issuers: Vec::new(),
+            mode,
+            penalty_multiplier: multiplier,
+            reason: None,
+        }];
+        let report = Backtester {
+            config: &config,
+            metrics: &metrics,
+        }
+        .run(&cases, &BacktestScenario::baseline());
+        assert_eq!(report.selection_share.get("fast_mock"), Some(&1.0));
+    }
+}
+
+#[test]
+fn backtest_uses_the_merchant_profile_and_its_signal_blend() {
+    let now = chrono::Utc::now();
+    let mut metrics = MetricHistory::default();
+    metrics.insert("fast_mock", "UPI", "HDFC", window_snapshot(now, 5, 0.50, 300));
+    metrics.insert("reliable_mock", "UPI", "HDFC", window_snapshot(now, 5, 0.99, 1500));
+    metrics.insert("fast_mock", "UPI", "HDFC", window_snapshot(now, 60, 0.99, 300));
+    metrics.insert("reliable_mock", "UPI", "HDFC", window_snapshot(now, 60, 0.60, 1500));
+    let mut config = snapshot_with(vec![gateway("fast_mock", 2), gateway("reliable_mock", 1)]);
+    let cases = vec![case(now, vec![])];
+
+    let report = Backtester {
+        config: &config,
+        metrics: &metrics,
+    }
+    .run(&cases, &BacktestScenario::baseline());
+    assert_eq!(report.selection_share.get("reliable_mock"), Some(&1.0));
+
+    config.profiles.insert(
+        "hourly".to_string(),
+        ResolvedProfile {
+            profile_id: "hourly".to_string(),
+            version: 1,
+            weights: default_weights(),
+            signal_blend: SignalBlend {
+                windows: vec![WindowWeight {
+                    window_minutes: 60,
+                    weight: 1.0,
+                }],
+            },
+        },
+    );
+    config.profile_assignments.push(ProfileAssignment {
+        assignment_id: uuid::Uuid::new_v4(),
+        merchant_id: Some("m1".to_string()),
+        payment_method: None,
+        amount_bucket: None,
+        profile_id: "hourly".to_string(),
+    });
+    let backtester = Backtester {
+        config: &config,
+        metrics: &metrics,
+    };
+    let report = backtester.run(&cases, &BacktestScenario::baseline());
+    assert_eq!(report.selection_share.get("fast_mock"), Some(&1.0));
+
+    let signal = backtester.signal("fast_mock", &cases[0], &config.blend_for(None));
+    let hourly = backtester.signal("fast_mock", &cases[0], &config.blend_for(config.profiles.get("hourly")));
+    assert!(hourly.success_rate > signal.success_rate);
+    assert_eq!(hourly.windows.len(), 1);
+    assert_eq!(hourly.windows[0].window_minutes, 60);
+}
+
+#[test]
+fn backtest_smooths_sparse_banks_toward_rollup_levels() {
+    let now = chrono::Utc::now();
+    let mut metrics = MetricHistory::default();
+    metrics.insert(
+        "g1",
+        "UPI",
+        "HDFC",
+        MetricSnapshot {
+            total_requests: 2,
+            ..window_snapshot(now, 5, 0.0, 900)
+        },
+    );
+    metrics.insert("g1", "UPI", "ALL", window_snapshot(now, 5, 0.95, 400));
+    let config = snapshot_with(vec![gateway("g1", 1)]);
+    let backtester = Backtester {
+        config: &config,
+        metrics: &metrics,
+    };
+    let signal = backtester.signal("g1", &case(now, vec![]), &SignalBlend::default());
+    assert_eq!(signal.level, MetricLevel::GatewayMethodBank);
+    assert!(signal.success_rate > 0.8);
+}
+
+fn snapshot_with(gateways: Vec<GatewayConfig>) -> RoutingConfigSnapshot {
+    RoutingConfigSnapshot {
+        gateways,
+        default_weights: default_weights(),
+        ..Default::default()
+    }
+}
+
+fn case(created_at: chrono::DateTime<chrono::Utc>, attempts: Vec<AttemptOutcome>) -> BacktestCase {
+    BacktestCase {
+        payment_id: uuid::Uuid::new_v4(),
+        created_at,
+        merchant_id: "m1".to_string(),
+        method: "UPI".to_string(),
+        issuing_bank: "HDFC".to_string(),
+        amount_minor: 10_000,
+        amount_bucket: "lt_500".to_string(),
+        card_network: None,
+        card_type: None,
+        attempts,
+    }
+}
+
+fn snapshot(at: chrono::DateTime<chrono::Utc>, success_rate: f64, latency_ms: i32) -> MetricSnapshot {
+    window_snapshot(at, 5, success_rate, latency_ms)
+}
+
+fn window_snapshot(at: chrono::DateTime<chrono::Utc>, window_minutes: i64, success_rate: f64, latency_ms: i32) -> MetricSnapshot {
+    MetricSnapshot {
+        snapshot_minute: at - chrono::Duration::minutes(1),
+        window_minutes,
+        total_requests: 1_000,
+        success_rate,
+        avg_latency_ms: latency_ms,
+        p95_latency_ms: latency_ms,
+    }
+}
+
+fn default_weights() -> ScoreWeights {
+    ScoreWeights {
+        success_rate_weight: 0.35,
+        latency_weight: 0.25,
+        method_affinity_weight: 0.15,
+        bank_affinity_weight: 0.12,
+        amount_fit_weight: 0.08,
+        time_weight: 0.05,
+        cost_weight: 0.0,
+    }
+}
+
+fn gateway(id: &str, priority: i32) -> GatewayConfig {
+    GatewayConfig {
+        gateway_id: id.to_string(),
+        gateway_name: id.to_string(),
+        adapter_type: "MOCK".to_string(),
+        is_enabled: true,
+        priority,
+        supported_methods: vec!["UPI".to_string()],
+        timeout_ms: 1000,
+        mock_behavior: None,
+        max_in_flight: None,
+        max_tps: None,
+    }
+}
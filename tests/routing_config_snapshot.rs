@@ -0,0 +1,219 @@
+use chrono::TimeZone;
+use payments_gateway::bandit::segments::{BanditSegment, DEFAULT_DEFINITION_ID};
+use payments_gateway::domain::routing_rule::{RoutingRule, RuleAction, RuleConditions, DEFAULT_RULE_SET};
+use payments_gateway::gateways::GatewayConfig;
+use payments_gateway::repo::error_classification_repo::ErrorClass;
+use payments_gateway::repo::scoring_config_repo::TimePenalty;
+use payments_gateway::scoring::blend::SignalBlend;
+use payments_gateway::scoring::profiles::{ProfileAssignment, ResolvedProfile};
+use payments_gateway::scoring::types::ScoreWeights;
+use payments_gateway::service::routing_config::{RoutingConfigSnapshot, RoutingConfigStore};
+use uuid::Uuid;
+
+#[test]
+fn lookups_fall_back_to_repo_defaults() {
+    let mut snapshot = RoutingConfigSnapshot::default();
+    snapshot
+        .method_affinity
+        .insert(("hdfc_mock".to_string(), "UPI".to_string()), 0.9);
+    snapshot
+        .error_classes
+        .insert(("hdfc_mock".to_string(), "MOCK_TIMEOUT".to_string()), ErrorClass {
+            retryable: false,
+            timeout_like: true,
+            non_retryable_user_error: false,
+        });
+    snapshot.bin_banks.insert("411111".to_string(), "HDFC".to_string());
+
+    assert_eq!(snapshot.method_affinity("hdfc_mock", "UPI"), 0.9);
+    assert_eq!(snapshot.method_affinity("hdfc_mock", "CARD"), 0.7);
+    assert_eq!(snapshot.amount_fit("hdfc_mock", "lt_500"), 0.7);
+    assert!(snapshot.classify_error("hdfc_mock", "MOCK_TIMEOUT").timeout_like);
+    assert!(!snapshot.classify_error("hdfc_mock", "UNKNOWN").retryable);
+    assert_eq!(snapshot.bank_from_bin("4111111111111111"), Some("HDFC"));
+    assert_eq!(snapshot.bank_from_bin("4111"), None);
+    assert_eq!(snapshot.retry_policy("m_001").max_attempts, 3);
+    assert_eq!(snapshot.circuit_thresholds("hdfc_mock", "UPI").cooldown_seconds, 30);
+    assert!(snapshot.fee_slabs("hdfc_mock", "UPI").is_empty());
+    assert!(!snapshot.bandit_enabled(&BanditSegment::new(DEFAULT_DEFINITION_ID, "UPI:lt_500")));
+}
+
+#[test]
+fn time_penalties_match_in_their_own_timezone() {
+    let snapshot = RoutingConfigSnapshot {
+        time_penalties: vec![TimePenalty {
+            timezone: chrono_tz::Asia::Kolkata,
+            ..penalty(0, Some(11), 0.4)
+        }],
+        ..Default::default()
+    };
+
+    let ist_midnight = chrono::Utc.with_ymd_and_hms(2026, 3, 10, 18, 45, 0).unwrap();
+    let utc_midnight = chrono::Utc.with_ymd_and_hms(2026, 3, 11, 0, 15, 0).unwrap();
+
+    assert_eq!(snapshot.time_multiplier("hdfc_mock", ist_midnight), 0.4);
+    assert_eq!(snapshot.time_multiplier("hdfc_mock", utc_midnight), 1.0);
+}
+
+#[test]
+fn day_specific_time_penalty_beats_hourly_default() {
+    let snapshot = RoutingConfigSnapshot {
+        time_penalties: vec![
+            penalty(12, None, 0.9),
+            penalty(12, Some(10), 0.8),
+            penalty(18, None, 0.85),
+        ],
+        ..Default::default()
+    };
+
+    let on_tenth = chrono::Utc.with_ymd_and_hms(2026, 3, 10, 12, 30, 0).unwrap();
+    let on_eleventh = chrono::Utc.with_ymd_and_hms(2026, 3, 11, 12, 30, 0).unwrap();
+    let off_peak = chrono::Utc.with_ymd_and_hms(2026, 3, 10, 3, 0, 0).unwrap();
+
+    assert_eq!(snapshot.time_multiplier("hdfc_mock", on_tenth), 0.8);
+    assert_eq!(snapshot.time_multiplier("hdfc_mock", on_eleventh), 0.9);
+    assert_eq!(snapshot.time_multiplier("hdfc_mock", off_peak), 1.0);
+    assert_eq!(snapshot.time_multiplier("icici_mock", on_tenth), 1.0);
+}
+
+#[test]
+fn enabled_gateways_filter_by_method_and_keep_priority_order() {
+    let snapshot = RoutingConfigSnapshot {
+        gateways: vec![
+            gateway("razorpay_real", true, &["UPI", "CARD"]),
+            gateway("hdfc_mock", true, &["CARD"]),
+            gateway("axis_mock", false, &["UPI"]),
+            gateway("icici_mock", true, &["UPI"]),
+        ],
+        ..Default::default()
+    };
+
+    let ids: Vec<String> = snapshot
+        .enabled_gateways("UPI")
+        .into_iter()
+        .map(|g| g.gateway_id)
+        .collect();
+    assert_eq!(ids, vec!["razorpay_real", "icici_mock"]);
+}
+
+#[test]
+fn profile_resolution_uses_assignments_then_default() {
+    let snapshot = RoutingConfigSnapshot {
+        default_weights: weights(0.5),
+        profiles: [
+            ("default".to_string(), profile("default", 3, 0.35)),
+            ("latency_sensitive".to_string(), profile("latency_sensitive", 1, 0.2)),
+        ]
+        .into_iter()
+        .collect(),
+        profile_assignments: vec![ProfileAssignment {
+            assignment_id: Uuid::new_v4(),
+            merchant_id: Some("m_007".to_string()),
+            payment_method: None,
+            amount_bucket: None,
+            profile_id: "latency_sensitive".to_string(),
+        }],
+        ..Default::default()
+    };
+
+    let assigned = snapshot.resolve_profile("m_007", "UPI", "lt_500").unwrap();
+    assert_eq!(assigned.profile_id, "latency_sensitive");
+    let fallback = snapshot.resolve_profile("m_001", "UPI", "lt_500").unwrap();
+    assert_eq!((fallback.profile_id.as_str(), fallback.version), ("default", 3));
+
+    assert_eq!(snapshot.weights_for(Some(&assigned)).success_rate_weight, 0.2);
+    assert_eq!(snapshot.weights_for(None).success_rate_weight, 0.5);
+}
+
+#[test]
+fn swap_replaces_snapshot_without_disturbing_readers() {
+    let store = RoutingConfigStore::new(RoutingConfigSnapshot::default());
+    let before = store.current();
+    assert_eq!(before.version, 0);
+
+    let mut next = RoutingConfigSnapshot::default();
+    next.bandit_segments.insert(BanditSegment::new(DEFAULT_DEFINITION_ID, "UPI:lt_500"));
+    assert_eq!(store.swap(next), 1);
+    assert_eq!(store.swap(RoutingConfigSnapshot::default()), 2);
+
+    assert!(!before.bandit_enabled(&BanditSegment::new(DEFAULT_DEFINITION_ID, "UPI:lt_500")));
+    assert_eq!(store.current().version, 2);
+}
+
+#[test]
+fn routing_rules_are_grouped_by_rule_set() {
+    let snapshot = RoutingConfigSnapshot {
+        routing_rules: vec![
+            rule("exclude-icici", DEFAULT_RULE_SET),
+            rule("exclude-axis", "strict"),
+            rule("exclude-hdfc", "strict"),
+        ],
+        ..Default::default()
+    };
+
+    let names = |set: &str| -> Vec<String> { snapshot.routing_rules_for(set).into_iter().map(|r| r.name).collect() };
+    assert_eq!(names(DEFAULT_RULE_SET), vec!["exclude-icici"]);
+    assert_eq!(names("strict"), vec!["exclude-axis", "exclude-hdfc"]);
+    assert!(names("missing").is_empty());
+    assert!(snapshot.has_rule_set("strict"));
+    assert!(snapshot.has_rule_set(DEFAULT_RULE_SET));
+    assert!(!snapshot.has_rule_set("strcit"));
+}
+
+fn rule(name: &str, rule_set: &str) -> RoutingRule {
+    RoutingRule {
+        rule_id: Uuid::new_v4(),
+        name: name.to_string(),
+        priority: 1,
+        enabled: true,
+        conditions: RuleConditions::default(),
+        action: RuleAction::Exclude,
+        gateway_id: "icici_mock".to_string(),
+        boost: None,
+        rule_set: rule_set.to_string(),
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+fn penalty(hour_of_day: i32, day_of_month: Option<i32>, multiplier: f64) -> TimePenalty {
+    TimePenalty {
+        gateway_id: "hdfc_mock".to_string(),
+        hour_of_day,
+        day_of_month,
+        multiplier,
+        timezone: chrono_tz::Tz::UTC,
+    }
+}
+
+fn gateway(id: &str, is_enabled: bool, methods: &[&str]) -> GatewayConfig {
+    GatewayConfig {
+        gateway_id: id.to_string(),
+        gateway_name: id.to_string(),
+        adapter_type: "MOCK".to_string(),
+        is_enabled,
+        priority: 1,
+        supported_methods: methods.iter().map(|m| m.to_string()).collect(),
+        timeout_ms: 1000,
+        mock_behavior: None,
+        max_in_flight: None,
+        max_tps: None,
+    }
+}
+
+fn profile(profile_id: &str, version: i32, success_rate_weight: f64) -> ResolvedProfile {
+    ResolvedProfile {
+        profile_id: profile_id.to_string(),
+        version,
+        weights: weights(success_rate_weight),
+        signal_blend: SignalBlend::default(),
+    }
+}
+
+fn weights(success_rate_weight: f64) -> ScoreWeights {
+    ScoreWeights {
+        success_rate_weight,
+        latency_weight: 0.25,
+        ..ScoreWeights::default()
+    }
+}
@@ -12,6 +12,14 @@ fn routing_decision_record_serializes() {
         reason_summary: "top score selected".to_string(),
         score_breakdown_json: serde_json::json!({"success_rate_score": 0.95}),
         ranked_gateways_json: serde_json::json!([{"gateway_id":"hdfc_mock","score":0.91}]),
+        matched_rule_id: None,
+        matched_rule_ids: serde_json::json!([]),
+        dropped_gateways_json: serde_json::json!([]),
+        penalized_gateways_json: serde_json::json!([]),
+        scoring_profile_id: Some("default".to_string()),
+        scoring_profile_version: Some(1),
+        bandit_propensity: None,
+        bandit_selection_json: None,
         created_at: chrono::Utc::now(),
     };
 
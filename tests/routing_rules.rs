@@ -0,0 +1,246 @@
+use payments_gateway::domain::routing_rule::{RoutingRule, RuleAction, RuleConditions, DEFAULT_RULE_SET};
+use payments_gateway::router::rules::{
+    apply_boosts, evaluate, layer_production_excludes, resolve_force, validate_rule, RuleInput,
+};
+use payments_gateway::scoring::types::{RankedGateway, ScoreBreakdown};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[test]
+fn force_rule_for_large_hdfc_card_payments() {
+    let force = rule(
+        10,
+        RuleConditions {
+            payment_method: Some("CARD".to_string()),
+            issuing_bank: Some("HDFC".to_string()),
+            min_amount_minor: Some(5_000_000),
+            ..RuleConditions::default()
+        },
+        RuleAction::Force,
+        "hdfc_mock",
+        None,
+    );
+
+    let outcome = evaluate(std::slice::from_ref(&force), &input("CARD", "m_001", "hdfc", 6_000_000));
+    assert_eq!(outcome.forced_gateway.as_deref(), Some("hdfc_mock"));
+    assert_eq!(outcome.primary_rule_id(&gateways()), Some(force.rule_id));
+    assert!(outcome.allows("hdfc_mock"));
+    assert!(!outcome.allows("razorpay_real"));
+
+    let small = evaluate(&[force], &input("CARD", "m_001", "HDFC", 10_000));
+    assert!(small.forced_gateway.is_none());
+    assert!(small.matched_rule_ids.is_empty());
+}
+
+#[test]
+fn exclusions_accumulate_and_force_stops_evaluation() {
+    let exclude = rule(
+        1,
+        RuleConditions {
+            merchant_id: Some("m_042".to_string()),
+            ..RuleConditions::default()
+        },
+        RuleAction::Exclude,
+        "razorpay_real",
+        None,
+    );
+    let force = rule(5, RuleConditions::default(), RuleAction::Force, "icici_mock", None);
+    let never_reached = rule(9, RuleConditions::default(), RuleAction::Exclude, "icici_mock", None);
+
+    let outcome = evaluate(
+        &[never_reached, force.clone(), exclude.clone()],
+        &input("UPI", "m_042", "OKHDFCBANK", 1000),
+    );
+    assert_eq!(outcome.matched_rule_ids, vec![exclude.rule_id, force.rule_id]);
+    assert_eq!(outcome.primary_rule_id(&gateways()), Some(force.rule_id));
+    assert!(!outcome.allows("razorpay_real"));
+    assert!(outcome.allows("icici_mock"));
+}
+
+#[test]
+fn primary_rule_is_the_first_that_affected_an_available_gateway() {
+    let stale_exclude = rule(1, RuleConditions::default(), RuleAction::Exclude, "retired_mock", None);
+    let boost = rule(2, RuleConditions::default(), RuleAction::Boost, "hdfc_mock", Some(0.1));
+    let exclude = rule(3, RuleConditions::default(), RuleAction::Exclude, "icici_mock", None);
+
+    let outcome = evaluate(
+        &[stale_exclude.clone(), boost.clone(), exclude],
+        &input("UPI", "m_001", "OKHDFCBANK", 1000),
+    );
+    assert_eq!(outcome.matched_rule_ids[0], stale_exclude.rule_id);
+    assert_eq!(outcome.primary_rule_id(&gateways()), Some(boost.rule_id));
+    assert_eq!(outcome.primary_rule_id(&["razorpay_real".to_string()]), None);
+}
+
+#[test]
+fn experiment_rule_sets_keep_production_exclusions() {
+    let compliance = rule(
+        50,
+        RuleConditions {
+            merchant_id: Some("m_042".to_string()),
+            ..RuleConditions::default()
+        },
+        RuleAction::Exclude,
+        "razorpay_real",
+        None,
+    );
+    let production_boost = rule(1, RuleConditions::default(), RuleAction::Boost, "hdfc_mock", Some(0.2));
+    let variant_force = rule(1, RuleConditions::default(), RuleAction::Force, "razorpay_real", None);
+    let variant_boost = rule(2, RuleConditions::default(), RuleAction::Boost, "icici_mock", Some(0.1));
+    let payment = input("UPI", "m_042", "OKHDFCBANK", 1000);
+
+    let boosted = layer_production_excludes(
+        evaluate(std::slice::from_ref(&variant_boost), &payment),
+        &[compliance.clone(), production_boost],
+        &payment,
+    );
+    assert!(!boosted.allows("razorpay_real"));
+    assert!(boosted.allows("hdfc_mock"));
+    assert!(!boosted.boosts.contains_key("hdfc_mock"));
+    assert_eq!(boosted.matched_rule_ids, vec![variant_boost.rule_id, compliance.rule_id]);
+
+    let forced = layer_production_excludes(
+        evaluate(std::slice::from_ref(&variant_force), &payment),
+        std::slice::from_ref(&compliance),
+        &payment,
+    );
+    assert!(!forced.allows("razorpay_real"));
+}
+
+#[test]
+fn unusable_forced_gateway_falls_back_to_the_remaining_rules() {
+    let force = rule(1, RuleConditions::default(), RuleAction::Force, "hdfc_mock", None);
+    let boost = rule(2, RuleConditions::default(), RuleAction::Boost, "icici_mock", Some(0.1));
+    let payment = input("UPI", "m_001", "OKHDFCBANK", 1000);
+
+    let (outcome, unavailable) = resolve_force(
+        &[force.clone(), boost.clone()],
+        |rules| evaluate(rules, &payment),
+        |gateway_id| gateway_id != "hdfc_mock",
+    );
+    assert_eq!(unavailable, vec!["hdfc_mock".to_string()]);
+    assert!(outcome.forced_gateway.is_none());
+    assert!(outcome.allows("hdfc_mock"));
+    assert!(outcome.allows("razorpay_real"));
+    assert_eq!(outcome.boosts.get("icici_mock"), Some(&0.1));
+    assert_eq!(outcome.matched_rule_ids, vec![force.rule_id, boost.rule_id]);
+
+    let (kept, none) = resolve_force(
+        std::slice::from_ref(&force),
+        |rules| evaluate(rules, &payment),
+        |_| true,
+    );
+    assert!(none.is_empty());
+    assert_eq!(kept.forced_gateway.as_deref(), Some("hdfc_mock"));
+}
+
+#[test]
+fn excluded_forced_gateway_falls_back_to_the_next_force() {
+    let exclude = rule(1, RuleConditions::default(), RuleAction::Exclude, "razorpay_real", None);
+    let force = rule(2, RuleConditions::default(), RuleAction::Force, "razorpay_real", None);
+    let next_force = rule(3, RuleConditions::default(), RuleAction::Force, "icici_mock", None);
+    let payment = input("UPI", "m_001", "OKHDFCBANK", 1000);
+
+    let (outcome, unavailable) = resolve_force(
+        &[exclude.clone(), force.clone(), next_force.clone()],
+        |rules| evaluate(rules, &payment),
+        |_| true,
+    );
+    assert_eq!(unavailable, vec!["razorpay_real".to_string()]);
+    assert_eq!(outcome.forced_gateway.as_deref(), Some("icici_mock"));
+    assert!(!outcome.allows("razorpay_real"));
+    assert_eq!(
+        outcome.matched_rule_ids,
+        vec![force.rule_id, exclude.rule_id, next_force.rule_id]
+    );
+}
+
+#[test]
+fn disabled_rules_are_ignored() {
+    let mut exclude = rule(1, RuleConditions::default(), RuleAction::Exclude, "hdfc_mock", None);
+    exclude.enabled = false;
+    let outcome = evaluate(&[exclude], &input("UPI", "m_001", "UNKNOWN", 1000));
+    assert!(outcome.allows("hdfc_mock"));
+}
+
+#[test]
+fn boosts_reorder_ranking() {
+    let ranked = vec![ranked("a", 0.80), ranked("b", 0.75)];
+    let mut boosts = HashMap::new();
+    boosts.insert("b".to_string(), 0.10);
+    let out = apply_boosts(ranked, &boosts);
+    assert_eq!(out[0].gateway_id, "b");
+    assert!((out[0].score - 0.85).abs() < 1e-9);
+}
+
+#[test]
+fn validation_rejects_bad_rules() {
+    let empty = RuleConditions::default();
+    let known = gateways();
+    assert!(validate_rule(RuleAction::Boost, "hdfc_mock", None, &empty, &known).is_err());
+    assert!(validate_rule(RuleAction::Boost, "hdfc_mock", Some(1.5), &empty, &known).is_err());
+    assert!(validate_rule(RuleAction::Exclude, "hdfc_mock", Some(0.1), &empty, &known).is_err());
+    assert!(validate_rule(RuleAction::Force, "", None, &empty, &known).is_err());
+    assert_eq!(
+        validate_rule(RuleAction::Exclude, "hdfc_mok", None, &empty, &known),
+        Err("unknown gateway hdfc_mok".to_string())
+    );
+    let inverted = RuleConditions {
+        min_amount_minor: Some(100),
+        max_amount_minor: Some(10),
+        ..RuleConditions::default()
+    };
+    assert!(validate_rule(RuleAction::Force, "hdfc_mock", None, &inverted, &known).is_err());
+    assert!(validate_rule(RuleAction::Boost, "hdfc_mock", Some(-0.2), &empty, &known).is_ok());
+}
+
+fn gateways() -> Vec<String> {
+    ["hdfc_mock", "icici_mock", "razorpay_real"].iter().map(|g| g.to_string()).collect()
+}
+
+fn rule(priority: i32, conditions: RuleConditions, action: RuleAction, gateway_id: &str, boost: Option<f64>) -> RoutingRule {
+    RoutingRule {
+        rule_id: Uuid::new_v4(),
+        name: format!("{}-{}", action.as_str(), gateway_id),
+        priority,
+        enabled: true,
+        conditions,
+        action,
+        gateway_id: gateway_id.to_string(),
+        boost,
+        rule_set: DEFAULT_RULE_SET.to_string(),
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    }
+}
+
+fn input(method: &str, merchant_id: &str, bank: &str, amount_minor: i64) -> RuleInput {
+    RuleInput {
+        payment_method: method.to_string(),
+        merchant_id: merchant_id.to_string(),
+        issuing_bank: bank.to_string(),
+        amount_minor,
+        amount_bucket: payments_gateway::metrics::amount_bucket::AmountBucketer::default().bucket("INR", amount_minor),
+        card_network: None,
+        card_type: None,
+    }
+}
+
+fn ranked(gateway_id: &str, score: f64) -> RankedGateway {
+    RankedGateway {
+        gateway_id: gateway_id.to_string(),
+        score,
+        breakdown: ScoreBreakdown {
+            success_rate_score: score,
+            latency_score: score,
+            method_affinity: score,
+            bank_affinity: score,
+            amount_fit: score,
+            time_weight: score,
+            cost_score: score,
+            final_score: score,
+            metric_level: None,
+            signal_windows: Vec::new(),
+        },
+    }
+}
@@ -1,5 +1,6 @@
 use payments_gateway::gateways::GatewayConfig;
 use payments_gateway::scoring::engine::rank_gateways;
+use payments_gateway::scoring::hierarchy::MetricLevel;
 use payments_gateway::scoring::types::{GatewayCandidate, ScoreInputs, ScoreWeights};
 
 #[test]
@@ -11,6 +12,7 @@ fn scoring_prefers_better_gateway() {
         bank_affinity_weight: 0.12,
         amount_fit_weight: 0.08,
         time_weight: 0.05,
+        cost_weight: 0.0,
     };
 
     let top = GatewayCandidate {
@@ -22,6 +24,9 @@ fn scoring_prefers_better_gateway() {
             bank_affinity: 1.0,
             amount_fit: 1.0,
             time_multiplier: 1.0,
+            cost_score: 0.5,
+            metric_level: MetricLevel::GatewayMethodBank,
+            signal_windows: Vec::new(),
         },
     };
 
@@ -34,6 +39,9 @@ fn scoring_prefers_better_gateway() {
             bank_affinity: 0.5,
             amount_fit: 0.7,
             time_multiplier: 0.9,
+            cost_score: 0.5,
+            metric_level: MetricLevel::GatewayMethodBank,
+            signal_windows: Vec::new(),
         },
     };
 
@@ -51,5 +59,7 @@ fn gateway(id: &str) -> GatewayConfig {
         supported_methods: vec!["UPI".to_string()],
         timeout_ms: 1000,
         mock_behavior: None,
+        max_in_flight: None,
+        max_tps: None,
     }
 }
@@ -0,0 +1,62 @@
+use payments_gateway::scoring::profiles::{select_assignment, validate_weights, ProfileAssignment};
+use payments_gateway::scoring::types::ScoreWeights;
+use uuid::Uuid;
+
+#[test]
+fn weights_must_be_non_negative_and_sum_to_one() {
+    assert!(validate_weights(&weights(0.35, 0.25)).is_ok());
+
+    let err = validate_weights(&weights(0.40, 0.25)).unwrap_err();
+    assert!(err.contains("sum to 1.0"));
+
+    let mut negative = weights(0.60, 0.25);
+    negative.time_weight = -0.25;
+    let err = validate_weights(&negative).unwrap_err();
+    assert!(err.contains("time_weight"));
+
+    let mut nan = weights(0.35, 0.25);
+    nan.cost_weight = f64::NAN;
+    assert!(validate_weights(&nan).is_err());
+}
+
+#[test]
+fn most_specific_assignment_wins() {
+    let assignments = vec![
+        assignment(None, Some("UPI"), None, "upi_default"),
+        assignment(Some("m_007"), None, None, "latency_sensitive"),
+        assignment(None, Some("CARD"), Some("gt_10000"), "large_card"),
+        assignment(Some("m_007"), Some("CARD"), None, "m_007_cards"),
+    ];
+
+    let pick = |m: &str, method: &str, bucket: &str| {
+        select_assignment(&assignments, m, method, bucket).map(|a| a.profile_id.clone())
+    };
+
+    assert_eq!(pick("m_001", "UPI", "lt_500").as_deref(), Some("upi_default"));
+    assert_eq!(pick("m_007", "UPI", "lt_500").as_deref(), Some("latency_sensitive"));
+    assert_eq!(pick("m_007", "card", "gt_10000").as_deref(), Some("m_007_cards"));
+    assert_eq!(pick("m_001", "CARD", "gt_10000").as_deref(), Some("large_card"));
+    assert_eq!(pick("m_001", "NETBANKING", "lt_500"), None);
+}
+
+fn assignment(merchant: Option<&str>, method: Option<&str>, bucket: Option<&str>, profile: &str) -> ProfileAssignment {
+    ProfileAssignment {
+        assignment_id: Uuid::new_v4(),
+        merchant_id: merchant.map(str::to_string),
+        payment_method: method.map(str::to_string),
+        amount_bucket: bucket.map(str::to_string),
+        profile_id: profile.to_string(),
+    }
+}
+
+fn weights(success_rate_weight: f64, latency_weight: f64) -> ScoreWeights {
+    ScoreWeights {
+        success_rate_weight,
+        latency_weight,
+        method_affinity_weight: 0.15,
+        bank_affinity_weight: 0.12,
+        amount_fit_weight: 0.08,
+        time_weight: 0.05,
+        cost_weight: 0.0,
+    }
+}
@@ -0,0 +1,132 @@
+use payments_gateway::scoring::blend::{blend_windows, validate_blend, SignalBlend, WindowWeight};
+use payments_gateway::scoring::hierarchy::{MetricLevel, SmoothedMetric};
+
+#[test]
+fn single_bad_minute_is_damped() {
+    let blend = multi_window();
+    let per_window = vec![
+        (1, metric(0.2, 3000, MetricLevel::GatewayMethodBank)),
+        (5, metric(0.9, 800, MetricLevel::GatewayMethodBank)),
+        (15, metric(0.92, 750, MetricLevel::GatewayMethodBank)),
+        (60, metric(0.93, 700, MetricLevel::GatewayMethodBank)),
+    ];
+
+    let out = blend_windows(&blend, &per_window);
+    assert!(out.success_rate > 0.8, "blended {} should stay near the longer windows", out.success_rate);
+    assert_eq!(out.windows.len(), 4);
+    let total: f64 = out.windows.iter().map(|w| w.weight).sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn sustained_degradation_moves_the_blend() {
+    let blend = multi_window();
+    let healthy = blend_windows(&blend, &uniform(0.95));
+    let degraded = blend_windows(
+        &blend,
+        &[
+            (1, metric(0.4, 2000, MetricLevel::GatewayMethodBank)),
+            (5, metric(0.45, 1900, MetricLevel::GatewayMethodBank)),
+            (15, metric(0.8, 1000, MetricLevel::GatewayMethodBank)),
+            (60, metric(0.93, 700, MetricLevel::GatewayMethodBank)),
+        ],
+    );
+    assert!(healthy.success_rate - degraded.success_rate > 0.3);
+    assert!(degraded.p95_latency_ms > healthy.p95_latency_ms);
+}
+
+#[test]
+fn windows_without_data_are_renormalised_away() {
+    let blend = multi_window();
+    let out = blend_windows(
+        &blend,
+        &[
+            (1, SmoothedMetric::default()),
+            (5, metric(0.9, 800, MetricLevel::GatewayMethod)),
+            (15, SmoothedMetric::default()),
+            (60, SmoothedMetric::default()),
+        ],
+    );
+    assert!((out.success_rate - 0.9).abs() < 1e-9);
+    assert_eq!(out.p95_latency_ms, 800);
+    assert_eq!(out.level, MetricLevel::GatewayMethod);
+    assert_eq!(out.windows.iter().filter(|w| w.weight > 0.0).count(), 1);
+
+    let empty = blend_windows(&blend, &[(5, SmoothedMetric::default())]);
+    assert_eq!(empty.level, MetricLevel::GlobalPrior);
+    assert_eq!((empty.success_rate, empty.p95_latency_ms), (0.5, 1500));
+}
+
+#[test]
+fn default_blend_is_the_5m_window_only() {
+    let blend = SignalBlend::default();
+    assert_eq!(blend.windows, vec![WindowWeight { window_minutes: 5, weight: 1.0 }]);
+
+    let out = blend_windows(
+        &blend,
+        &[
+            (1, metric(0.2, 3000, MetricLevel::GatewayMethodBank)),
+            (5, metric(0.9, 800, MetricLevel::GatewayMethodBank)),
+            (15, metric(0.95, 700, MetricLevel::GatewayMethodBank)),
+        ],
+    );
+    assert!((out.success_rate - 0.9).abs() < 1e-9);
+    assert_eq!(out.p95_latency_ms, 800);
+    assert_eq!(out.windows.len(), 1);
+}
+
+#[test]
+fn ewma_weights_decay_with_window_length() {
+    let blend = SignalBlend::ewma(0.5);
+    assert!(validate_blend(&blend).is_ok());
+    let weights: Vec<f64> = blend.windows.iter().map(|w| w.weight).collect();
+    assert!(weights.windows(2).all(|pair| pair[0] > pair[1]));
+    assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn validation_rejects_bad_blends() {
+    let bad_window = SignalBlend {
+        windows: vec![WindowWeight { window_minutes: 10, weight: 1.0 }],
+    };
+    let duplicate = SignalBlend {
+        windows: vec![
+            WindowWeight { window_minutes: 5, weight: 0.5 },
+            WindowWeight { window_minutes: 5, weight: 0.5 },
+        ],
+    };
+    let zero = SignalBlend {
+        windows: vec![WindowWeight { window_minutes: 5, weight: 0.0 }],
+    };
+    assert!(validate_blend(&bad_window).is_err());
+    assert!(validate_blend(&duplicate).is_err());
+    assert!(validate_blend(&zero).is_err());
+    assert!(validate_blend(&SignalBlend { windows: vec![] }).is_err());
+    assert!(validate_blend(&SignalBlend::default()).is_ok());
+}
+
+fn multi_window() -> SignalBlend {
+    SignalBlend {
+        windows: vec![
+            WindowWeight { window_minutes: 1, weight: 0.15 },
+            WindowWeight { window_minutes: 5, weight: 0.45 },
+            WindowWeight { window_minutes: 15, weight: 0.25 },
+            WindowWeight { window_minutes: 60, weight: 0.15 },
+        ],
+    }
+}
+
+fn uniform(success_rate: f64) -> Vec<(i64, SmoothedMetric)> {
+    [1, 5, 15, 60]
+        .into_iter()
+        .map(|w| (w, metric(success_rate, 700, MetricLevel::GatewayMethodBank)))
+        .collect()
+}
+
+fn metric(success_rate: f64, p95_latency_ms: i32, level: MetricLevel) -> SmoothedMetric {
+    SmoothedMetric {
+        success_rate,
+        p95_latency_ms,
+        level,
+    }
+}
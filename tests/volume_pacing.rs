@@ -0,0 +1,143 @@
+use chrono::{TimeZone, Utc};
+use payments_gateway::volume::pacing::{
+    has_cap, is_capped, month_elapsed_fraction, pace_boost, periods, progress, usage_delta, validate_target, VolumeTarget,
+    VolumeUsage,
+};
+use payments_gateway::volume::store_redis::{VolumeLease, VolumeStoreRedis};
+
+#[test]
+fn periods_follow_ist_calendar() {
+    let late_utc = Utc.with_ymd_and_hms(2026, 10, 31, 20, 0, 0).unwrap();
+    let p = periods(late_utc);
+    assert_eq!(p.day, "2026-11-01");
+    assert_eq!(p.month, "2026-11");
+    assert_eq!(p.month_start, Utc.with_ymd_and_hms(2026, 10, 31, 18, 30, 0).unwrap());
+    assert_eq!(p.month_end, Utc.with_ymd_and_hms(2026, 11, 30, 18, 30, 0).unwrap());
+
+    let december = periods(Utc.with_ymd_and_hms(2026, 12, 15, 0, 0, 0).unwrap());
+    assert_eq!(december.month_end, Utc.with_ymd_and_hms(2026, 12, 31, 18, 30, 0).unwrap());
+}
+
+#[test]
+fn caps_exclude_when_payment_would_exceed() {
+    let t = target(None, Some(100_000), None);
+    let usage = VolumeUsage {
+        daily_minor: 90_000,
+        monthly_minor: 90_000,
+    };
+    assert!(!is_capped(&t, &usage, 10_000));
+    assert!(is_capped(&t, &usage, 10_001));
+
+    let monthly = target(None, None, Some(1_000_000));
+    let usage = VolumeUsage {
+        daily_minor: 0,
+        monthly_minor: 1_000_000,
+    };
+    assert!(is_capped(&monthly, &usage, 1));
+}
+
+#[test]
+fn behind_pace_gateways_get_boost() {
+    let mid_month = Utc.with_ymd_and_hms(2026, 11, 15, 18, 30, 0).unwrap();
+    assert!((month_elapsed_fraction(mid_month) - 0.5).abs() < 1e-9);
+
+    let t = target(Some(10_000_000), None, None);
+    let behind = VolumeUsage {
+        daily_minor: 0,
+        monthly_minor: 2_500_000,
+    };
+    assert!((pace_boost(&t, &behind, mid_month) - 0.05).abs() < 1e-9);
+
+    let ahead = VolumeUsage {
+        daily_minor: 0,
+        monthly_minor: 6_000_000,
+    };
+    assert_eq!(pace_boost(&t, &ahead, mid_month), 0.0);
+    assert_eq!(pace_boost(&target(None, None, None), &behind, mid_month), 0.0);
+}
+
+#[test]
+fn progress_reports_commitment_and_caps() {
+    let mid_month = Utc.with_ymd_and_hms(2026, 11, 15, 18, 30, 0).unwrap();
+    let t = target(Some(10_000_000), Some(500_000), None);
+    let usage = VolumeUsage {
+        daily_minor: 500_000,
+        monthly_minor: 5_000_000,
+    };
+    let p = progress(&t, &usage, mid_month);
+    assert_eq!(p.commit_pct, Some(0.5));
+    assert_eq!(p.pace_ratio, Some(1.0));
+    assert_eq!(p.daily_remaining_minor, Some(0));
+    assert!(p.capped);
+}
+
+#[test]
+fn reconciliation_applies_a_delta_and_only_capped_targets_reserve() {
+    let settled = VolumeUsage {
+        daily_minor: 40_000,
+        monthly_minor: 900_000,
+    };
+    let observed = VolumeUsage {
+        daily_minor: 55_000,
+        monthly_minor: 880_000,
+    };
+    assert_eq!(
+        usage_delta(settled, observed),
+        VolumeUsage {
+            daily_minor: -15_000,
+            monthly_minor: 20_000,
+        }
+    );
+    assert_eq!(usage_delta(settled, settled), VolumeUsage::default());
+
+    assert!(has_cap(&target(None, Some(1), None)));
+    assert!(has_cap(&target(None, None, Some(1))));
+    assert!(!has_cap(&target(Some(1_000_000), None, None)));
+}
+
+#[test]
+fn usages_parse_day_and_month_counters() {
+    let gateways = vec!["g1".to_string(), "g2".to_string()];
+    let now = Utc.with_ymd_and_hms(2026, 3, 15, 12, 0, 0).unwrap();
+    assert_eq!(VolumeStoreRedis::usage_keys(&gateways, now).len(), 4);
+
+    let values = vec![Some("1500".to_string()), Some("90000".to_string()), None, None];
+    let usages = VolumeStoreRedis::usages_from_values(&gateways, &values);
+    assert_eq!(
+        usages["g1"],
+        VolumeUsage {
+            daily_minor: 1_500,
+            monthly_minor: 90_000,
+        }
+    );
+    assert_eq!(usages["g2"], VolumeUsage::default());
+}
+
+#[test]
+fn in_flight_lease_member_carries_its_amount() {
+    let first = VolumeLease::new("hdfc_mock", 25_000);
+    let second = VolumeLease::new("hdfc_mock", 25_000);
+    assert!(first.member().ends_with(":25000"));
+    assert_eq!(first.member().split(':').count(), 2);
+    assert_ne!(first.member(), second.member());
+}
+
+#[test]
+fn validation_rejects_negative_and_out_of_range() {
+    assert!(validate_target(&target(Some(-1), None, None)).is_err());
+    let mut t = target(None, None, None);
+    t.pace_boost = 1.5;
+    assert!(validate_target(&t).is_err());
+    assert!(validate_target(&target(Some(1), Some(1), Some(1))).is_ok());
+}
+
+fn target(commit: Option<i64>, daily_cap: Option<i64>, monthly_cap: Option<i64>) -> VolumeTarget {
+    VolumeTarget {
+        gateway_id: "hdfc_mock".to_string(),
+        monthly_commit_minor: commit,
+        daily_cap_minor: daily_cap,
+        monthly_cap_minor: monthly_cap,
+        pace_boost: 0.1,
+        enabled: true,
+    }
+}